@@ -4,8 +4,7 @@ use crate::Renderer;
 use baseview::WindowScalePolicy;
 use femtovg::Canvas;
 use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
-use tuix_core::events::{Event, Propagation};
-use tuix_core::state::hierarchy::IntoHierarchyIterator;
+use tuix_core::events::{Event, PointerInfo, Propagation};
 use tuix_core::state::mouse::{MouseButton, MouseButtonState};
 use tuix_core::state::Fonts;
 use tuix_core::window::WindowWidget;
@@ -166,6 +165,13 @@ impl ApplicationRunner {
     */
 
     pub fn on_frame_update(&mut self) {
+        // Sends any long press events which are due, shows any pending tooltip and
+        // updates the widgets bound to parameters changed by the audio thread and the level meters
+        self.state.update_gestures();
+        self.state.update_tooltips();
+        self.state.update_params();
+        self.state.update_meters();
+
         if self.state.apply_animations() {
             self.state.insert_event(
                 Event::new(WindowEvent::Relayout)
@@ -187,6 +193,7 @@ impl ApplicationRunner {
         if self.should_redraw {
             self.event_manager
                 .draw(&mut self.state, &self.hierarchy, &mut self.canvas);
+            self.state.update_accessibility();
             self.should_redraw = false;
             true
         } else {
@@ -343,6 +350,8 @@ impl ApplicationRunner {
                         );
                     }
 
+                    self.state.pointer_move(0, cursorx, cursory, None);
+
                     self.pos = (cursorx, cursory);
                 }
                 baseview::MouseEvent::ButtonPressed(button) => {
@@ -408,6 +417,12 @@ impl ApplicationRunner {
 
                         _ => {}
                     }
+
+                    self.state.pointer_down(PointerInfo::mouse(
+                        b,
+                        self.state.mouse.cursorx,
+                        self.state.mouse.cursory,
+                    ));
                 }
                 baseview::MouseEvent::ButtonReleased(button) => {
                     let b = match button {
@@ -468,6 +483,11 @@ impl ApplicationRunner {
 
                         _ => {}
                     }
+
+                    if self.state.gestures.pressed_button(0) == Some(b) {
+                        let (x, y) = (self.state.mouse.cursorx, self.state.mouse.cursory);
+                        self.state.pointer_up(0, x, y);
+                    }
                 }
                 baseview::MouseEvent::WheelScrolled(scroll_delta) => {
                     let (lines_x, lines_y) = match scroll_delta {
@@ -526,56 +546,11 @@ impl ApplicationRunner {
                 }
 
                 if event.code == Code::Tab && s == MouseButtonState::Pressed {
-                    let next_focus = self
-                        .state
-                        .style
-                        .focus_order
-                        .get(self.state.focused)
-                        .cloned()
-                        .unwrap_or_default()
-                        .next;
-                    let prev_focus = self
-                        .state
-                        .style
-                        .focus_order
-                        .get(self.state.focused)
-                        .cloned()
-                        .unwrap_or_default()
-                        .prev;
-
                     if self.state.modifiers.shift {
-                        if prev_focus != Entity::null() {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused = prev_focus;
-                            self.state.focused.set_focus(&mut self.state, true);
-                        } else {
-                            // TODO impliment reverse iterator for hierarchy
-                            // state.focused = match state.focused.into_iter(&state.hierarchy).next() {
-                            //     Some(val) => val,
-                            //     None => Entity::root(),
-                            // };
-                        }
+                        self.state.focus_prev();
                     } else {
-                        if next_focus != Entity::null() {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused = next_focus;
-                            self.state.focused.set_focus(&mut self.state, true);
-                        } else {
-                            self.state.focused.set_focus(&mut self.state, false);
-                            self.state.focused =
-                                match self.state.focused.into_iter(&self.hierarchy).next() {
-                                    Some(val) => val,
-                                    None => Entity::root(),
-                                };
-                            self.state.focused.set_focus(&mut self.state, true);
-                        }
+                        self.state.focus_next();
                     }
-
-                    self.state.insert_event(
-                        Event::new(WindowEvent::Restyle)
-                            .target(Entity::root())
-                            .origin(Entity::root()),
-                    );
                 }
 
                 match s {
@@ -630,12 +605,25 @@ impl ApplicationRunner {
             }
             baseview::Event::Window(event) => match event {
                 baseview::WindowEvent::Focused => {
+                    self.state.insert_event(
+                        Event::new(WindowEvent::WindowFocus(true))
+                            .target(Entity::root())
+                            .propagate(Propagation::All),
+                    );
+
                     self.state.insert_event(
                         Event::new(WindowEvent::Restyle)
                             .target(Entity::root())
                             .origin(Entity::root()),
                     );
                 }
+                baseview::WindowEvent::Unfocused => {
+                    self.state.insert_event(
+                        Event::new(WindowEvent::WindowFocus(false))
+                            .target(Entity::root())
+                            .propagate(Propagation::All),
+                    );
+                }
                 baseview::WindowEvent::Resized(window_info) => {
                     self.scale_factor = match self.scale_policy {
                         WindowScalePolicy::ScaleFactor(scale) => scale,
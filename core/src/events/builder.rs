@@ -1,4 +1,4 @@
-use crate::{Entity, EventHandler, State};
+use crate::{Entity, EventHandler, Role, State};
 
 use crate::state::style::*;
 
@@ -107,6 +107,15 @@ impl<'a> Builder<'a> {
         self
     }
 
+    pub fn set_tooltip_content<F>(mut self, content: F) -> Self
+    where
+        F: 'static + Fn(&mut State, Entity) + Send,
+    {
+        self.state.set_tooltip_content(self.entity, content);
+
+        self
+    }
+
     // Display
 
     pub fn set_display(mut self, val: Display) -> Self {
@@ -490,6 +499,28 @@ impl<'a> Builder<'a> {
         self
     }
 
+    // Sets the position in the keyboard focus order. A value of 0 follows the hierarchy order,
+    // positive values are visited first and negative values can be focused but are skipped by Tab.
+    pub fn set_tab_index(mut self, val: i32) -> Self {
+        self.state.style.tab_index.insert(self.entity, val);
+
+        self
+    }
+
+    // Sets the role reported to assistive technology
+    pub fn set_role(mut self, val: Role) -> Self {
+        self.state.accessibility.role.insert(self.entity, val);
+
+        self
+    }
+
+    // Sets the name reported to assistive technology, overriding the text of the widget
+    pub fn set_accessible_name(mut self, val: &str) -> Self {
+        self.state.accessibility.name.insert(self.entity, val.to_string());
+
+        self
+    }
+
     pub fn set_rotate(mut self, rotate: f32) -> Self {
         self.state.style.rotate.insert(self.entity, rotate);
 
@@ -1,10 +1,10 @@
 use crate::{
-    BuildHandler, Builder, CursorIcon, Entity, Event, EventHandler, Hierarchy, HierarchyTree,
-    IntoBranchIterator, IntoHierarchyIterator, IntoParentIterator, PropSet, Propagation, State,
-    WindowEvent,
+    BuildHandler, Builder, Code, CursorIcon, Entity, Event, EventHandler, Hierarchy, HierarchyTree,
+    IntoBranchIterator, IntoHierarchyIterator, IntoParentIterator, MouseButton, PropSet, Propagation,
+    Role, ShortcutMatch, State, WindowEvent,
 };
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{HashMap, HashSet, VecDeque},
     println,
 };
 
@@ -23,6 +23,9 @@ pub struct EventManager {
     // Queue of events to be processed
     pub event_queue: Vec<Event>,
 
+    // Keys whose key down was consumed, so their key up is consumed too
+    consumed_keys: HashSet<Code>,
+
     prev_width: f32,
     prev_height: f32,
     prev_dpi_factor: f64,
@@ -34,6 +37,8 @@ impl EventManager {
             event_handlers: FnvHashMap::default(),
             event_queue: Vec::new(),
 
+            consumed_keys: HashSet::new(),
+
             prev_width: 0.0,
             prev_height: 0.0,
             prev_dpi_factor: 1.0,
@@ -89,6 +94,77 @@ impl EventManager {
                         needs_redraw = true;
                     }
 
+                    // Pressing outside of an open popup closes it, and right-clicking opens a context menu
+                    WindowEvent::MouseDown(button) => {
+                        let button = *button;
+
+                        state.suppress_tooltip();
+
+                        if !state.popups.is_empty() {
+                            state.dismiss_popups(state.hovered);
+                        }
+
+                        // Presses forwarded by widgets have an origin and don't open the menu again
+                        if button == MouseButton::Right && event.origin == Entity::null() {
+                            state.open_context_menu_at_cursor(state.hovered);
+                        }
+                    }
+
+                    WindowEvent::MouseScroll(_, _) => {
+                        state.suppress_tooltip();
+                    }
+
+                    // Key presses which match a shortcut are replaced by the bound event
+                    WindowEvent::KeyDown(code, _) => {
+                        let code = *code;
+
+                        if code == Code::ContextMenu || (code == Code::F10 && state.modifiers.shift) {
+                            if state.open_context_menu_from_keyboard() {
+                                self.consumed_keys.insert(code);
+                                continue 'events;
+                            }
+                        }
+
+                        // Escape closes the topmost popup before anything else sees it
+                        if code == Code::Escape {
+                            if let Some(popup) = state.popups.top() {
+                                state.close_popup(popup);
+                                self.consumed_keys.insert(code);
+                                continue 'events;
+                            }
+                        }
+
+                        // Keys typed without Ctrl, Alt or Logo go to a text input which is being edited
+                        let modified = state.modifiers.ctrl || state.modifiers.alt || state.modifiers.logo;
+                        let editing = state.accessibility.role.get(state.focused) == Some(&Role::TextInput)
+                            && state.focused.is_active(state);
+
+                        if modified || !editing {
+                            match state.shortcuts.key_down(&hierarchy, event.target, state.modifiers, code) {
+                                ShortcutMatch::Matched(mut shortcut_event) => {
+                                    shortcut_event.origin = event.target;
+                                    state.insert_event(shortcut_event);
+                                    self.consumed_keys.insert(code);
+                                    continue 'events;
+                                }
+
+                                ShortcutMatch::Pending => {
+                                    self.consumed_keys.insert(code);
+                                    continue 'events;
+                                }
+
+                                ShortcutMatch::None => {}
+                            }
+                        }
+                    }
+
+                    // The key up of a consumed key down is consumed with it
+                    WindowEvent::KeyUp(code, _) => {
+                        if self.consumed_keys.remove(code) {
+                            continue 'events;
+                        }
+                    }
+
                     /*
                     WindowEvent::SetCursor(cursor_icon) => match cursor_icon {
                         CursorIcon::Arrow => {
@@ -249,6 +325,13 @@ impl EventManager {
             .unwrap_or_default()
             .into();
 
+        // Delete the images of removed widgets
+        if let Ok(mut released_images) = state.released_images.lock() {
+            for image_id in released_images.drain(..) {
+                canvas.delete_image(image_id);
+            }
+        }
+
         // Clear the canvas
         canvas.clear_rect(0, 0, width as u32, height as u32, background_color);
 
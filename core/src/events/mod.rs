@@ -18,5 +18,8 @@ pub use builder::*;
 pub mod window_event;
 pub use window_event::*;
 
+pub mod pointer_event;
+pub use pointer_event::*;
+
 pub mod window_description;
 pub use window_description::*;
@@ -0,0 +1,73 @@
+use crate::state::mouse::MouseButton;
+
+// The kind of device which produced a pointer event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerKind {
+    Mouse,
+    Touch,
+    Pen,
+}
+
+// Describes the pointer which produced a pointer event
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerInfo {
+    // Identifies the pointer. The mouse is always 0, touches are numbered from 1.
+    pub id: u64,
+    pub kind: PointerKind,
+    // The button held down by the pointer. Touches and pens report MouseButton::Left.
+    pub button: MouseButton,
+    // Normalized pressure between 0.0 and 1.0, if the backend provides it
+    pub pressure: Option<f32>,
+    // Position of the pointer in window coordinates
+    pub x: f32,
+    pub y: f32,
+}
+
+impl PointerInfo {
+    pub fn mouse(button: MouseButton, x: f32, y: f32) -> Self {
+        PointerInfo {
+            id: 0,
+            kind: PointerKind::Mouse,
+            button,
+            pressure: None,
+            x,
+            y,
+        }
+    }
+
+    pub fn touch(id: u64, x: f32, y: f32, pressure: Option<f32>) -> Self {
+        PointerInfo {
+            id: id + 1,
+            kind: PointerKind::Touch,
+            button: MouseButton::Left,
+            pressure,
+            x,
+            y,
+        }
+    }
+}
+
+// Gesture events produced from raw pointer input by the gesture layer in State
+//
+// Pointer events are sent to the entity which was pressed and propagate up the hierarchy,
+// so containers can handle gestures that start on their children.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PointerEvent {
+    // Emitted when a pointer is pressed and released without dragging.
+    // The count is 2 for a double click and 3 for a triple click.
+    Click { pointer: PointerInfo, count: u32 },
+    // Emitted when a pressed pointer first moves further than the drag threshold.
+    // The position is where the pointer was originally pressed.
+    DragStart { pointer: PointerInfo, x: f32, y: f32 },
+    // Emitted when a dragging pointer moves, with the change in position since the last DragMove
+    // and the total change since the pointer was pressed
+    DragMove { pointer: PointerInfo, dx: f32, dy: f32, total_dx: f32, total_dy: f32 },
+    // Emitted when a dragging pointer is released, with the total change in position
+    DragEnd { pointer: PointerInfo, total_dx: f32, total_dy: f32 },
+    // Emitted when a touch or pen is held down without moving for the long press delay
+    LongPress { pointer: PointerInfo },
+    // Emitted when two touches move together, with the change in position of their center
+    Pan { dx: f32, dy: f32 },
+    // Emitted when two touches move apart or together. Scale is relative to the previous Pinch event.
+    Pinch { scale: f32, x: f32, y: f32 },
+}
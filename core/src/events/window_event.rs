@@ -53,6 +53,12 @@ pub enum WindowEvent {
     MouseCaptureEvent,
     // Emitted when mouse events have been released
     MouseCaptureOutEvent,
+    // Emitted when an entity receives keyboard focus
+    FocusIn,
+    // Emitted when an entity loses keyboard focus
+    FocusOut,
+    // Emitted to every entity when the window gains or loses focus, with whether the window is focused
+    WindowFocus(bool),
     // Emitted when an entity changes position or size (TODO: check if this includes margins + borders)
     GeometryChanged(GeometryChanged),
     // Requests a redraw of the window contents
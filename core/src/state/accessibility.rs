@@ -0,0 +1,544 @@
+use crate::state::storage::dense_storage::DenseStorage;
+use crate::{Entity, IntoChildIterator, PropSet, State, Visibility};
+
+use std::fmt;
+
+/// The semantic role of a widget as reported to assistive technology
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Role {
+    Generic,
+    Window,
+    Label,
+    Image,
+    Group,
+    Button,
+    CheckBox,
+    Switch,
+    RadioButton,
+    RadioGroup,
+    TextInput,
+    Slider,
+    SpinButton,
+    ProgressBar,
+    Meter,
+    ComboBox,
+    ListBox,
+    ListItem,
+    Menu,
+    MenuBar,
+    MenuItem,
+    MenuItemCheckBox,
+    MenuItemRadio,
+    Separator,
+    Tab,
+    TabList,
+    TabPanel,
+    Dialog,
+    Tooltip,
+    ScrollArea,
+    ScrollBar,
+    Tree,
+    TreeItem,
+    Table,
+    Row,
+    Cell,
+    ColumnHeader,
+}
+
+impl Default for Role {
+    fn default() -> Self {
+        Role::Generic
+    }
+}
+
+impl Role {
+    /// Returns the name of the role used in snapshots
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Generic => "generic",
+            Role::Window => "window",
+            Role::Label => "label",
+            Role::Image => "image",
+            Role::Group => "group",
+            Role::Button => "button",
+            Role::CheckBox => "checkbox",
+            Role::Switch => "switch",
+            Role::RadioButton => "radio",
+            Role::RadioGroup => "radiogroup",
+            Role::TextInput => "textbox",
+            Role::Slider => "slider",
+            Role::SpinButton => "spinbutton",
+            Role::ProgressBar => "progressbar",
+            Role::Meter => "meter",
+            Role::ComboBox => "combobox",
+            Role::ListBox => "listbox",
+            Role::ListItem => "listitem",
+            Role::Menu => "menu",
+            Role::MenuBar => "menubar",
+            Role::MenuItem => "menuitem",
+            Role::MenuItemCheckBox => "menuitemcheckbox",
+            Role::MenuItemRadio => "menuitemradio",
+            Role::Separator => "separator",
+            Role::Tab => "tab",
+            Role::TabList => "tablist",
+            Role::TabPanel => "tabpanel",
+            Role::Dialog => "dialog",
+            Role::Tooltip => "tooltip",
+            Role::ScrollArea => "scrollarea",
+            Role::ScrollBar => "scrollbar",
+            Role::Tree => "tree",
+            Role::TreeItem => "treeitem",
+            Role::Table => "table",
+            Role::Row => "row",
+            Role::Cell => "cell",
+            Role::ColumnHeader => "columnheader",
+        }
+    }
+
+    // Whether the text of the widget (and its descendants) describes it.
+    // Checkboxes and radio buttons use their text for icons so need an explicit name.
+    fn named_from_text(&self) -> bool {
+        match self {
+            Role::Generic
+            | Role::Label
+            | Role::Button
+            | Role::ListItem
+            | Role::Menu
+            | Role::MenuItem
+            | Role::Tab
+            | Role::Dialog
+            | Role::Tooltip
+            | Role::TreeItem
+            | Role::Cell
+            | Role::ColumnHeader => true,
+
+            _ => false,
+        }
+    }
+
+    // Roles which report the `checked` pseudo-class as checked
+    fn is_checkable(&self) -> bool {
+        match self {
+            Role::CheckBox
+            | Role::Switch
+            | Role::RadioButton
+            | Role::MenuItemCheckBox
+            | Role::MenuItemRadio => true,
+            _ => false,
+        }
+    }
+
+    // Roles which report the `checked` pseudo-class as selected
+    fn is_selectable(&self) -> bool {
+        match self {
+            Role::Tab | Role::ListItem | Role::TreeItem | Role::Row => true,
+            _ => false,
+        }
+    }
+
+    // Roles which report the `checked` pseudo-class as expanded
+    fn is_expandable(&self) -> bool {
+        match self {
+            Role::Menu | Role::ComboBox => true,
+            _ => false,
+        }
+    }
+}
+
+/// The state flags of an accessibility node
+///
+/// Flags which don't apply to the role of the node are `None`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccessState {
+    pub checked: Option<bool>,
+    pub selected: Option<bool>,
+    pub expanded: Option<bool>,
+    pub disabled: bool,
+    pub focused: bool,
+    pub invalid: bool,
+}
+
+/// A node of the accessibility tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessNode {
+    pub entity: Entity,
+    pub role: Role,
+    pub name: String,
+    pub value: Option<String>,
+    pub state: AccessState,
+    // Bounding box of the widget in window coordinates (x, y, width, height)
+    pub bounds: (f32, f32, f32, f32),
+    pub children: Vec<AccessNode>,
+}
+
+impl AccessNode {
+    /// Returns the node of the tree belonging to the specified entity
+    pub fn find(&self, entity: Entity) -> Option<&AccessNode> {
+        if self.entity == entity {
+            return Some(self);
+        }
+
+        self.children.iter().find_map(|child| child.find(entity))
+    }
+
+    /// Serializes the tree to a JSON string
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        json.push_str(&format!(
+            "{{\"id\":{},\"role\":\"{}\",\"name\":{}",
+            self.entity.index_unchecked(),
+            self.role.name(),
+            json_string(&self.name)
+        ));
+
+        if let Some(value) = &self.value {
+            json.push_str(&format!(",\"value\":{}", json_string(value)));
+        }
+
+        let flags = [
+            ("checked", self.state.checked),
+            ("selected", self.state.selected),
+            ("expanded", self.state.expanded),
+            ("disabled", Some(self.state.disabled)),
+            ("focused", Some(self.state.focused)),
+            ("invalid", Some(self.state.invalid)),
+        ];
+
+        for (flag, value) in flags.iter() {
+            if let Some(value) = value {
+                json.push_str(&format!(",\"{}\":{}", flag, value));
+            }
+        }
+
+        json.push_str(&format!(
+            ",\"bounds\":[{},{},{},{}],\"children\":[",
+            json_number(self.bounds.0),
+            json_number(self.bounds.1),
+            json_number(self.bounds.2),
+            json_number(self.bounds.3)
+        ));
+
+        for (index, child) in self.children.iter().enumerate() {
+            if index > 0 {
+                json.push(',');
+            }
+
+            child.write_json(json);
+        }
+
+        json.push_str("]}");
+    }
+
+    fn write_text(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", self.role.name(), indent = depth * 2)?;
+
+        if !self.name.is_empty() {
+            write!(f, " {:?}", self.name)?;
+        }
+
+        if let Some(value) = &self.value {
+            write!(f, " = {:?}", value)?;
+        }
+
+        let mut flags = Vec::new();
+
+        match self.state.checked {
+            Some(true) => flags.push("checked"),
+            Some(false) => flags.push("unchecked"),
+            None => {}
+        }
+
+        if self.state.selected == Some(true) {
+            flags.push("selected");
+        }
+
+        match self.state.expanded {
+            Some(true) => flags.push("expanded"),
+            Some(false) => flags.push("collapsed"),
+            None => {}
+        }
+
+        if self.state.disabled {
+            flags.push("disabled");
+        }
+
+        if self.state.focused {
+            flags.push("focused");
+        }
+
+        if self.state.invalid {
+            flags.push("invalid");
+        }
+
+        if !flags.is_empty() {
+            write!(f, " [{}]", flags.join(", "))?;
+        }
+
+        writeln!(f)?;
+
+        for child in self.children.iter() {
+            child.write_text(f, depth + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Displays the tree as indented text, one node per line, e.g. `button "OK" [focused]`
+///
+/// Bounds are omitted so the output is stable across layout changes, which makes it
+/// suitable for snapshot tests.
+impl fmt::Display for AccessNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_text(f, 0)
+    }
+}
+
+/// Receives the accessibility tree so that it can be forwarded to a platform accessibility API
+pub trait AccessibilityAdapter: Send {
+    /// Called with the full tree whenever it changes
+    fn update(&mut self, tree: &AccessNode);
+
+    /// Called when keyboard focus moves to a different entity
+    fn focus_changed(&mut self, _focused: Entity) {}
+}
+
+/// Accessibility properties of widgets
+///
+/// Widgets set their role when built. The name defaults to the text of the widget and the
+/// state is derived from the pseudo-classes, so most widgets only need to provide a value.
+pub struct Accessibility {
+    pub role: DenseStorage<Role>,
+    pub name: DenseStorage<String>,
+    pub value: DenseStorage<String>,
+    // Expanded state of widgets which don't report it with the checked pseudo-class, such as tree items.
+    // None for widgets which can't be expanded.
+    pub expanded: DenseStorage<Option<bool>>,
+
+    adapter: Option<Box<dyn AccessibilityAdapter>>,
+    snapshot: Option<AccessNode>,
+    focused: Entity,
+}
+
+impl Accessibility {
+    pub fn new() -> Self {
+        Accessibility {
+            role: DenseStorage::new(),
+            name: DenseStorage::new(),
+            value: DenseStorage::new(),
+            expanded: DenseStorage::new(),
+
+            adapter: None,
+            snapshot: None,
+            focused: Entity::null(),
+        }
+    }
+
+    /// Sets the adapter which receives updates to the accessibility tree
+    pub fn set_adapter<A: AccessibilityAdapter + 'static>(&mut self, adapter: A) {
+        self.adapter = Some(Box::new(adapter));
+        self.snapshot = None;
+        self.focused = Entity::null();
+    }
+
+    pub fn has_adapter(&self) -> bool {
+        self.adapter.is_some()
+    }
+}
+
+impl State {
+    /// Builds a snapshot of the accessibility tree
+    ///
+    /// Hidden widgets are excluded, and widgets with no role or text are flattened into their parent.
+    pub fn accessibility_tree(&mut self) -> AccessNode {
+        let root = Entity::root();
+        let children = self.access_children(root);
+
+        AccessNode {
+            entity: root,
+            role: Role::Window,
+            name: self.accessibility.name.get(root).cloned().unwrap_or_default(),
+            value: None,
+            state: AccessState {
+                focused: self.focused == root,
+                ..Default::default()
+            },
+            bounds: self.access_bounds(root),
+            children,
+        }
+    }
+
+    /// Sends the accessibility tree to the adapter if it has changed since the last update
+    ///
+    /// Called by the backends after drawing. Does nothing if no adapter has been set.
+    pub fn update_accessibility(&mut self) {
+        if !self.accessibility.has_adapter() {
+            return;
+        }
+
+        let tree = self.accessibility_tree();
+        let focused = self.focused;
+
+        let accessibility = &mut self.accessibility;
+
+        if let Some(adapter) = accessibility.adapter.as_mut() {
+            if accessibility.snapshot.as_ref() != Some(&tree) {
+                adapter.update(&tree);
+                accessibility.snapshot = Some(tree);
+            }
+
+            if accessibility.focused != focused {
+                adapter.focus_changed(focused);
+                accessibility.focused = focused;
+            }
+        }
+    }
+
+    fn access_children(&mut self, entity: Entity) -> Vec<AccessNode> {
+        let children = entity.child_iter(&self.hierarchy).collect::<Vec<_>>();
+
+        let mut nodes = Vec::new();
+
+        for child in children {
+            nodes.extend(self.access_node(child));
+        }
+
+        nodes
+    }
+
+    // Returns the node for an entity, or the nodes of its children if the entity is flattened
+    fn access_node(&mut self, entity: Entity) -> Vec<AccessNode> {
+        if self.data.get_visibility(entity) == Visibility::Invisible
+            || self.data.get_opacity(entity) == 0.0
+        {
+            return Vec::new();
+        }
+
+        let children = self.access_children(entity);
+
+        let explicit_role = self.accessibility.role.get(entity).cloned();
+        let explicit_name = self.accessibility.name.get(entity).cloned();
+        let text = self
+            .style
+            .text
+            .get(entity)
+            .map(|text| text.text.clone())
+            .unwrap_or_default();
+
+        // Widgets without a role are only reported if they display some text
+        let role = match explicit_role {
+            Some(role) => role,
+            None => {
+                if explicit_name.is_none() && text.is_empty() {
+                    return children;
+                }
+
+                Role::Label
+            }
+        };
+
+        let name = match explicit_name {
+            Some(name) => name,
+            None => {
+                if role.named_from_text() {
+                    if text.is_empty() {
+                        collect_names(&children)
+                    } else {
+                        text.clone()
+                    }
+                } else {
+                    String::new()
+                }
+            }
+        };
+
+        let value = match self.accessibility.value.get(entity) {
+            Some(value) => Some(value.clone()),
+            None => {
+                if role == Role::TextInput {
+                    Some(text)
+                } else {
+                    None
+                }
+            }
+        };
+
+        let checked = entity.is_checked(self);
+
+        let state = AccessState {
+            checked: if role.is_checkable() { Some(checked) } else { None },
+            selected: if role.is_selectable() { Some(checked) } else { None },
+            expanded: match self.accessibility.expanded.get(entity) {
+                Some(expanded) => *expanded,
+                None if role.is_expandable() => Some(checked),
+                None => None,
+            },
+            disabled: entity.is_disabled(self),
+            focused: self.focused == entity,
+            invalid: entity.is_invalid(self),
+        };
+
+        vec![AccessNode {
+            entity,
+            role,
+            name,
+            value,
+            state,
+            bounds: self.access_bounds(entity),
+            children,
+        }]
+    }
+
+    fn access_bounds(&self, entity: Entity) -> (f32, f32, f32, f32) {
+        (
+            self.data.get_posx(entity),
+            self.data.get_posy(entity),
+            self.data.get_width(entity),
+            self.data.get_height(entity),
+        )
+    }
+}
+
+// Joins the names of label nodes, used to name a widget from its child labels
+fn collect_names(nodes: &[AccessNode]) -> String {
+    nodes
+        .iter()
+        .filter(|node| node.role == Role::Label && !node.name.is_empty())
+        .map(|node| node.name.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+// Formats a number for JSON, which has no representation for NaN or infinity
+fn json_number(value: f32) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        String::from("null")
+    }
+}
@@ -0,0 +1,130 @@
+use crate::widgets::{first_item, MenuDescription, PopupMenu};
+use crate::{BuildHandler, Entity, IntoParentIterator, PopupAnchor, PopupPlacement, State};
+
+use fnv::FnvHashMap;
+
+/// The context menus attached to entities
+///
+/// A context menu opens at the cursor when its entity, or a descendant without a menu of its own,
+/// is right-clicked. It also opens below the focused entity when the Menu key or Shift+F10 is
+/// pressed, in which case the first item is focused.
+pub struct ContextMenus {
+    menus: FnvHashMap<Entity, MenuDescription>,
+    // The menus which have been opened, keyed by the entity they belong to, which are kept to be opened again
+    built: FnvHashMap<Entity, Entity>,
+}
+
+impl ContextMenus {
+    pub fn new() -> Self {
+        ContextMenus {
+            menus: FnvHashMap::default(),
+            built: FnvHashMap::default(),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.menus.remove(&entity);
+        self.built.remove(&entity);
+        self.built.retain(|_, menu| *menu != entity);
+    }
+
+    // Returns the built menus of the given entities, which are removed along with them
+    pub(crate) fn built_menus(&self, entities: &[Entity]) -> Vec<Entity> {
+        entities
+            .iter()
+            .filter_map(|entity| self.built.get(entity).cloned())
+            .collect()
+    }
+}
+
+impl State {
+    /// Attaches a context menu to an entity, items without an event target send their event to the entity
+    pub fn set_context_menu(&mut self, entity: Entity, menu: MenuDescription) {
+        self.context_menus.menus.insert(entity, menu);
+        self.remove_built_context_menu(entity);
+    }
+
+    /// Removes the context menu attached to an entity
+    pub fn remove_context_menu(&mut self, entity: Entity) {
+        self.context_menus.menus.remove(&entity);
+        self.remove_built_context_menu(entity);
+    }
+
+    /// Opens the context menu of an entity and returns the menu
+    ///
+    /// The menu is built the first time it opens and kept to be opened again.
+    pub fn open_context_menu(&mut self, entity: Entity, anchor: PopupAnchor) -> Option<Entity> {
+        let description = self.context_menus.menus.get(&entity)?.clone();
+
+        self.close_all_popups();
+
+        let menu = match self.context_menus.built.get(&entity).cloned() {
+            Some(menu) => menu,
+            None => {
+                let menu = PopupMenu::new(description)
+                    .with_owner(entity)
+                    .build(self, Entity::root(), |builder| builder);
+
+                self.context_menus.built.insert(entity, menu);
+
+                menu
+            }
+        };
+
+        self.open_popup(menu, anchor, PopupPlacement::Below);
+
+        self.set_focus(menu);
+
+        Some(menu)
+    }
+
+    // Opens the context menu for a right-click on an entity
+    pub(crate) fn open_context_menu_at_cursor(&mut self, entity: Entity) {
+        if let Some(owner) = self.context_menu_owner(entity) {
+            let anchor = PopupAnchor::Point(self.mouse.cursorx, self.mouse.cursory);
+            self.open_context_menu(owner, anchor);
+        }
+    }
+
+    // Opens the context menu for the focused entity from the keyboard. Returns true if a menu was opened.
+    pub(crate) fn open_context_menu_from_keyboard(&mut self) -> bool {
+        if let Some(owner) = self.context_menu_owner(self.focused) {
+            if let Some(menu) = self.open_context_menu(owner, PopupAnchor::Entity(owner)) {
+                if let Some(first) = first_item(self, menu) {
+                    self.set_focus(first);
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    // Stores the checked state of a checkable item so that it is kept the next time the menu opens
+    pub(crate) fn set_context_menu_checked(&mut self, entity: Entity, path: &[usize], checked: bool) {
+        if let Some(item) = self
+            .context_menus
+            .menus
+            .get_mut(&entity)
+            .and_then(|menu| menu.item_mut(path))
+        {
+            item.checked = Some(checked);
+        }
+    }
+
+    // Removes the built menu of an entity so that it's rebuilt from the new description when it next opens
+    fn remove_built_context_menu(&mut self, entity: Entity) {
+        if let Some(menu) = self.context_menus.built.remove(&entity) {
+            self.close_popup(menu);
+            self.remove(menu);
+        }
+    }
+
+    // Returns the nearest ancestor of an entity, including itself, which has a context menu
+    fn context_menu_owner(&self, entity: Entity) -> Option<Entity> {
+        entity
+            .parent_iter(&self.hierarchy)
+            .find(|ancestor| self.context_menus.menus.contains_key(ancestor))
+    }
+}
@@ -0,0 +1,205 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Stored in the playhead when there is no playhead
+const NO_PLAYHEAD: usize = usize::MAX;
+
+struct WaveformData {
+    // The bits of each sample
+    samples: Box<[AtomicU32]>,
+    len: AtomicUsize,
+    playhead: AtomicUsize,
+    // Counts changes to the samples so a view knows when to decimate them again
+    generation: AtomicUsize,
+}
+
+/// Samples shown by a `WaveformView`, shared with the audio thread
+///
+/// The buffer has a fixed capacity, so samples can be pushed and the playhead moved from the audio
+/// thread without blocking or allocating. The view checks the buffer once per frame and redraws
+/// when it has changed. Samples should be pushed from one thread at a time.
+///
+/// # Examples
+/// ```ignore
+/// let buffer = WaveformBuffer::new(44100 * 10);
+///
+/// // UI thread
+/// WaveformView::new().with_buffer(buffer.clone()).build(state, parent, |builder| builder);
+///
+/// // Audio thread, while recording
+/// buffer.push(&input);
+/// buffer.set_playhead(Some(buffer.len()));
+/// ```
+#[derive(Clone)]
+pub struct WaveformBuffer {
+    data: Arc<WaveformData>,
+}
+
+impl WaveformBuffer {
+    pub fn new(capacity: usize) -> Self {
+        WaveformBuffer {
+            data: Arc::new(WaveformData {
+                samples: (0..capacity).map(|_| AtomicU32::new(0)).collect(),
+                len: AtomicUsize::new(0),
+                playhead: AtomicUsize::new(NO_PLAYHEAD),
+                generation: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Creates a full buffer holding a copy of some samples, e.g. of a loaded file
+    pub fn from_samples(samples: &[f32]) -> Self {
+        let buffer = WaveformBuffer::new(samples.len());
+        buffer.push(samples);
+        buffer
+    }
+
+    /// Returns the number of samples the buffer can hold
+    pub fn capacity(&self) -> usize {
+        self.data.samples.len()
+    }
+
+    /// Returns the number of samples in the buffer
+    pub fn len(&self) -> usize {
+        self.data.len.load(Ordering::Acquire)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends samples to the buffer, returning how many fitted before it was full
+    pub fn push(&self, samples: &[f32]) -> usize {
+        let len = self.data.len.load(Ordering::Acquire);
+        let count = samples.len().min(self.capacity() - len);
+
+        for (slot, sample) in self.data.samples[len..len + count].iter().zip(samples) {
+            slot.store(sample.to_bits(), Ordering::Relaxed);
+        }
+
+        if count > 0 {
+            self.data.len.store(len + count, Ordering::Release);
+            self.data.generation.fetch_add(1, Ordering::AcqRel);
+        }
+
+        count
+    }
+
+    /// Removes all samples from the buffer
+    pub fn clear(&self) {
+        self.data.len.store(0, Ordering::Release);
+        self.data.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Sets the index of the sample being played, or None to hide the playhead
+    pub fn set_playhead(&self, position: Option<usize>) {
+        self.data
+            .playhead
+            .store(position.unwrap_or(NO_PLAYHEAD), Ordering::Release);
+    }
+
+    pub fn playhead(&self) -> Option<usize> {
+        match self.data.playhead.load(Ordering::Acquire) {
+            NO_PLAYHEAD => None,
+            position => Some(position),
+        }
+    }
+
+    // Returns a sample, or silence past the end of the buffer
+    pub(crate) fn sample(&self, index: usize) -> f32 {
+        self.data
+            .samples
+            .get(index)
+            .map(|sample| f32::from_bits(sample.load(Ordering::Relaxed)))
+            .unwrap_or(0.0)
+    }
+
+    pub(crate) fn generation(&self) -> usize {
+        self.data.generation.load(Ordering::Acquire)
+    }
+}
+
+struct SpectrumData {
+    // The bits of the magnitude of each bin
+    bins: Box<[AtomicU32]>,
+    sample_rate: f32,
+    // Counts published spectra so a view knows when to take a new one
+    generation: AtomicUsize,
+}
+
+/// Magnitudes shown by a `SpectrumView`, shared with the audio thread
+///
+/// The bins run evenly from 0 Hz to half the sample rate, as from a real FFT of `2 * (bins - 1)`
+/// samples, and hold linear magnitudes scaled so a full scale sine is 1.0. The audio thread, or an
+/// analysis thread, publishes each spectrum without blocking or allocating, and the view takes the
+/// latest one once per frame. A view may show a mix of two spectra published during one frame,
+/// which can't be seen once smoothed.
+///
+/// # Examples
+/// ```ignore
+/// let spectrum = SpectrumBuffer::new(1025, 44100.0);
+///
+/// // UI thread
+/// SpectrumView::new().with_buffer(spectrum.clone()).build(state, parent, |builder| builder);
+///
+/// // Analysis thread, after an FFT of 2048 samples
+/// spectrum.publish(&magnitudes);
+/// ```
+#[derive(Clone)]
+pub struct SpectrumBuffer {
+    data: Arc<SpectrumData>,
+}
+
+impl SpectrumBuffer {
+    pub fn new(bins: usize, sample_rate: f32) -> Self {
+        SpectrumBuffer {
+            data: Arc::new(SpectrumData {
+                bins: (0..bins).map(|_| AtomicU32::new(0)).collect(),
+                sample_rate,
+                generation: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Returns the number of bins
+    pub fn bins(&self) -> usize {
+        self.data.bins.len()
+    }
+
+    pub fn sample_rate(&self) -> f32 {
+        self.data.sample_rate
+    }
+
+    /// Returns the frequency of a bin in Hz
+    pub fn frequency(&self, bin: usize) -> f32 {
+        if self.bins() < 2 {
+            return 0.0;
+        }
+
+        bin as f32 * self.data.sample_rate / (2 * (self.bins() - 1)) as f32
+    }
+
+    /// Publishes the magnitudes of a spectrum, ignoring any past the last bin
+    pub fn publish(&self, magnitudes: &[f32]) {
+        for (bin, magnitude) in self.data.bins.iter().zip(magnitudes) {
+            bin.store(magnitude.to_bits(), Ordering::Relaxed);
+        }
+
+        self.data.generation.fetch_add(1, Ordering::AcqRel);
+    }
+
+    // Copies the latest magnitudes into a vector with one entry per bin
+    pub(crate) fn read(&self, magnitudes: &mut Vec<f32>) {
+        magnitudes.clear();
+        magnitudes.extend(
+            self.data
+                .bins
+                .iter()
+                .map(|bin| f32::from_bits(bin.load(Ordering::Relaxed))),
+        );
+    }
+
+    pub(crate) fn generation(&self) -> usize {
+        self.data.generation.load(Ordering::Acquire)
+    }
+}
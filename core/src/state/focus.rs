@@ -0,0 +1,271 @@
+use crate::{
+    Entity, Event, HierarchyTree, IntoBranchIterator, IntoChildIterator, IntoParentIterator,
+    PropSet, Propagation, State, Visibility, WindowEvent,
+};
+
+// A focus scope restricts keyboard focus navigation to the descendants of the scope entity.
+// The previously focused entity is stored so that focus can be restored when the scope is removed.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FocusScope {
+    pub scope: Entity,
+    pub restore: Entity,
+}
+
+impl State {
+    /// Moves keyboard focus to the specified entity, sending `FocusOut` and `FocusIn` events
+    pub fn set_focus(&mut self, entity: Entity) {
+        if self.focused == entity {
+            return;
+        }
+
+        let previous = self.focused;
+
+        if previous != Entity::null() {
+            previous.set_focus(self, false);
+            self.set_focus_within(previous, false);
+            self.insert_event(
+                Event::new(WindowEvent::FocusOut)
+                    .target(previous)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        self.focused = entity;
+
+        if entity != Entity::null() {
+            entity.set_focus(self, true);
+            self.set_focus_within(entity, true);
+            self.insert_event(
+                Event::new(WindowEvent::FocusIn)
+                    .target(entity)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        self.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+        self.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    /// Moves keyboard focus to the next entity in the focus order
+    ///
+    /// An explicit focus order set with `set_next_focus` takes priority. Otherwise entities with a
+    /// positive tab index are visited first in ascending order, followed by entities with a tab index
+    /// of zero in hierarchy order. Navigation wraps around within the current focus scope.
+    pub fn focus_next(&mut self) {
+        let explicit = self
+            .style
+            .focus_order
+            .get(self.focused)
+            .map(|focus_order| focus_order.next)
+            .unwrap_or_default();
+
+        if explicit != Entity::null() && self.is_focusable(explicit) {
+            self.set_focus(explicit);
+            return;
+        }
+
+        let stops = self.tab_stops();
+
+        if stops.is_empty() {
+            return;
+        }
+
+        let next = match self.focused_stop(&stops) {
+            Some(index) => stops[(index + 1) % stops.len()],
+            None => stops[0],
+        };
+
+        self.set_focus(next);
+    }
+
+    /// Moves keyboard focus to the previous entity in the focus order
+    pub fn focus_prev(&mut self) {
+        let explicit = self
+            .style
+            .focus_order
+            .get(self.focused)
+            .map(|focus_order| focus_order.prev)
+            .unwrap_or_default();
+
+        if explicit != Entity::null() && self.is_focusable(explicit) {
+            self.set_focus(explicit);
+            return;
+        }
+
+        let stops = self.tab_stops();
+
+        if stops.is_empty() {
+            return;
+        }
+
+        let prev = match self.focused_stop(&stops) {
+            Some(index) => stops[(index + stops.len() - 1) % stops.len()],
+            None => stops[stops.len() - 1],
+        };
+
+        self.set_focus(prev);
+    }
+
+    /// Moves keyboard focus to the next or previous focusable child of a container
+    ///
+    /// Used by widgets which support arrow key navigation between their children, such as
+    /// `RadioList`, `Listbox` and `Menu`. If no child currently contains focus then the first
+    /// (or last) focusable child is focused. Returns the newly focused child.
+    pub fn focus_child(&mut self, container: Entity, forward: bool, wrap: bool) -> Option<Entity> {
+        let mut children = container.child_iter(&self.hierarchy).collect::<Vec<_>>();
+
+        children.retain(|child| self.is_focusable(*child));
+
+        if children.is_empty() {
+            return None;
+        }
+
+        let current = children
+            .iter()
+            .position(|child| self.focused.is_descendant_of(&self.hierarchy, *child));
+
+        let index = match current {
+            Some(index) => {
+                if forward {
+                    if index + 1 < children.len() {
+                        index + 1
+                    } else if wrap {
+                        0
+                    } else {
+                        index
+                    }
+                } else {
+                    if index > 0 {
+                        index - 1
+                    } else if wrap {
+                        children.len() - 1
+                    } else {
+                        index
+                    }
+                }
+            }
+
+            None => {
+                if forward {
+                    0
+                } else {
+                    children.len() - 1
+                }
+            }
+        };
+
+        self.set_focus(children[index]);
+
+        Some(children[index])
+    }
+
+    /// Restricts keyboard focus navigation to the descendants of the scope entity
+    ///
+    /// Focus scopes are stacked, so a dialog opened from a menu traps focus until it is closed.
+    /// If focus is not already within the scope then the first tab stop in the scope is focused.
+    pub fn push_focus_scope(&mut self, scope: Entity) {
+        if self.focus_scopes.iter().any(|focus_scope| focus_scope.scope == scope) {
+            return;
+        }
+
+        self.focus_scopes.push(FocusScope {
+            scope,
+            restore: self.focused,
+        });
+
+        if !self.focused.is_descendant_of(&self.hierarchy, scope) {
+            if let Some(first) = self.tab_stops().first().cloned() {
+                self.set_focus(first);
+            }
+        }
+    }
+
+    /// Removes a focus scope, restoring focus to the entity which was focused when it was pushed
+    pub fn pop_focus_scope(&mut self, scope: Entity) {
+        if let Some(index) = self
+            .focus_scopes
+            .iter()
+            .position(|focus_scope| focus_scope.scope == scope)
+        {
+            let focus_scope = self.focus_scopes.remove(index);
+
+            if self.focused == Entity::null() || self.focused.is_descendant_of(&self.hierarchy, scope) {
+                self.set_focus(focus_scope.restore);
+            }
+        }
+    }
+
+    /// Returns the innermost focus scope, or the root if no scope is active
+    pub fn focus_scope(&self) -> Entity {
+        self.focus_scopes
+            .last()
+            .map(|focus_scope| focus_scope.scope)
+            .unwrap_or(Entity::root())
+    }
+
+    /// Returns true if the entity can currently receive keyboard focus
+    ///
+    /// An entity is focusable if it has a tab index and is visible and enabled.
+    pub fn is_focusable(&mut self, entity: Entity) -> bool {
+        if entity == Entity::null() || self.style.tab_index.get(entity).is_none() {
+            return false;
+        }
+
+        if self.data.get_visibility(entity) == Visibility::Invisible {
+            return false;
+        }
+
+        // Widgets hidden by opacity, such as the container of a closed menu, are skipped
+        if self.data.get_opacity(entity) == 0.0 {
+            return false;
+        }
+
+        !entity.is_disabled(self)
+    }
+
+    // Returns the entities which can be reached by Tab in the current focus scope, in focus order
+    fn tab_stops(&mut self) -> Vec<Entity> {
+        let scope = self.focus_scope();
+
+        let mut stops = scope
+            .branch_iter(&self.hierarchy)
+            .filter_map(|entity| {
+                self.style
+                    .tab_index
+                    .get(entity)
+                    .cloned()
+                    .filter(|tab_index| *tab_index >= 0)
+                    .map(|tab_index| (tab_index, entity))
+            })
+            .collect::<Vec<_>>();
+
+        stops.retain(|(_, entity)| self.is_focusable(*entity));
+
+        // Positive tab indices come first in ascending order, the sort is stable so ties keep hierarchy order
+        stops.sort_by_key(|(tab_index, _)| if *tab_index > 0 { *tab_index } else { std::i32::MAX });
+
+        stops.into_iter().map(|(_, entity)| entity).collect()
+    }
+
+    // Returns the index of the tab stop containing focus. Focus can be on an entity which is skipped
+    // by Tab, such as a listbox item, in which case the nearest ancestor tab stop is used.
+    fn focused_stop(&self, stops: &[Entity]) -> Option<usize> {
+        self.focused
+            .parent_iter(&self.hierarchy)
+            .find_map(|ancestor| stops.iter().position(|stop| *stop == ancestor))
+    }
+
+    fn set_focus_within(&mut self, entity: Entity, flag: bool) {
+        if entity.index().is_none() {
+            return;
+        }
+
+        let ancestors = entity.parent_iter(&self.hierarchy).collect::<Vec<_>>();
+
+        for ancestor in ancestors {
+            if let Some(pseudo_classes) = self.style.pseudo_classes.get_mut(ancestor) {
+                pseudo_classes.set_focus_within(flag);
+            }
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use crate::{
+    Entity, Event, HierarchyTree, MouseButton, PointerEvent, PointerInfo, PointerKind,
+    Propagation, State, WindowEvent,
+};
+
+use std::time::{Duration, Instant};
+
+// A pointer which is currently held down
+#[derive(Debug, Clone, Copy)]
+struct PointerTrack {
+    pointer: PointerInfo,
+    target: Entity,
+    start: (f32, f32),
+    last: (f32, f32),
+    pressed_at: Instant,
+    count: u32,
+    dragging: bool,
+    // Set when the press has already produced a gesture (long press or pinch) and should not click
+    consumed: bool,
+}
+
+// Two touches being tracked as a pinch/pan gesture
+#[derive(Debug, Clone, Copy)]
+struct MultiTouch {
+    first: u64,
+    second: u64,
+    target: Entity,
+    distance: f32,
+    center: (f32, f32),
+}
+
+// The last press, used to count double and triple clicks
+#[derive(Debug, Clone, Copy)]
+struct LastPress {
+    button: MouseButton,
+    time: Instant,
+    pos: (f32, f32),
+    count: u32,
+}
+
+/// Turns raw pointer input from the backend into `PointerEvent` gestures
+pub struct Gestures {
+    /// Distance in pixels a pointer must move before a press becomes a drag
+    pub drag_threshold: f32,
+    /// Maximum time between presses for them to count as a double or triple click
+    pub multi_click_interval: Duration,
+    /// Time a pointer must be held down without moving to produce a long press
+    pub long_press_delay: Duration,
+
+    pointers: Vec<PointerTrack>,
+    multi_touch: Option<MultiTouch>,
+    last_press: Option<LastPress>,
+}
+
+impl Gestures {
+    pub fn new() -> Self {
+        Gestures {
+            drag_threshold: 4.0,
+            multi_click_interval: Duration::from_millis(500),
+            long_press_delay: Duration::from_millis(600),
+
+            pointers: Vec::new(),
+            multi_touch: None,
+            last_press: None,
+        }
+    }
+
+    /// Returns the button held down by a pointer, or None if the pointer is not pressed
+    pub fn pressed_button(&self, id: u64) -> Option<MouseButton> {
+        self.pointers
+            .iter()
+            .find(|track| track.pointer.id == id)
+            .map(|track| track.pointer.button)
+    }
+}
+
+impl State {
+    /// Called by the backend when a pointer is pressed
+    ///
+    /// The pressed entity is the captured entity, or the hovered entity if nothing has captured the
+    /// mouse, so the backend should update the hovered entity before calling this for touches.
+    pub fn pointer_down(&mut self, pointer: PointerInfo) {
+        if self.gestures.pointers.iter().any(|track| track.pointer.id == pointer.id) {
+            return;
+        }
+
+        let target = if self.captured != Entity::null() {
+            self.captured
+        } else {
+            self.hovered
+        };
+
+        let now = Instant::now();
+        let pos = (pointer.x, pointer.y);
+
+        let threshold = self.gestures.drag_threshold;
+        let count = match self.gestures.last_press {
+            Some(last)
+                if last.button == pointer.button
+                    && now.duration_since(last.time) <= self.gestures.multi_click_interval
+                    && distance(last.pos, pos) <= threshold
+                    && last.count < 3 =>
+            {
+                last.count + 1
+            }
+
+            _ => 1,
+        };
+
+        self.gestures.last_press = Some(LastPress {
+            button: pointer.button,
+            time: now,
+            pos,
+            count,
+        });
+
+        if count == 2 {
+            self.insert_event(
+                Event::new(WindowEvent::MouseDoubleClick(pointer.button))
+                    .target(target)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        self.gestures.pointers.push(PointerTrack {
+            pointer,
+            target,
+            start: pos,
+            last: pos,
+            pressed_at: now,
+            count,
+            dragging: false,
+            consumed: false,
+        });
+
+        // A second touch turns the first touch into a pinch/pan gesture
+        if pointer.kind == PointerKind::Touch && self.gestures.multi_touch.is_none() {
+            let touches = self
+                .gestures
+                .pointers
+                .iter()
+                .filter(|track| track.pointer.kind == PointerKind::Touch)
+                .cloned()
+                .collect::<Vec<_>>();
+
+            if touches.len() == 2 {
+                let first = touches[0];
+                let second = touches[1];
+
+                if first.dragging {
+                    self.end_drag(&first);
+                }
+
+                for track in self.gestures.pointers.iter_mut() {
+                    if track.pointer.id == first.pointer.id || track.pointer.id == second.pointer.id {
+                        track.dragging = false;
+                        track.consumed = true;
+                    }
+                }
+
+                self.gestures.multi_touch = Some(MultiTouch {
+                    first: first.pointer.id,
+                    second: second.pointer.id,
+                    target: first.target,
+                    distance: distance(first.last, second.last),
+                    center: midpoint(first.last, second.last),
+                });
+            }
+        }
+    }
+
+    /// Called by the backend when a pointer moves
+    pub fn pointer_move(&mut self, id: u64, x: f32, y: f32, pressure: Option<f32>) {
+        let index = match self
+            .gestures
+            .pointers
+            .iter()
+            .position(|track| track.pointer.id == id)
+        {
+            Some(index) => index,
+            None => return,
+        };
+
+        let mut track = self.gestures.pointers[index];
+        let previous = track.last;
+
+        track.pointer.x = x;
+        track.pointer.y = y;
+        if pressure.is_some() {
+            track.pointer.pressure = pressure;
+        }
+        track.last = (x, y);
+
+        if let Some(mut multi) = self.gestures.multi_touch {
+            if multi.first == id || multi.second == id {
+                self.gestures.pointers[index] = track;
+
+                let other_id = if multi.first == id { multi.second } else { multi.first };
+                let other = match self
+                    .gestures
+                    .pointers
+                    .iter()
+                    .find(|track| track.pointer.id == other_id)
+                {
+                    Some(other) => other.last,
+                    None => return,
+                };
+
+                let new_distance = distance(track.last, other);
+                let new_center = midpoint(track.last, other);
+
+                if new_center != multi.center {
+                    self.insert_event(
+                        Event::new(PointerEvent::Pan {
+                            dx: new_center.0 - multi.center.0,
+                            dy: new_center.1 - multi.center.1,
+                        })
+                        .target(multi.target)
+                        .propagate(Propagation::Up),
+                    );
+                }
+
+                if multi.distance > 0.0 && new_distance != multi.distance {
+                    self.insert_event(
+                        Event::new(PointerEvent::Pinch {
+                            scale: new_distance / multi.distance,
+                            x: new_center.0,
+                            y: new_center.1,
+                        })
+                        .target(multi.target)
+                        .propagate(Propagation::Up),
+                    );
+                }
+
+                multi.distance = new_distance;
+                multi.center = new_center;
+                self.gestures.multi_touch = Some(multi);
+
+                return;
+            }
+        }
+
+        if !track.dragging && !track.consumed {
+            if distance(track.start, (x, y)) > self.gestures.drag_threshold {
+                track.dragging = true;
+
+                self.insert_event(
+                    Event::new(PointerEvent::DragStart {
+                        pointer: track.pointer,
+                        x: track.start.0,
+                        y: track.start.1,
+                    })
+                    .target(track.target)
+                    .propagate(Propagation::Up),
+                );
+
+                self.send_drag_move(&track, track.start);
+            }
+        } else if track.dragging {
+            self.send_drag_move(&track, previous);
+        }
+
+        self.gestures.pointers[index] = track;
+    }
+
+    /// Called by the backend when a pointer is released
+    pub fn pointer_up(&mut self, id: u64, x: f32, y: f32) {
+        let index = match self
+            .gestures
+            .pointers
+            .iter()
+            .position(|track| track.pointer.id == id)
+        {
+            Some(index) => index,
+            None => return,
+        };
+
+        let mut track = self.gestures.pointers.remove(index);
+        track.pointer.x = x;
+        track.pointer.y = y;
+        track.last = (x, y);
+
+        if let Some(multi) = self.gestures.multi_touch {
+            if multi.first == id || multi.second == id {
+                self.gestures.multi_touch = None;
+            }
+        }
+
+        if track.dragging {
+            self.end_drag(&track);
+            return;
+        }
+
+        if track.consumed {
+            return;
+        }
+
+        // Only click if the pointer was released over the entity it was pressed on
+        let released = if self.captured != Entity::null() && self.captured != track.target {
+            self.captured
+        } else {
+            self.hovered
+        };
+
+        if released == track.target || released.is_descendant_of(&self.hierarchy, track.target) {
+            self.insert_event(
+                Event::new(PointerEvent::Click {
+                    pointer: track.pointer,
+                    count: track.count,
+                })
+                .target(track.target)
+                .propagate(Propagation::Up),
+            );
+        }
+    }
+
+    /// Called by the backend when a pointer is lost, such as a cancelled touch
+    ///
+    /// Any drag in progress is ended but no click is produced.
+    pub fn pointer_cancel(&mut self, id: u64) {
+        if let Some(index) = self
+            .gestures
+            .pointers
+            .iter()
+            .position(|track| track.pointer.id == id)
+        {
+            let track = self.gestures.pointers.remove(index);
+
+            if track.dragging {
+                self.end_drag(&track);
+            }
+        }
+
+        if let Some(multi) = self.gestures.multi_touch {
+            if multi.first == id || multi.second == id {
+                self.gestures.multi_touch = None;
+            }
+        }
+    }
+
+    /// Sends long press events for touches and pens which have been held down long enough
+    ///
+    /// Should be called by the backend once per iteration of the event loop. Returns the time at
+    /// which the next long press is due so that the backend can wake up in time to send it.
+    pub fn update_gestures(&mut self) -> Option<Instant> {
+        let now = Instant::now();
+        let delay = self.gestures.long_press_delay;
+
+        let mut deadline: Option<Instant> = None;
+        let mut long_presses = Vec::new();
+
+        for track in self.gestures.pointers.iter_mut() {
+            // A slow mouse click is still a click, long press is only recognised for touch and pen
+            if track.dragging || track.consumed || track.pointer.kind == PointerKind::Mouse {
+                continue;
+            }
+
+            let due = track.pressed_at + delay;
+
+            if due <= now {
+                track.consumed = true;
+                long_presses.push(*track);
+            } else {
+                deadline = Some(deadline.map_or(due, |deadline| deadline.min(due)));
+            }
+        }
+
+        for track in long_presses {
+            self.insert_event(
+                Event::new(PointerEvent::LongPress {
+                    pointer: track.pointer,
+                })
+                .target(track.target)
+                .propagate(Propagation::Up),
+            );
+        }
+
+        deadline
+    }
+
+    fn send_drag_move(&mut self, track: &PointerTrack, previous: (f32, f32)) {
+        self.insert_event(
+            Event::new(PointerEvent::DragMove {
+                pointer: track.pointer,
+                dx: track.last.0 - previous.0,
+                dy: track.last.1 - previous.1,
+                total_dx: track.last.0 - track.start.0,
+                total_dy: track.last.1 - track.start.1,
+            })
+            .target(track.target)
+            .propagate(Propagation::Up),
+        );
+    }
+
+    fn end_drag(&mut self, track: &PointerTrack) {
+        self.insert_event(
+            Event::new(PointerEvent::DragEnd {
+                pointer: track.pointer,
+                total_dx: track.last.0 - track.start.0,
+                total_dy: track.last.1 - track.start.1,
+            })
+            .target(track.target)
+            .propagate(Propagation::Up),
+        );
+    }
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+fn midpoint(a: (f32, f32), b: (f32, f32)) -> (f32, f32) {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
@@ -375,6 +375,9 @@ impl Hierarchy {
             self.prev_sibling[next_sibling.index_unchecked()] = self.get_prev_sibling(entity);
         }
 
+        self.next_sibling[entity.index_unchecked()] = None;
+        self.prev_sibling[entity.index_unchecked()] = None;
+
         if self.first_child[parent.index_unchecked()] == None {
             self.first_child[parent.index_unchecked()] = Some(entity);
         } else {
@@ -0,0 +1,163 @@
+use crate::{Entity, Event, State};
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The peak and RMS level of one channel measured since the last frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterReading {
+    pub peak: f32,
+    pub rms: f32,
+    // Whether a sample reached full scale
+    pub clipped: bool,
+}
+
+struct ChannelFeed {
+    // The bits of the largest absolute sample
+    peak: AtomicU32,
+    // The bits of the sum of the squared samples
+    sum_squares: AtomicU32,
+    samples: AtomicU32,
+    clipped: AtomicBool,
+}
+
+/// Levels measured on the audio thread for a `LevelMeter`
+///
+/// The audio thread passes each block of samples to `process`, which folds them into the peak and
+/// RMS of the channel without blocking or allocating. The meter takes the levels once per frame, so
+/// it shows every block however the block rate compares to the frame rate. Taking the levels
+/// clears them, so each feed should be read by one meter.
+///
+/// # Examples
+/// ```ignore
+/// let feed = MeterFeed::new(2);
+///
+/// // UI thread
+/// LevelMeter::new(2).with_feed(feed.clone()).build(state, parent, |builder| builder);
+///
+/// // Audio thread
+/// feed.process(0, &left);
+/// feed.process(1, &right);
+/// ```
+#[derive(Clone)]
+pub struct MeterFeed {
+    channels: Arc<[ChannelFeed]>,
+}
+
+impl MeterFeed {
+    pub fn new(channels: usize) -> Self {
+        MeterFeed {
+            channels: (0..channels)
+                .map(|_| ChannelFeed {
+                    peak: AtomicU32::new(0),
+                    sum_squares: AtomicU32::new(0),
+                    samples: AtomicU32::new(0),
+                    clipped: AtomicBool::new(false),
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the number of channels
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Measures a block of samples of a channel
+    pub fn process(&self, channel: usize, samples: &[f32]) {
+        let feed = match self.channels.get(channel) {
+            Some(feed) => feed,
+            None => return,
+        };
+
+        let mut peak = 0.0f32;
+        let mut sum_squares = 0.0f32;
+
+        for sample in samples {
+            peak = peak.max(sample.abs());
+            sum_squares += sample * sample;
+        }
+
+        let _ = feed.peak.fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+            Some(f32::from_bits(bits).max(peak).to_bits())
+        });
+
+        let _ = feed.sum_squares.fetch_update(Ordering::AcqRel, Ordering::Acquire, |bits| {
+            Some((f32::from_bits(bits) + sum_squares).to_bits())
+        });
+
+        feed.samples.fetch_add(samples.len() as u32, Ordering::AcqRel);
+
+        if peak >= 1.0 {
+            feed.clipped.store(true, Ordering::Release);
+        }
+    }
+
+    // Returns the levels of a channel measured since the last call, or None if no samples were measured
+    pub(crate) fn take(&self, channel: usize) -> Option<MeterReading> {
+        let feed = self.channels.get(channel)?;
+
+        // A block measured between these swaps is split across two frames, which a meter can't show
+        let samples = feed.samples.swap(0, Ordering::AcqRel);
+        let peak = f32::from_bits(feed.peak.swap(0, Ordering::AcqRel));
+        let sum_squares = f32::from_bits(feed.sum_squares.swap(0, Ordering::AcqRel));
+        let clipped = feed.clipped.swap(false, Ordering::AcqRel);
+
+        if samples == 0 {
+            return None;
+        }
+
+        Some(MeterReading {
+            peak,
+            rms: (sum_squares / samples as f32).sqrt(),
+            clipped,
+        })
+    }
+}
+
+/// The level meters and other audio displays which are updated every frame
+pub struct Meters {
+    /// Time between updates of the meters
+    pub frame_interval: Duration,
+
+    // Each display with the event it is sent every frame
+    entities: Vec<(Entity, fn() -> Event)>,
+}
+
+impl Meters {
+    pub fn new() -> Self {
+        Meters {
+            frame_interval: Duration::from_millis(16),
+
+            entities: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, entity: Entity, frame: fn() -> Event) {
+        if !self.entities.iter().any(|(meter, _)| *meter == entity) {
+            self.entities.push((entity, frame));
+        }
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.entities.retain(|(meter, _)| *meter != entity);
+    }
+}
+
+impl State {
+    /// Sends a frame to every level meter and audio display so it can take new data and apply its ballistics
+    ///
+    /// Returns when the meters should next be updated. Called by the event loop every frame.
+    pub fn update_meters(&mut self) -> Option<Instant> {
+        if self.meters.entities.is_empty() {
+            return None;
+        }
+
+        for (entity, frame) in self.meters.entities.clone() {
+            self.insert_event((frame)().target(entity));
+        }
+
+        Some(Instant::now() + self.meters.frame_interval)
+    }
+}
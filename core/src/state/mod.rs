@@ -22,12 +22,43 @@ pub use mouse::*;
 pub mod resource;
 pub use resource::*;
 
+pub mod shortcuts;
+pub use shortcuts::*;
+
+pub mod focus;
+pub(crate) use focus::FocusScope;
+
+pub mod accessibility;
+pub use accessibility::*;
+
+pub mod gestures;
+pub use gestures::*;
+
+pub mod popups;
+pub use popups::*;
+
+pub mod tooltips;
+pub use tooltips::*;
+
+pub mod context_menus;
+pub use context_menus::*;
+
+pub mod params;
+pub use params::*;
+
+pub mod meters;
+pub use meters::*;
+
+pub mod displays;
+pub use displays::*;
+
 pub use crate::events::{Builder, Event, EventHandler, Propagation};
 pub use crate::window_event::WindowEvent;
 
-use femtovg::FontId;
+use femtovg::{FontId, ImageId};
 
 use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 
 use fnv::FnvHashMap;
 
@@ -55,6 +86,16 @@ pub struct State {
     pub active: Entity,
     pub captured: Entity,
     pub focused: Entity,
+    pub gestures: Gestures,
+    pub(crate) focus_scopes: Vec<FocusScope>,
+    pub popups: Popups,
+    pub tooltips: Tooltips,
+    pub context_menus: ContextMenus,
+    pub params: Params,
+    pub meters: Meters,
+
+    pub shortcuts: Shortcuts,
+    pub accessibility: Accessibility,
 
     pub event_handlers: FnvHashMap<Entity, Box<dyn EventHandler>>,
     pub(crate) removed_entities: Vec<Entity>,
@@ -62,6 +103,9 @@ pub struct State {
 
     pub fonts: Fonts, //TODO - Replace with resource manager
 
+    // Images uploaded by widgets which have since been dropped, deleted from the canvas at the next draw
+    pub(crate) released_images: Arc<Mutex<Vec<ImageId>>>,
+
     resource_manager: ResourceManager, //TODO
 }
 
@@ -96,6 +140,15 @@ impl State {
             active: Entity::null(),
             captured: Entity::null(),
             focused: Entity::new(0),
+            gestures: Gestures::new(),
+            focus_scopes: Vec::new(),
+            popups: Popups::new(),
+            tooltips: Tooltips::new(),
+            context_menus: ContextMenus::new(),
+            params: Params::new(),
+            meters: Meters::new(),
+            shortcuts: Shortcuts::new(),
+            accessibility: Accessibility::new(),
             event_handlers: FnvHashMap::default(),
             event_queue: VecDeque::new(),
             removed_entities: Vec::new(),
@@ -105,6 +158,7 @@ impl State {
                 icons: None,
                 emoji: None,
             },
+            released_images: Arc::new(Mutex::new(Vec::new())),
             resource_manager: ResourceManager::new(),
         }
     }
@@ -211,6 +265,11 @@ impl State {
         // Text Alignment
         self.style.text_align.remove_styles();
         self.style.text_justify.remove_styles();
+        // Knob Arcs
+        self.style.arc_start_angle.remove_styles();
+        self.style.arc_end_angle.remove_styles();
+        self.style.arc_width.remove_styles();
+        self.style.arc_center.remove_styles();
 
         let mut overall_theme = String::new();
 
@@ -326,11 +385,33 @@ impl State {
     //  TODO
     pub fn remove(&mut self, entity: Entity) {
 
-        let delete_list = entity.branch_iter(&self.hierarchy).collect::<Vec<_>>();
+        let mut delete_list = entity.branch_iter(&self.hierarchy).collect::<Vec<_>>();
+
+        // Rich tooltips are built on the root, so they're removed along with the widget they belong to
+        for tooltip in self.tooltips.rich_tooltips(&delete_list) {
+            delete_list.extend(tooltip.branch_iter(&self.hierarchy));
+        }
+
+        // So are their context menus
+        for menu in self.context_menus.built_menus(&delete_list) {
+            delete_list.extend(menu.branch_iter(&self.hierarchy));
+        }
 
         println!("Delete List: {:?}", delete_list);
 
+        // Focus leaves the branch before it's removed, so its ancestors lose the focus-within state
+        if delete_list.contains(&self.focused) {
+            self.set_focus(Entity::root());
+        }
+
         for entity in delete_list.iter().rev() {
+            self.shortcuts.unbind_scope(*entity);
+            self.focus_scopes.retain(|focus_scope| focus_scope.scope != *entity);
+            self.popups.remove(*entity);
+            self.tooltips.remove(*entity);
+            self.context_menus.remove(*entity);
+            self.params.remove(*entity);
+            self.meters.remove(*entity);
             self.hierarchy.remove(*entity);
             self.hierarchy.remove(*entity);
             self.data.remove(*entity);
@@ -0,0 +1,506 @@
+use crate::widgets::SliderEvent;
+use crate::{Entity, Event, IntoParentIterator, State};
+
+use fnv::FnvHashMap;
+
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The index of a parameter in a `ParamStore`
+pub type ParamId = usize;
+
+/// The range and default value of a parameter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamInfo {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    pub default: f32,
+}
+
+impl ParamInfo {
+    pub fn new(name: &str, min: f32, max: f32, default: f32) -> Self {
+        ParamInfo {
+            name: name.to_string(),
+            min,
+            max,
+            default,
+        }
+    }
+}
+
+/// An edit of a parameter made in the UI, passed to the audio thread
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamGesture {
+    // The user started editing the parameter, e.g. pressed a knob
+    Begin(ParamId),
+    // The user changed the value of the parameter
+    Perform(ParamId, f32),
+    // The user finished editing the parameter
+    End(ParamId),
+}
+
+struct Param {
+    info: ParamInfo,
+    // The bits of the f32 value
+    value: AtomicU32,
+    // Set when the value is changed from outside the UI, cleared when the UI picks it up
+    changed: AtomicBool,
+}
+
+// A slot of the gesture queue. The kind is stored in the top two bits of `kind_id`.
+struct GestureSlot {
+    kind_id: AtomicU32,
+    value: AtomicU32,
+}
+
+const GESTURE_BEGIN: u32 = 0;
+const GESTURE_PERFORM: u32 = 1;
+const GESTURE_END: u32 = 2;
+const GESTURE_ID_MASK: u32 = (1 << 30) - 1;
+
+// A fixed size single-producer single-consumer queue of gestures
+struct GestureQueue {
+    slots: Box<[GestureSlot]>,
+    // Index of the next gesture to pop, only written by the consumer
+    head: AtomicUsize,
+    // Index of the next gesture to push, only written by the producer
+    tail: AtomicUsize,
+}
+
+impl GestureQueue {
+    fn new(capacity: usize) -> Self {
+        GestureQueue {
+            slots: (0..capacity.max(1))
+                .map(|_| GestureSlot {
+                    kind_id: AtomicU32::new(0),
+                    value: AtomicU32::new(0),
+                })
+                .collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    fn push(&self, gesture: ParamGesture) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let head = self.head.load(Ordering::Acquire);
+
+        if tail.wrapping_sub(head) >= self.slots.len() {
+            return false;
+        }
+
+        let (kind, id, value) = match gesture {
+            ParamGesture::Begin(id) => (GESTURE_BEGIN, id, 0.0),
+            ParamGesture::Perform(id, value) => (GESTURE_PERFORM, id, value),
+            ParamGesture::End(id) => (GESTURE_END, id, 0.0),
+        };
+
+        let slot = &self.slots[tail % self.slots.len()];
+        slot.kind_id
+            .store((kind << 30) | (id as u32 & GESTURE_ID_MASK), Ordering::Relaxed);
+        slot.value.store(value.to_bits(), Ordering::Relaxed);
+
+        self.tail.store(tail.wrapping_add(1), Ordering::Release);
+
+        true
+    }
+
+    fn pop(&self) -> Option<ParamGesture> {
+        let head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        let slot = &self.slots[head % self.slots.len()];
+        let kind_id = slot.kind_id.load(Ordering::Relaxed);
+        let value = f32::from_bits(slot.value.load(Ordering::Relaxed));
+
+        self.head.store(head.wrapping_add(1), Ordering::Release);
+
+        let id = (kind_id & GESTURE_ID_MASK) as ParamId;
+
+        Some(match kind_id >> 30 {
+            GESTURE_BEGIN => ParamGesture::Begin(id),
+            GESTURE_PERFORM => ParamGesture::Perform(id, value),
+            _ => ParamGesture::End(id),
+        })
+    }
+}
+
+struct ParamStoreInner {
+    params: Box<[Param]>,
+    gestures: GestureQueue,
+    // Set when any parameter is changed outside of the UI, cleared when the UI polls the store
+    pending: AtomicBool,
+}
+
+/// A set of parameters shared between the audio thread and the UI
+///
+/// Values are stored in atomics, so reading and writing them never blocks or allocates. The audio
+/// thread reads values with `get` and writes values which change outside of the UI, such as host
+/// automation or meter levels, with `set`. Bound widgets pick up those changes once per frame. Edits
+/// made in the UI update the value and are queued as `ParamGesture`s, which the audio thread drains
+/// with `pop_gesture` to notify the host. The queue has a single producer, the UI, and must have a
+/// single consumer.
+///
+/// # Examples
+/// ```ignore
+/// let params = ParamStore::new(vec![
+///     ParamInfo::new("Cutoff", 20.0, 20000.0, 1000.0),
+///     ParamInfo::new("Output", 0.0, 1.0, 0.0),
+/// ]);
+///
+/// // UI thread
+/// state.set_param_store(params.clone());
+/// state.bind_param(cutoff_knob, CUTOFF);
+/// state.bind_param_with(meter, OUTPUT, |level| Event::new(AudioLevelEvent::SetLevel(level)));
+///
+/// // Audio thread
+/// while let Some(gesture) = params.pop_gesture() {
+///     host.notify(gesture);
+/// }
+/// params.set(OUTPUT, peak);
+/// let cutoff = params.get(CUTOFF);
+/// ```
+#[derive(Clone)]
+pub struct ParamStore {
+    inner: Arc<ParamStoreInner>,
+}
+
+impl ParamStore {
+    /// Creates a store with a gesture queue which holds 1024 gestures
+    pub fn new(params: Vec<ParamInfo>) -> Self {
+        Self::with_gesture_capacity(params, 1024)
+    }
+
+    /// Creates a store with a gesture queue of the given size
+    ///
+    /// Gestures pushed while the queue is full are dropped, but the value of the parameter is still set.
+    pub fn with_gesture_capacity(params: Vec<ParamInfo>, capacity: usize) -> Self {
+        ParamStore {
+            inner: Arc::new(ParamStoreInner {
+                params: params
+                    .into_iter()
+                    .map(|info| Param {
+                        value: AtomicU32::new(info.default.to_bits()),
+                        changed: AtomicBool::new(false),
+                        info,
+                    })
+                    .collect(),
+                gestures: GestureQueue::new(capacity),
+                pending: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Returns the number of parameters
+    pub fn len(&self) -> usize {
+        self.inner.params.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.params.is_empty()
+    }
+
+    pub fn info(&self, id: ParamId) -> Option<&ParamInfo> {
+        self.inner.params.get(id).map(|param| &param.info)
+    }
+
+    /// Returns the value of a parameter, or 0.0 if there is no parameter with the id
+    pub fn get(&self, id: ParamId) -> f32 {
+        match self.inner.params.get(id) {
+            Some(param) => f32::from_bits(param.value.load(Ordering::Acquire)),
+            None => 0.0,
+        }
+    }
+
+    /// Sets the value of a parameter from outside the UI, e.g. from host automation
+    ///
+    /// The value is clamped to the range of the parameter and widgets bound to it are updated on
+    /// the next frame.
+    pub fn set(&self, id: ParamId, value: f32) {
+        if let Some(param) = self.inner.params.get(id) {
+            let value = value.max(param.info.min).min(param.info.max);
+
+            param.value.store(value.to_bits(), Ordering::Release);
+            param.changed.store(true, Ordering::Release);
+
+            // Only a flag is set here, the UI picks the change up when it next polls the store
+            self.inner.pending.store(true, Ordering::Release);
+        }
+    }
+
+    /// Returns the oldest gesture made in the UI which hasn't been handled yet
+    pub fn pop_gesture(&self) -> Option<ParamGesture> {
+        self.inner.gestures.pop()
+    }
+
+    // Sets a value edited in the UI without marking it as changed
+    fn set_from_ui(&self, id: ParamId, value: f32) -> f32 {
+        match self.inner.params.get(id) {
+            Some(param) => {
+                let value = value.max(param.info.min).min(param.info.max);
+                param.value.store(value.to_bits(), Ordering::Release);
+                value
+            }
+
+            None => value,
+        }
+    }
+
+    // Returns the value of a parameter if it was changed outside of the UI since the last call
+    fn take_changed(&self, id: ParamId) -> Option<f32> {
+        let param = self.inner.params.get(id)?;
+
+        if param.changed.swap(false, Ordering::AcqRel) {
+            Some(f32::from_bits(param.value.load(Ordering::Acquire)))
+        } else {
+            None
+        }
+    }
+
+    // Returns true if any parameter was changed outside of the UI since the last call
+    fn take_pending(&self) -> bool {
+        self.inner.pending.swap(false, Ordering::AcqRel)
+    }
+
+    // Marks the store as having changes which the UI hasn't picked up yet
+    fn keep_pending(&self) {
+        self.inner.pending.store(true, Ordering::Release);
+    }
+
+    fn push_gesture(&self, gesture: ParamGesture) -> bool {
+        self.inner.gestures.push(gesture)
+    }
+}
+
+struct ParamBinding {
+    id: ParamId,
+    message: Box<dyn Fn(f32) -> Event + Send>,
+    // Changes from the audio thread are held back while the widget is being edited
+    editing: bool,
+}
+
+/// The bindings between widgets and the parameters of a `ParamStore`
+pub struct Params {
+    /// Time between polls of the parameter store for changes made outside of the UI
+    pub poll_interval: Duration,
+
+    store: Option<ParamStore>,
+    bindings: FnvHashMap<Entity, ParamBinding>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Params {
+            poll_interval: Duration::from_millis(16),
+
+            store: None,
+            bindings: FnvHashMap::default(),
+        }
+    }
+
+    /// Returns the parameter store shared with the audio thread
+    pub fn store(&self) -> Option<&ParamStore> {
+        self.store.as_ref()
+    }
+
+    /// Returns the parameter bound to an entity
+    pub fn binding(&self, entity: Entity) -> Option<ParamId> {
+        self.bindings.get(&entity).map(|binding| binding.id)
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.bindings.remove(&entity);
+    }
+}
+
+impl State {
+    /// Sets the parameter store which bound widgets read from and write to
+    pub fn set_param_store(&mut self, store: ParamStore) {
+        self.params.store = Some(store);
+
+        for entity in self.params.bindings.keys().cloned().collect::<Vec<_>>() {
+            self.send_param_value(entity);
+        }
+    }
+
+    /// Binds a value widget, such as a knob or slider, to a parameter
+    ///
+    /// Changes to the parameter are sent to the widget as `SliderEvent::SetValue`.
+    pub fn bind_param(&mut self, entity: Entity, id: ParamId) {
+        self.bind_param_with(entity, id, |value| Event::new(SliderEvent::SetValue(value)));
+    }
+
+    /// Binds a widget to a parameter with a function which creates the event sent when it changes
+    pub fn bind_param_with<F>(&mut self, entity: Entity, id: ParamId, message: F)
+    where
+        F: 'static + Fn(f32) -> Event + Send,
+    {
+        self.params.bindings.insert(
+            entity,
+            ParamBinding {
+                id,
+                message: Box::new(message),
+                editing: false,
+            },
+        );
+
+        self.send_param_value(entity);
+    }
+
+    pub fn unbind_param(&mut self, entity: Entity) {
+        self.params.bindings.remove(&entity);
+    }
+
+    /// Notifies the audio thread that the user started editing the parameter bound to an entity
+    ///
+    /// The parameter of the nearest bound ancestor is used if the entity isn't bound, so widgets
+    /// made of other value widgets can be bound as a whole.
+    pub fn begin_param_edit(&mut self, entity: Entity) {
+        let entity = self.param_owner(entity);
+
+        if let (Some(store), Some(binding)) =
+            (&self.params.store, self.params.bindings.get_mut(&entity))
+        {
+            binding.editing = true;
+            store.push_gesture(ParamGesture::Begin(binding.id));
+        }
+    }
+
+    /// Sets the parameter bound to an entity to a value edited in the UI
+    ///
+    /// Other widgets bound to the same parameter are updated.
+    pub fn perform_param_edit(&mut self, entity: Entity, value: f32) {
+        let entity = self.param_owner(entity);
+
+        let (id, value) = match (&self.params.store, self.params.bindings.get(&entity)) {
+            (Some(store), Some(binding)) => {
+                let value = store.set_from_ui(binding.id, value);
+                store.push_gesture(ParamGesture::Perform(binding.id, value));
+                (binding.id, value)
+            }
+
+            _ => return,
+        };
+
+        let others = self
+            .params
+            .bindings
+            .iter()
+            .filter(|(other, binding)| **other != entity && binding.id == id)
+            .map(|(other, binding)| {
+                let mut event = (binding.message)(value);
+                event.target = *other;
+                event
+            })
+            .collect::<Vec<_>>();
+
+        for event in others {
+            self.insert_event(event);
+        }
+    }
+
+    /// Notifies the audio thread that the user finished editing the parameter bound to an entity
+    pub fn end_param_edit(&mut self, entity: Entity) {
+        let entity = self.param_owner(entity);
+
+        if let (Some(store), Some(binding)) =
+            (&self.params.store, self.params.bindings.get_mut(&entity))
+        {
+            binding.editing = false;
+            store.push_gesture(ParamGesture::End(binding.id));
+        }
+    }
+
+    /// Sends the parameters changed outside of the UI to their bound widgets
+    ///
+    /// Called by the window once per frame. Returns the time of the next poll while any widget is
+    /// bound, so changes made while the UI is idle are picked up without the audio thread waking it.
+    /// A change to a parameter which is being edited is held back until the edit ends.
+    pub fn update_params(&mut self) -> Option<Instant> {
+        let store = self.params.store.clone()?;
+
+        if self.params.bindings.is_empty() {
+            return None;
+        }
+
+        let next_poll = Instant::now() + self.params.poll_interval;
+
+        if !store.take_pending() {
+            return Some(next_poll);
+        }
+
+        let mut ids = self
+            .params
+            .bindings
+            .values()
+            .map(|binding| binding.id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+
+        let mut events = Vec::new();
+        let mut held = false;
+
+        for id in ids {
+            // The changed flag stays set while the parameter is edited, so the change is picked up afterwards
+            if self
+                .params
+                .bindings
+                .values()
+                .any(|binding| binding.id == id && binding.editing)
+            {
+                held = true;
+                continue;
+            }
+
+            if let Some(value) = store.take_changed(id) {
+                for (entity, binding) in self.params.bindings.iter() {
+                    if binding.id == id {
+                        let mut event = (binding.message)(value);
+                        event.target = *entity;
+                        events.push(event);
+                    }
+                }
+            }
+        }
+
+        for event in events {
+            self.insert_event(event);
+        }
+
+        if held {
+            store.keep_pending();
+        }
+
+        Some(next_poll)
+    }
+
+    // Returns the nearest ancestor of an entity, including itself, which is bound to a parameter
+    fn param_owner(&self, entity: Entity) -> Entity {
+        entity
+            .parent_iter(&self.hierarchy)
+            .find(|ancestor| self.params.bindings.contains_key(ancestor))
+            .unwrap_or(entity)
+    }
+
+    // Sends the current value of the bound parameter to an entity
+    fn send_param_value(&mut self, entity: Entity) {
+        let event = match (&self.params.store, self.params.bindings.get(&entity)) {
+            (Some(store), Some(binding)) => {
+                let mut event = (binding.message)(store.get(binding.id));
+                event.target = entity;
+                event
+            }
+
+            _ => return,
+        };
+
+        self.insert_event(event);
+    }
+}
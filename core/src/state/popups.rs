@@ -0,0 +1,262 @@
+use crate::{
+    Display, Entity, Event, HierarchyTree, Length, Position, Propagation, PropSet, State,
+    WindowEvent,
+};
+
+/// The z order of the first open popup, popups opened on top of it are placed one higher each
+pub const POPUP_Z_ORDER: i32 = 100;
+
+/// What a popup is positioned against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupAnchor {
+    // The bounds of an entity, e.g. the header of a dropdown
+    Entity(Entity),
+    // A point in window coordinates, e.g. the cursor position for a context menu
+    Point(f32, f32),
+}
+
+/// The side of the anchor a popup is placed on
+///
+/// `Below` and `Above` align the popup with the left edge of the anchor, `Right` and `Left` align
+/// it with the top edge. If the popup doesn't fit in the window on the preferred side it flips to
+/// the opposite side, and it is then shifted to keep it inside the window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopupPlacement {
+    Below,
+    Above,
+    Right,
+    Left,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PopupEvent {
+    // Sent to a popup after it has been closed, propagates up so the widget which opened it is notified
+    Closed,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct OpenPopup {
+    popup: Entity,
+    anchor: PopupAnchor,
+    placement: PopupPlacement,
+    // The entity which was focused when the popup was opened
+    restore: Entity,
+}
+
+/// The overlay layer of open popups, such as menus, dropdown lists and tooltips
+///
+/// Popups stay in the hierarchy where they were built but are drawn above everything else and are
+/// not clipped by their ancestors. The layer is a stack, closing a popup also closes any popups
+/// which were opened after it, such as submenus.
+pub struct Popups {
+    open: Vec<OpenPopup>,
+}
+
+impl Popups {
+    pub fn new() -> Self {
+        Popups { open: Vec::new() }
+    }
+
+    /// Returns true if the popup is currently open
+    pub fn is_open(&self, popup: Entity) -> bool {
+        self.open.iter().any(|open| open.popup == popup)
+    }
+
+    /// Returns the most recently opened popup
+    pub fn top(&self) -> Option<Entity> {
+        self.open.last().map(|open| open.popup)
+    }
+
+    /// Returns the number of open popups
+    pub fn len(&self) -> usize {
+        self.open.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.open.is_empty()
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.open.retain(|open| open.popup != entity);
+    }
+}
+
+impl State {
+    /// Opens a popup above all other widgets, positioned against an anchor
+    /// The popup is sent `PopupEvent::Closed` when it's dismissed or closed with `close_popup`.
+    pub fn open_popup(&mut self, popup: Entity, anchor: PopupAnchor, placement: PopupPlacement) {
+        if let Some(open) = self.popups.open.iter_mut().find(|open| open.popup == popup) {
+            open.anchor = anchor;
+            open.placement = placement;
+
+            self.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+            return;
+        }
+
+        let z_order = POPUP_Z_ORDER + self.popups.open.len() as i32;
+
+        self.popups.open.push(OpenPopup {
+            popup,
+            anchor,
+            placement,
+            restore: self.focused,
+        });
+
+        // The popup is placed against its anchor by the window after the next layout
+        popup
+            .set_position(self, Position::Absolute)
+            .set_clip_widget(self, Entity::root())
+            .set_z_order(self, z_order)
+            .set_display(self, Display::Flexbox);
+
+        self.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+    }
+
+    /// Closes a popup along with any popups opened after it
+    pub fn close_popup(&mut self, popup: Entity) {
+        if let Some(index) = self.popups.open.iter().position(|open| open.popup == popup) {
+            self.close_popups_from(index);
+        }
+    }
+
+    /// Closes all open popups
+    pub fn close_all_popups(&mut self) {
+        self.close_popups_from(0);
+    }
+
+    // Closes the popups which don't contain the pressed entity. A press on the anchor of a popup
+    // keeps it open so the widget which owns the popup can toggle it.
+    pub(crate) fn dismiss_popups(&mut self, pressed: Entity) {
+        let keep = self.popups.open.iter().rposition(|open| {
+            pressed.is_descendant_of(&self.hierarchy, open.popup)
+                || match open.anchor {
+                    PopupAnchor::Entity(anchor) => pressed.is_descendant_of(&self.hierarchy, anchor),
+                    PopupAnchor::Point(_, _) => false,
+                }
+        });
+
+        match keep {
+            Some(index) => self.close_popups_from(index + 1),
+            None => self.close_all_popups(),
+        }
+    }
+
+    fn close_popups_from(&mut self, index: usize) {
+        while self.popups.open.len() > index {
+            let open = self.popups.open.pop().unwrap();
+
+            open.popup.set_display(self, Display::None);
+
+            if self.focused.is_descendant_of(&self.hierarchy, open.popup) {
+                self.set_focus(open.restore);
+            }
+
+            self.insert_event(
+                Event::new(PopupEvent::Closed)
+                    .target(open.popup)
+                    .origin(open.popup)
+                    .propagate(Propagation::Up),
+            );
+        }
+
+        self.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+    }
+
+    /// Positions the open popups against their anchors
+    ///
+    /// Called by the window after layout, since a popup can only be placed once its size is known.
+    /// Returns true if any popup moved, in which case layout needs to run again.
+    pub fn place_popups(&mut self) -> bool {
+        let window_width = self.data.get_width(Entity::root());
+        let window_height = self.data.get_height(Entity::root());
+
+        let mut moved = false;
+
+        for open in self.popups.open.clone().iter() {
+            let popup = open.popup;
+
+            let width = self.data.get_width(popup);
+            let height = self.data.get_height(popup);
+
+            let anchor = self.anchor_bounds(open.anchor);
+
+            let (mut x, mut y) = match open.placement {
+                PopupPlacement::Below | PopupPlacement::Above => {
+                    let below = anchor.y + anchor.h;
+                    let above = anchor.y - height;
+
+                    let fits_below = below + height <= window_height;
+                    let fits_above = above >= 0.0;
+
+                    let y = match open.placement {
+                        PopupPlacement::Below if !fits_below && fits_above => above,
+                        PopupPlacement::Above if !fits_above && fits_below => below,
+                        PopupPlacement::Below => below,
+                        _ => above,
+                    };
+
+                    (anchor.x, y)
+                }
+
+                PopupPlacement::Right | PopupPlacement::Left => {
+                    let right = anchor.x + anchor.w;
+                    let left = anchor.x - width;
+
+                    let fits_right = right + width <= window_width;
+                    let fits_left = left >= 0.0;
+
+                    let x = match open.placement {
+                        PopupPlacement::Right if !fits_right && fits_left => left,
+                        PopupPlacement::Left if !fits_left && fits_right => right,
+                        PopupPlacement::Right => right,
+                        _ => left,
+                    };
+
+                    (x, anchor.y)
+                }
+            };
+
+            // Shift the popup back inside the window
+            x = x.min(window_width - width).max(0.0);
+            y = y.min(window_height - height).max(0.0);
+
+            let parent = popup.parent(&self.hierarchy).unwrap_or(Entity::root());
+
+            let left = Length::Pixels(x - self.data.get_posx(parent));
+            let top = Length::Pixels(y - self.data.get_posy(parent));
+
+            if self.style.left.get(popup) != Some(&left) {
+                self.style.left.insert(popup, left);
+                moved = true;
+            }
+
+            if self.style.top.get(popup) != Some(&top) {
+                self.style.top.insert(popup, top);
+                moved = true;
+            }
+        }
+
+        moved
+    }
+
+    fn anchor_bounds(&self, anchor: PopupAnchor) -> Bounds {
+        match anchor {
+            PopupAnchor::Entity(entity) => Bounds {
+                x: self.data.get_posx(entity),
+                y: self.data.get_posy(entity),
+                w: self.data.get_width(entity),
+                h: self.data.get_height(entity),
+            },
+
+            PopupAnchor::Point(x, y) => Bounds { x, y, w: 0.0, h: 0.0 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
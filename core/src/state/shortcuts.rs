@@ -0,0 +1,477 @@
+use crate::entity::Entity;
+use crate::events::Event;
+use crate::state::hierarchy::{Hierarchy, IntoParentIterator};
+use crate::state::mouse::ModifiersState;
+
+use keyboard_types::Code;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShortcutError {
+    // The shortcut string contains no keys
+    Empty,
+    // A key name in the shortcut string could not be recognised
+    UnknownKey(String),
+    // The shortcut clashes with an existing binding in the same scope
+    Conflict(String),
+}
+
+impl std::fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ShortcutError::Empty => write!(f, "Empty shortcut"),
+            ShortcutError::UnknownKey(key) => write!(f, "Unknown key: {}", key),
+            ShortcutError::Conflict(shortcut) => {
+                write!(f, "Shortcut conflicts with existing binding: {}", shortcut)
+            }
+        }
+    }
+}
+
+/// A single key press combined with modifier keys, e.g. "Ctrl+Shift+P"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeyChord {
+    pub modifiers: ModifiersState,
+    pub code: Code,
+}
+
+impl KeyChord {
+    pub fn new(modifiers: ModifiersState, code: Code) -> Self {
+        KeyChord { modifiers, code }
+    }
+
+    /// Parses a chord from a string of '+' separated modifiers followed by a key
+    pub fn parse(chord: &str) -> Result<Self, ShortcutError> {
+        let mut modifiers = ModifiersState::default();
+        let mut code = None;
+
+        for part in chord.split('+').map(|part| part.trim()) {
+            if part.is_empty() {
+                return Err(ShortcutError::UnknownKey(chord.to_string()));
+            }
+
+            match part.to_lowercase().as_ref() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "logo" | "super" | "cmd" | "meta" | "win" => modifiers.logo = true,
+                _ => {
+                    if code.is_some() {
+                        return Err(ShortcutError::UnknownKey(part.to_string()));
+                    }
+
+                    code = Some(
+                        parse_code(part).ok_or(ShortcutError::UnknownKey(part.to_string()))?,
+                    );
+                }
+            }
+        }
+
+        if let Some(code) = code {
+            Ok(KeyChord { modifiers, code })
+        } else {
+            Err(ShortcutError::Empty)
+        }
+    }
+}
+
+impl std::fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+
+        if self.modifiers.logo {
+            write!(f, "Logo+")?;
+        }
+
+        write!(f, "{}", code_name(self.code))
+    }
+}
+
+/// A key chord or a sequence of key chords separated by spaces, e.g. "Ctrl+K Ctrl+C"
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shortcut(pub Vec<KeyChord>);
+
+impl Shortcut {
+    pub fn parse(shortcut: &str) -> Result<Self, ShortcutError> {
+        let chords = shortcut
+            .split_whitespace()
+            .map(|chord| KeyChord::parse(chord))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if chords.is_empty() {
+            return Err(ShortcutError::Empty);
+        }
+
+        Ok(Shortcut(chords))
+    }
+
+    // Returns true if one of the shortcuts is the start of the other
+    fn overlaps(&self, other: &Shortcut) -> bool {
+        self.0.iter().zip(other.0.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl std::fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (index, chord) in self.0.iter().enumerate() {
+            if index != 0 {
+                write!(f, " ")?;
+            }
+
+            write!(f, "{}", chord)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ShortcutBinding {
+    pub shortcut: Shortcut,
+    // The binding is active while the focused entity is this entity or one of its descendants
+    pub scope: Entity,
+    pub event: Event,
+}
+
+// The result of passing a key press to the shortcut registry
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShortcutMatch {
+    // The key press completed a shortcut
+    Matched(Event),
+    // The key press is part of a longer shortcut sequence
+    Pending,
+    // The key press did not match any shortcut
+    None,
+}
+
+/// The application-wide registry of keyboard shortcuts
+///
+/// Shortcuts are bound to an event and a scope entity. When a key is pressed the bindings of the scopes
+/// containing the focused entity are searched from the innermost scope outwards.
+///
+/// # Examples
+/// ```ignore
+/// state.shortcuts.bind("Ctrl+Shift+P", Entity::root(), Event::new(AppEvent::OpenPalette))?;
+/// state.shortcuts.bind("Ctrl+K Ctrl+C", editor, Event::new(EditorEvent::Comment))?;
+/// ```
+pub struct Shortcuts {
+    bindings: Vec<ShortcutBinding>,
+    // Chords of a partially entered shortcut sequence
+    pending: Vec<KeyChord>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        Shortcuts {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Binds a shortcut to an event within the subtree of the scope entity
+    ///
+    /// Use `Entity::root()` as the scope for application-wide shortcuts. If the event has no target
+    /// it is sent to the scope entity. Returns an error if the shortcut cannot be parsed or if it
+    /// conflicts with another shortcut bound to the same scope.
+    pub fn bind(&mut self, shortcut: &str, scope: Entity, event: Event) -> Result<(), ShortcutError> {
+        let shortcut = Shortcut::parse(shortcut)?;
+
+        if let Some(conflict) = self.conflicts(&shortcut, scope).first() {
+            return Err(ShortcutError::Conflict(conflict.shortcut.to_string()));
+        }
+
+        self.bindings.push(ShortcutBinding {
+            shortcut,
+            scope,
+            event,
+        });
+
+        Ok(())
+    }
+
+    /// Removes a shortcut from a scope
+    pub fn unbind(&mut self, shortcut: &str, scope: Entity) -> Result<(), ShortcutError> {
+        let shortcut = Shortcut::parse(shortcut)?;
+
+        self.bindings
+            .retain(|binding| binding.scope != scope || binding.shortcut != shortcut);
+
+        Ok(())
+    }
+
+    /// Removes all of the shortcuts bound to a scope
+    pub fn unbind_scope(&mut self, scope: Entity) {
+        self.bindings.retain(|binding| binding.scope != scope);
+    }
+
+    /// Returns the bindings in the same scope which would clash with the shortcut
+    ///
+    /// Two shortcuts clash if they are the same or if one is the start of the other sequence.
+    pub fn conflicts(&self, shortcut: &Shortcut, scope: Entity) -> Vec<&ShortcutBinding> {
+        self.bindings
+            .iter()
+            .filter(|binding| binding.scope == scope && binding.shortcut.overlaps(shortcut))
+            .collect()
+    }
+
+    /// Returns the accelerator text of the first shortcut bound to an event
+    pub fn shortcut_text(&self, event: &Event) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.event.message.equals_a(&*event.message))
+            .map(|binding| binding.shortcut.to_string())
+    }
+
+    pub fn bindings(&self) -> &[ShortcutBinding] {
+        &self.bindings
+    }
+
+    // Matches a key press against the bindings of the scopes containing the target entity
+    pub(crate) fn key_down(
+        &mut self,
+        hierarchy: &Hierarchy,
+        target: Entity,
+        modifiers: ModifiersState,
+        code: Code,
+    ) -> ShortcutMatch {
+        // Modifier keys on their own don't interrupt a sequence
+        if is_modifier(code) {
+            return ShortcutMatch::None;
+        }
+
+        if self.bindings.is_empty() {
+            return ShortcutMatch::None;
+        }
+
+        let chord = KeyChord::new(modifiers, code);
+
+        let mut sequence = self.pending.clone();
+        sequence.push(chord);
+
+        let scopes: Vec<Entity> = if target == Entity::null() {
+            vec![Entity::root()]
+        } else {
+            target.parent_iter(hierarchy).collect()
+        };
+
+        let result = self.find(&scopes, &sequence);
+
+        if result == ShortcutMatch::None && !self.pending.is_empty() {
+            // The sequence was broken so try the key press on its own
+            self.pending.clear();
+            return self.key_down(hierarchy, target, modifiers, code);
+        }
+
+        match &result {
+            ShortcutMatch::Pending => self.pending = sequence,
+            _ => self.pending.clear(),
+        }
+
+        result
+    }
+
+    fn find(&self, scopes: &[Entity], sequence: &[KeyChord]) -> ShortcutMatch {
+        let mut pending = false;
+
+        // Search from the innermost scope outwards
+        for scope in scopes.iter() {
+            for binding in self.bindings.iter().filter(|binding| binding.scope == *scope) {
+                let chords = &binding.shortcut.0;
+
+                if chords.as_slice() == sequence {
+                    let mut event = binding.event.clone();
+
+                    if event.target == Entity::null() {
+                        event.target = binding.scope;
+                    }
+
+                    return ShortcutMatch::Matched(event);
+                }
+
+                if chords.len() > sequence.len() && chords.starts_with(sequence) {
+                    pending = true;
+                }
+            }
+        }
+
+        if pending {
+            ShortcutMatch::Pending
+        } else {
+            ShortcutMatch::None
+        }
+    }
+}
+
+fn is_modifier(code: Code) -> bool {
+    match code {
+        Code::ShiftLeft
+        | Code::ShiftRight
+        | Code::ControlLeft
+        | Code::ControlRight
+        | Code::AltLeft
+        | Code::AltRight
+        | Code::MetaLeft
+        | Code::MetaRight => true,
+
+        _ => false,
+    }
+}
+
+const LETTERS: [Code; 26] = [
+    Code::KeyA,
+    Code::KeyB,
+    Code::KeyC,
+    Code::KeyD,
+    Code::KeyE,
+    Code::KeyF,
+    Code::KeyG,
+    Code::KeyH,
+    Code::KeyI,
+    Code::KeyJ,
+    Code::KeyK,
+    Code::KeyL,
+    Code::KeyM,
+    Code::KeyN,
+    Code::KeyO,
+    Code::KeyP,
+    Code::KeyQ,
+    Code::KeyR,
+    Code::KeyS,
+    Code::KeyT,
+    Code::KeyU,
+    Code::KeyV,
+    Code::KeyW,
+    Code::KeyX,
+    Code::KeyY,
+    Code::KeyZ,
+];
+
+const DIGITS: [Code; 10] = [
+    Code::Digit0,
+    Code::Digit1,
+    Code::Digit2,
+    Code::Digit3,
+    Code::Digit4,
+    Code::Digit5,
+    Code::Digit6,
+    Code::Digit7,
+    Code::Digit8,
+    Code::Digit9,
+];
+
+const FUNCTION_KEYS: [Code; 12] = [
+    Code::F1,
+    Code::F2,
+    Code::F3,
+    Code::F4,
+    Code::F5,
+    Code::F6,
+    Code::F7,
+    Code::F8,
+    Code::F9,
+    Code::F10,
+    Code::F11,
+    Code::F12,
+];
+
+// Converts a key name to a key code
+fn parse_code(key: &str) -> Option<Code> {
+    let mut chars = key.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if c.is_ascii_alphabetic() {
+            return Some(LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize]);
+        }
+
+        if c.is_ascii_digit() {
+            return Some(DIGITS[(c as u8 - b'0') as usize]);
+        }
+
+        return match c {
+            '-' => Some(Code::Minus),
+            '=' => Some(Code::Equal),
+            ',' => Some(Code::Comma),
+            '.' => Some(Code::Period),
+            '/' => Some(Code::Slash),
+            '\\' => Some(Code::Backslash),
+            ';' => Some(Code::Semicolon),
+            '\'' => Some(Code::Quote),
+            '[' => Some(Code::BracketLeft),
+            ']' => Some(Code::BracketRight),
+            '`' => Some(Code::Backquote),
+            _ => None,
+        };
+    }
+
+    let lower = key.to_lowercase();
+
+    if lower.starts_with('f') {
+        if let Ok(n) = lower[1..].parse::<usize>() {
+            if n >= 1 && n <= FUNCTION_KEYS.len() {
+                return Some(FUNCTION_KEYS[n - 1]);
+            }
+        }
+    }
+
+    match lower.as_ref() {
+        "esc" | "escape" => Some(Code::Escape),
+        "enter" | "return" => Some(Code::Enter),
+        "tab" => Some(Code::Tab),
+        "space" => Some(Code::Space),
+        "backspace" => Some(Code::Backspace),
+        "del" | "delete" => Some(Code::Delete),
+        "ins" | "insert" => Some(Code::Insert),
+        "home" => Some(Code::Home),
+        "end" => Some(Code::End),
+        "pageup" => Some(Code::PageUp),
+        "pagedown" => Some(Code::PageDown),
+        "up" | "arrowup" => Some(Code::ArrowUp),
+        "down" | "arrowdown" => Some(Code::ArrowDown),
+        "left" | "arrowleft" => Some(Code::ArrowLeft),
+        "right" | "arrowright" => Some(Code::ArrowRight),
+        "plus" => Some(Code::Equal),
+        "minus" => Some(Code::Minus),
+        "menu" | "contextmenu" => Some(Code::ContextMenu),
+        _ => None,
+    }
+}
+
+// Converts a key code to the name used in accelerator text
+fn code_name(code: Code) -> String {
+    if let Some(index) = LETTERS.iter().position(|c| *c == code) {
+        return ((b'A' + index as u8) as char).to_string();
+    }
+
+    if let Some(index) = DIGITS.iter().position(|c| *c == code) {
+        return index.to_string();
+    }
+
+    match code {
+        Code::Escape => "Esc".to_string(),
+        Code::Delete => "Del".to_string(),
+        Code::ArrowUp => "Up".to_string(),
+        Code::ArrowDown => "Down".to_string(),
+        Code::ArrowLeft => "Left".to_string(),
+        Code::ArrowRight => "Right".to_string(),
+        Code::Minus => "-".to_string(),
+        Code::Equal => "=".to_string(),
+        Code::Comma => ",".to_string(),
+        Code::Period => ".".to_string(),
+        Code::Slash => "/".to_string(),
+        Code::Backslash => "\\".to_string(),
+        Code::Semicolon => ";".to_string(),
+        Code::Quote => "'".to_string(),
+        Code::BracketLeft => "[".to_string(),
+        Code::BracketRight => "]".to_string(),
+        Code::Backquote => "`".to_string(),
+        _ => code.to_string(),
+    }
+}
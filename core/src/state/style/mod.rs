@@ -137,6 +137,8 @@ pub struct Style {
     pub clip_widget: DenseStorage<Entity>,
 
     pub focus_order: DenseStorage<FocusOrder>,
+    // Position in the keyboard focus order (see `State::focus_next`)
+    pub tab_index: DenseStorage<i32>,
 
     // Flexbox
     pub align_self: StyleStorage<AlignSelf>,
@@ -173,6 +175,12 @@ pub struct Style {
 
     pub text_align: StyleStorage<Align>,
     pub text_justify: StyleStorage<Justify>,
+
+    // Knob Arcs
+    pub arc_start_angle: StyleStorage<f32>,
+    pub arc_end_angle: StyleStorage<f32>,
+    pub arc_width: StyleStorage<Length>,
+    pub arc_center: StyleStorage<f32>,
 }
 
 impl Style {
@@ -259,6 +267,7 @@ impl Style {
             visibility: StyleStorage::new(),
             clip_widget: DenseStorage::new(),
             focus_order: DenseStorage::new(),
+            tab_index: DenseStorage::new(),
 
             // Box Shadow
             shadow_h_offset: AnimatableStorage::new(),
@@ -282,6 +291,11 @@ impl Style {
             //size_constraints: DenseStorage::new(),
             text: DenseStorage::new(),
             tooltip: DenseStorage::new(),
+
+            arc_start_angle: StyleStorage::new(),
+            arc_end_angle: StyleStorage::new(),
+            arc_width: StyleStorage::new(),
+            arc_center: StyleStorage::new(),
         }
     }
 
@@ -505,6 +519,23 @@ impl Style {
                         self.z_order.insert_rule(rule_id, value);
                     }
 
+                    // Knob Arcs
+                    Property::ArcStartAngle(value) => {
+                        self.arc_start_angle.insert_rule(rule_id, value);
+                    }
+
+                    Property::ArcEndAngle(value) => {
+                        self.arc_end_angle.insert_rule(rule_id, value);
+                    }
+
+                    Property::ArcWidth(value) => {
+                        self.arc_width.insert_rule(rule_id, value);
+                    }
+
+                    Property::ArcCenter(value) => {
+                        self.arc_center.insert_rule(rule_id, value);
+                    }
+
                     Property::BoxShadow(box_shadow) => {
                         self.shadow_h_offset
                             .insert_rule(rule_id, box_shadow.horizontal_offset);
@@ -1,5 +1,5 @@
 use crate::state::style::*;
-use crate::State;
+use crate::{Role, State};
 use crate::{entity::Entity, BuildHandler, Builder, EventHandler, Propagation};
 
 use crate::{Event, WindowEvent};
@@ -21,6 +21,7 @@ pub trait PropSet {
     fn is_over(self, state: &mut State) -> bool;
     fn is_active(self, state: &mut State) -> bool;
     fn is_focused(self, state: &mut State) -> bool;
+    fn is_invalid(self, state: &mut State) -> bool;
 
     // Pseudoclass
     fn set_enabled(self, state: &mut State, value: bool) -> Self;
@@ -30,6 +31,7 @@ pub trait PropSet {
     fn set_active(self, state: &mut State, value: bool) -> Self;
     fn set_hover(self, state: &mut State, value: bool) -> Self;
     fn set_focus(self, state: &mut State, value: bool) -> Self;
+    fn set_invalid(self, state: &mut State, value: bool) -> Self;
 
     // Style
     fn set_element(self, state: &mut State, value: &str) -> Self;
@@ -140,6 +142,12 @@ pub trait PropSet {
     fn set_next_focus(self, state: &mut State, value: Entity) -> Self;
     fn set_prev_focus(self, state: &mut State, value: Entity) -> Self;
     fn set_focus_order(self, state: &mut State, next: Entity, prev: Entity) -> Self;
+    fn set_tab_index(self, state: &mut State, value: i32) -> Self;
+
+    // Accessibility
+    fn set_role(self, state: &mut State, value: Role) -> Self;
+    fn set_accessible_name(self, state: &mut State, value: &str) -> Self;
+    fn set_accessible_value(self, state: &mut State, value: &str) -> Self;
 
     fn mutate<F: FnMut(Builder) -> Builder>(self, state: &mut State, builder: F) -> Self;
 
@@ -242,6 +250,13 @@ impl PropSet for Entity {
             false
         }
     }
+    fn is_invalid(self, state: &mut State) -> bool {
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(self) {
+            pseudo_classes.get_invalid()
+        } else {
+            false
+        }
+    }
 
     // PseudoClass
     fn set_enabled(self, state: &mut State, value: bool) -> Self {
@@ -384,6 +399,25 @@ impl PropSet for Entity {
         self
     }
 
+    fn set_invalid(self, state: &mut State, value: bool) -> Self {
+        if let Some(pseudo_classes) = state.style.pseudo_classes.get_mut(self) {
+            pseudo_classes.set_invalid(value);
+        }
+
+        state.insert_event(
+            Event::new(WindowEvent::Restyle)
+                .origin(self)
+                .target(Entity::root()),
+        );
+        state.insert_event(
+            Event::new(WindowEvent::Redraw)
+                .origin(self)
+                .target(Entity::root()),
+        );
+
+        self
+    }
+
     // Style
     fn set_element(self, state: &mut State, value: &str) -> Self {
         state.style.insert_element(self, value);
@@ -1135,6 +1169,30 @@ impl PropSet for Entity {
 
         self
     }
+
+    fn set_tab_index(self, state: &mut State, value: i32) -> Self {
+        state.style.tab_index.insert(self, value);
+
+        self
+    }
+
+    fn set_role(self, state: &mut State, value: Role) -> Self {
+        state.accessibility.role.insert(self, value);
+
+        self
+    }
+
+    fn set_accessible_name(self, state: &mut State, value: &str) -> Self {
+        state.accessibility.name.insert(self, value.to_string());
+
+        self
+    }
+
+    fn set_accessible_value(self, state: &mut State, value: &str) -> Self {
+        state.accessibility.value.insert(self, value.to_string());
+
+        self
+    }
 }
 
 pub trait PropGet {
@@ -87,4 +87,10 @@ pub enum Property {
     Transition(Vec<Transition>),
 
     ZIndex(i32),
+
+    // Knob arcs
+    ArcStartAngle(f32),
+    ArcEndAngle(f32),
+    ArcWidth(Length),
+    ArcCenter(f32),
 }
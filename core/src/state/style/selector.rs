@@ -26,10 +26,11 @@ use crate::Specificity;
 // 4 - Enabled
 // 5 - Disabled
 // 6 - Checked
-// 7 - Unassigned
+// 7 - Invalid
+// 8 - FocusWithin
 
 #[derive(Debug, Clone)]
-pub struct PseudoClasses(u8);
+pub struct PseudoClasses(u16);
 
 impl Default for PseudoClasses {
     fn default() -> Self {
@@ -98,6 +99,22 @@ impl PseudoClasses {
         }
     }
 
+    pub fn set_invalid(&mut self, flag: bool) {
+        if flag {
+            self.0 |= (1 << 7);
+        } else {
+            self.0 &= !(1 << 7);
+        }
+    }
+
+    pub fn set_focus_within(&mut self, flag: bool) {
+        if flag {
+            self.0 |= (1 << 8);
+        } else {
+            self.0 &= !(1 << 8);
+        }
+    }
+
     pub fn get_hover(&mut self) -> bool {
         (self.0 & 1) != 0
     }
@@ -125,6 +142,14 @@ impl PseudoClasses {
     pub fn get_checked(&mut self) -> bool {
         (self.0 & (1 << 6)) >> 6 != 0
     }
+
+    pub fn get_invalid(&mut self) -> bool {
+        (self.0 & (1 << 7)) >> 7 != 0
+    }
+
+    pub fn get_focus_within(&mut self) -> bool {
+        (self.0 & (1 << 8)) >> 8 != 0
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -223,6 +223,8 @@ fn parse_selectors<'i, 't>(
                     "enabled" => selector.pseudo_classes.set_enabled(true),
                     "disabled" => selector.pseudo_classes.set_disabled(true),
                     "checked" => selector.pseudo_classes.set_checked(true),
+                    "invalid" => selector.pseudo_classes.set_invalid(true),
+                    "focus-within" => selector.pseudo_classes.set_focus_within(true),
 
                     _ => {}
                 }
@@ -390,6 +392,12 @@ impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
 
             "z-index" => Property::ZIndex(parse_z_index(input)?),
 
+            // Knob arcs
+            "arc-start-angle" => Property::ArcStartAngle(parse_angle(input)?),
+            "arc-end-angle" => Property::ArcEndAngle(parse_angle(input)?),
+            "arc-width" => Property::ArcWidth(parse_length(input)?),
+            "arc-center" => Property::ArcCenter(parse_length_or_percentage(input)?),
+
             _ => {
                 let basic_error = BasicParseError {
                     kind: BasicParseErrorKind::UnexpectedToken(input.next()?.to_owned()),
@@ -515,6 +523,37 @@ fn parse_z_index<'i, 't>(
     })
 }
 
+// Parses an angle in degrees, which may be given in deg, rad or turn units
+fn parse_angle<'i, 't>(
+    input: &mut Parser<'i, 't>,
+) -> Result<f32, ParseError<'i, CustomParseError>> {
+    let location = input.current_source_location();
+
+    Ok(match input.next()? {
+        Token::Number { value: x, .. } => *x as f32,
+
+        Token::Dimension {
+            value: x, unit, ..
+        } if unit.eq_ignore_ascii_case("deg") => *x as f32,
+
+        Token::Dimension {
+            value: x, unit, ..
+        } if unit.eq_ignore_ascii_case("rad") => (*x as f32).to_degrees(),
+
+        Token::Dimension {
+            value: x, unit, ..
+        } if unit.eq_ignore_ascii_case("turn") => *x as f32 * 360.0,
+
+        t => {
+            let basic_error = BasicParseError {
+                kind: BasicParseErrorKind::UnexpectedToken(t.to_owned()),
+                location,
+            };
+            return Err(basic_error.into());
+        }
+    })
+}
+
 //TODO
 // fn parse_transition<'i, 't>(
 //     input: &mut Parser<'i, 't>,
@@ -0,0 +1,210 @@
+use crate::widgets::Tooltip;
+use crate::{BuildHandler, Entity, IntoParentIterator, PopupAnchor, PopupPlacement, PropSet, State};
+
+use fnv::FnvHashMap;
+
+use std::time::{Duration, Instant};
+
+// Builds the content of a rich tooltip into the tooltip entity
+type TooltipContent = Box<dyn Fn(&mut State, Entity) + Send>;
+
+/// Shows the tooltip of the widget under the cursor after a hover delay
+///
+/// A widget has a tooltip if it has tooltip text, set with `set_tooltip`, or a content builder set
+/// with `state.set_tooltip_content`. Widgets without a tooltip show the tooltip of their nearest
+/// ancestor which has one. The tooltip is hidden when the cursor leaves the widget, and when the
+/// mouse is pressed or scrolled it stays hidden until the cursor moves to another widget.
+///
+/// Tooltip entities are reused. Every widget with tooltip text shares one tooltip whose text is
+/// changed when it's shown, and a rich tooltip is built once per widget and kept until the widget
+/// is removed.
+pub struct Tooltips {
+    /// Time the cursor must rest on a widget before its tooltip is shown
+    pub delay: Duration,
+    /// Offset of the tooltip from the cursor
+    pub offset: (f32, f32),
+
+    content: FnvHashMap<Entity, TooltipContent>,
+
+    // The widget under the cursor whose tooltip is pending or shown
+    owner: Entity,
+    hovered_at: Instant,
+    // Set by a click or scroll, cleared when the owner changes
+    suppressed: bool,
+    // The tooltip being shown
+    tooltip: Entity,
+    // The tooltip shared by every widget with tooltip text
+    text_tooltip: Entity,
+    // The tooltips built for widgets with rich content, keyed by widget
+    rich: FnvHashMap<Entity, Entity>,
+}
+
+impl Tooltips {
+    pub fn new() -> Self {
+        Tooltips {
+            delay: Duration::from_millis(500),
+            offset: (0.0, 20.0),
+
+            content: FnvHashMap::default(),
+
+            owner: Entity::null(),
+            hovered_at: Instant::now(),
+            suppressed: false,
+            tooltip: Entity::null(),
+            text_tooltip: Entity::null(),
+            rich: FnvHashMap::default(),
+        }
+    }
+
+    /// Returns the tooltip currently being shown
+    pub fn shown(&self) -> Option<Entity> {
+        if self.tooltip == Entity::null() {
+            None
+        } else {
+            Some(self.tooltip)
+        }
+    }
+
+    pub(crate) fn remove(&mut self, entity: Entity) {
+        self.content.remove(&entity);
+        self.rich.remove(&entity);
+        self.rich.retain(|_, tooltip| *tooltip != entity);
+
+        if self.tooltip == entity {
+            self.tooltip = Entity::null();
+        }
+
+        if self.text_tooltip == entity {
+            self.text_tooltip = Entity::null();
+        }
+    }
+
+    // Returns the rich tooltips of the given widgets, which are removed along with them
+    pub(crate) fn rich_tooltips(&self, entities: &[Entity]) -> Vec<Entity> {
+        entities
+            .iter()
+            .filter_map(|entity| self.rich.get(entity).cloned())
+            .collect()
+    }
+}
+
+impl State {
+    /// Sets a builder for rich tooltip content, called the first time the tooltip is shown
+    pub fn set_tooltip_content<F>(&mut self, entity: Entity, content: F)
+    where
+        F: 'static + Fn(&mut State, Entity) + Send,
+    {
+        self.tooltips.content.insert(entity, Box::new(content));
+
+        if let Some(tooltip) = self.tooltips.rich.remove(&entity) {
+            if self.tooltips.tooltip == tooltip {
+                self.hide_tooltip();
+            }
+
+            self.remove(tooltip);
+        }
+    }
+
+    /// Shows or hides the tooltip for the hovered widget
+    ///
+    /// Should be called by the backend once per iteration of the event loop. Returns the time at
+    /// which a pending tooltip is due so that the backend can wake up in time to show it.
+    pub fn update_tooltips(&mut self) -> Option<Instant> {
+        let owner = self.tooltip_owner(self.hovered);
+
+        if owner != self.tooltips.owner {
+            self.hide_tooltip();
+
+            self.tooltips.owner = owner;
+            self.tooltips.hovered_at = Instant::now();
+            self.tooltips.suppressed = false;
+        }
+
+        if owner == Entity::null() || self.tooltips.suppressed || self.tooltips.tooltip != Entity::null() {
+            return None;
+        }
+
+        let due = self.tooltips.hovered_at + self.tooltips.delay;
+
+        if due > Instant::now() {
+            return Some(due);
+        }
+
+        self.show_tooltip(owner);
+
+        None
+    }
+
+    /// Hides the tooltip until the cursor moves to another widget
+    ///
+    /// Called when the mouse is pressed or scrolled.
+    pub fn suppress_tooltip(&mut self) {
+        if self.tooltips.owner != Entity::null() {
+            self.tooltips.suppressed = true;
+        }
+
+        self.hide_tooltip();
+    }
+
+    fn show_tooltip(&mut self, owner: Entity) {
+        let tooltip = if let Some(tooltip) = self.tooltips.rich.get(&owner).cloned() {
+            tooltip
+        } else if let Some(content) = self.tooltips.content.remove(&owner) {
+            let tooltip = Tooltip::new("").build(self, Entity::root(), |builder| builder);
+            (content)(self, tooltip);
+            self.tooltips.content.insert(owner, content);
+            self.tooltips.rich.insert(owner, tooltip);
+            tooltip
+        } else {
+            let text = self.style.tooltip.get(owner).cloned().unwrap_or_default();
+            let tooltip = self.tooltips.text_tooltip;
+
+            if tooltip == Entity::null() {
+                self.tooltips.text_tooltip =
+                    Tooltip::new(&text).build(self, Entity::root(), |builder| builder);
+            } else {
+                if let Some(label) = self.hierarchy.get_first_child(tooltip) {
+                    label.set_text(self, &text);
+                }
+
+                tooltip.set_accessible_name(self, &text);
+            }
+
+            self.tooltips.text_tooltip
+        };
+
+        self.tooltips.tooltip = tooltip;
+
+        let (offset_x, offset_y) = self.tooltips.offset;
+
+        self.open_popup(
+            tooltip,
+            PopupAnchor::Point(self.mouse.cursorx + offset_x, self.mouse.cursory + offset_y),
+            PopupPlacement::Below,
+        );
+    }
+
+    fn hide_tooltip(&mut self) {
+        let tooltip = self.tooltips.tooltip;
+
+        if tooltip != Entity::null() {
+            self.tooltips.tooltip = Entity::null();
+            self.close_popup(tooltip);
+        }
+    }
+
+    // Returns the nearest ancestor of the hovered widget, including itself, which has a tooltip
+    fn tooltip_owner(&self, hovered: Entity) -> Entity {
+        hovered
+            .parent_iter(&self.hierarchy)
+            .find(|entity| {
+                self.tooltips.content.contains_key(entity)
+                    || self
+                        .style
+                        .tooltip
+                        .get(*entity)
+                        .map_or(false, |text| !text.is_empty())
+            })
+            .unwrap_or(Entity::null())
+    }
+}
@@ -420,6 +420,23 @@ pub fn apply_styles(state: &mut State, hierarchy: &Hierarchy) {
             should_redraw = true;
         }
 
+        // Knob Arcs
+        if state.style.arc_start_angle.link_rule(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.arc_end_angle.link_rule(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.arc_width.link_rule(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
+        if state.style.arc_center.link_rule(entity, &matched_rules) {
+            should_redraw = true;
+        }
+
         // Font
         if state.style.font_color.link_rule(entity, &matched_rules) {
             should_redraw = true;
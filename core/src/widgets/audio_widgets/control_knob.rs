@@ -2,42 +2,99 @@
 
 use crate::entity::Entity;
 use crate::mouse::*;
-use crate::State;
+use crate::{PointerEvent, PropSet, Role, State};
 use crate::{BuildHandler, Event, EventHandler, WindowEvent};
 
-use crate::style::{Display, Visibility};
+use crate::style::{Display, Length, Visibility};
 
 use crate::widgets::slider::SliderEvent;
-use crate::widgets::Element;
-
-use femtovg::{renderer::OpenGl, Canvas, LineCap, Paint, Path, Solidity};
+use crate::widgets::{begin_edit, end_edit, perform_edit, reset_edit, Element};
+use crate::widgets::{LinearMapping, LogMapping, ValueMapping};
 
+use std::f32::consts::PI;
 use std::sync::{Arc, Mutex};
 
+use femtovg::imgref::Img;
+use femtovg::rgb::FromSlice;
+use femtovg::{
+    renderer::OpenGl, Canvas, ImageFlags, ImageId, LineCap, Paint, Path, Solidity,
+};
+
+/// The pixels of an image used to draw a knob, in RGBA order with 4 bytes per pixel
+///
+/// The pixels are shared, so one image can skin many knobs without its pixels being copied. Each knob
+/// uploads the image to the canvas when it's first drawn and deletes it when the knob is removed.
+#[derive(Clone)]
+pub struct KnobImage {
+    data: Arc<Vec<u8>>,
+    width: usize,
+    height: usize,
+}
+
+impl KnobImage {
+    /// Creates an image from its pixels, which must hold `width * height * 4` bytes
+    pub fn new(data: Vec<u8>, width: usize, height: usize) -> Self {
+        KnobImage {
+            data: Arc::new(data),
+            width,
+            height,
+        }
+    }
+}
+
+/// How a `ControlKnob` is drawn
+#[derive(Clone)]
+pub enum KnobSkin {
+    // An arc showing the value around a knob with a tick, drawn with the colors of the `.back`,
+    // `.slider` and `.tick` classes
+    Vector,
+    // One frame of an image of frames stacked vertically, from the frame for the lowest value at the
+    // top to the frame for the highest value at the bottom
+    FilmStrip { image: KnobImage, frames: usize },
+    // An image rotated through the sweep of the arc, drawn upright at the middle of the sweep, inside
+    // the arc showing the value
+    Rotary { image: KnobImage },
+}
+
 pub struct ControlKnob {
     sliding: bool, // Could replace this with a bool in state, maybe in mouse
+    // Whether the current drag has changed the value and so begun an edit
+    editing: bool,
     value: f32,
+    // The normalized value a drag continues from
     temp: f32,
 
     mouse_down_posy: f32,
+    // Whether the fine adjustment modifier was held at the last mouse move of a drag
     shift_pressed: bool,
 
     back: Entity,
     slider: Entity,
     tick: Entity,
 
-    min: f32,
-    max: f32,
+    default: f32,
 
+    pub(crate) mapping: Arc<dyn ValueMapping>,
+
+    /// Whether the knob maps its position to a value logarithmically, read when the knob is built
+    #[deprecated(note = "use `with_mapping(LogMapping::new(min, max))` instead")]
     pub is_log: bool,
 
-    pub on_change: Option<Arc<Mutex<dyn Fn(f32) -> Event + Send>>>,
+    skin: KnobSkin,
+    // The image of the skin once it has been uploaded to the canvas
+    image_id: Option<ImageId>,
+    // Where the image is queued to be deleted from the canvas when the knob is removed
+    released_images: Option<Arc<Mutex<Vec<ImageId>>>>,
+
+    pub on_change: Option<Box<dyn Fn(f32) -> Event + Send>>,
 }
 
 impl ControlKnob {
+    #[allow(deprecated)]
     pub fn new(init: f32, min: f32, max: f32) -> Self {
         ControlKnob {
             sliding: false,
+            editing: false,
             value: init,
             temp: init,
 
@@ -48,17 +105,48 @@ impl ControlKnob {
             slider: Entity::null(),
             tick: Entity::null(),
 
-            min,
-            max,
+            default: init,
+
+            mapping: Arc::new(LinearMapping::new(min, max)),
 
             is_log: false,
 
+            skin: KnobSkin::Vector,
+            image_id: None,
+            released_images: None,
+
             on_change: None,
         }
     }
 
+    /// Sets how the knob maps its position to a value, replacing the linear mapping between min and max
+    pub fn with_mapping<M: ValueMapping + 'static>(mut self, mapping: M) -> Self {
+        self.mapping = Arc::new(mapping);
+
+        self
+    }
+
+    /// Maps the position of the knob to a value logarithmically between min and max
+    #[deprecated(note = "use `with_mapping(LogMapping::new(min, max))` instead")]
+    #[allow(deprecated)]
     pub fn with_log_scale(mut self) -> Self {
         self.is_log = true;
+        self.mapping = Arc::new(LogMapping::new(self.mapping.min(), self.mapping.max()));
+
+        self
+    }
+
+    /// Sets the value the knob is reset to when double clicked, which is the initial value by default
+    pub fn with_default(mut self, default: f32) -> Self {
+        self.default = default;
+
+        self
+    }
+
+    /// Sets how the knob is drawn, which is with vector arcs by default
+    pub fn with_skin(mut self, skin: KnobSkin) -> Self {
+        self.skin = skin;
+        self.image_id = None;
 
         self
     }
@@ -68,14 +156,47 @@ impl ControlKnob {
         F: Fn(f32) -> Event,
         F: 'static + Send,
     {
-        self.on_change = Some(Arc::new(Mutex::new(message)));
+        self.on_change = Some(Box::new(message));
         self
     }
+
+    fn normalized(&self, value: f32) -> f32 {
+        self.mapping.to_normalized(value)
+    }
+
+    fn send_change(&self, state: &mut State, entity: Entity) {
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(self.value);
+            if !event.target {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+            state.insert_event(event);
+        }
+
+        state.insert_event(Event::new(SliderEvent::ValueChanged(self.value)).target(entity));
+    }
 }
 
 impl BuildHandler for ControlKnob {
     type Ret = Entity;
+    #[allow(deprecated)]
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Slider);
+
+        // Setting the deprecated is_log field directly still switches the knob to a log mapping
+        if self.is_log {
+            self.mapping = Arc::new(LogMapping::new(self.mapping.min(), self.mapping.max()));
+        }
+
+        self.value = self.mapping.snap(self.value);
+        self.default = self.mapping.snap(self.default);
+        self.temp = self.normalized(self.value);
+
+        entity.set_accessible_value(state, &self.mapping.format(self.value));
+
         self.back = Element::new().build(state, entity, |builder| {
             builder
                 .set_hoverability(false)
@@ -107,13 +228,30 @@ impl EventHandler for ControlKnob {
             match slider_event {
                 SliderEvent::SetValue(val) => {
                     if event.target == entity {
-                        if event.target == entity {
-                            self.value = ((*val).min(self.max)).max(self.min);
+                        self.value = self.mapping.snap(*val);
+                        entity.set_accessible_value(state, &self.mapping.format(self.value));
 
-                            state.insert_event(
-                                Event::new(WindowEvent::Redraw).target(Entity::root()),
-                            );
-                        }
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                // Double clicking resets the knob to its default value
+                PointerEvent::Click { pointer, count } => {
+                    if event.target == entity && pointer.button == MouseButton::Left && *count == 2 {
+                        self.value = self.default;
+
+                        entity.set_accessible_value(state, &self.mapping.format(self.value));
+
+                        self.send_change(state, entity);
+                        reset_edit(state, entity, self.value, self.normalized(self.value));
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
                     }
                 }
 
@@ -127,60 +265,60 @@ impl EventHandler for ControlKnob {
                     if event.target == entity && *button == MouseButton::Left {
                         self.sliding = true;
                         self.mouse_down_posy = state.mouse.left.pos_down.1;
+                        self.shift_pressed = state.modifiers.shift;
                         state.capture(entity);
-                        state.focused = entity;
-                        self.temp = self.value;
+                        state.set_focus(entity);
+                        self.temp = self.normalized(self.value);
                     }
                 }
 
                 WindowEvent::MouseUp(button) => {
                     if event.target == entity && *button == MouseButton::Left {
+                        if self.editing {
+                            end_edit(state, entity);
+                        }
+
                         self.sliding = false;
+                        self.editing = false;
                         state.release(entity);
-                        self.temp = self.value;
                     }
                 }
 
                 WindowEvent::MouseMove(_, y) => {
                     if event.target == entity {
                         if self.sliding {
+                            // Pressing or releasing the fine adjustment modifier continues the drag from the current value
+                            if state.modifiers.shift != self.shift_pressed {
+                                self.shift_pressed = state.modifiers.shift;
+                                self.mouse_down_posy = *y;
+                                self.temp = self.normalized(self.value);
+                            }
+
                             let dy = self.mouse_down_posy - *y;
 
-                            let normalised = if state.modifiers.shift {
+                            let normalised = if self.shift_pressed {
                                 dy / 1000.0
                             } else {
                                 dy / 200.0
                             };
 
-                            let new_val = if self.is_log {
-                                let _t = self.temp.log10()
-                                    + (self.max.log10() - self.min.log10()) * normalised;
-                                10.0f32.powf(
-                                    self.temp.log10()
-                                        + (self.max.log10() - self.min.log10()) * normalised,
-                                )
-                            } else {
-                                self.temp + (self.max - self.min) * normalised
-                            };
+                            let new_val = self.mapping.to_plain(self.temp + normalised);
 
-                            self.value = (new_val.min(self.max)).max(self.min);
+                            if new_val != self.value {
+                                self.value = new_val;
+                                entity.set_accessible_value(state, &self.mapping.format(self.value));
 
-                            //println!("val: {}", normalised);
+                                self.send_change(state, entity);
 
-                            if let Some(on_change) = &self.on_change {
-                                let mut event = (on_change.lock().unwrap())(self.value);
-                                if !event.target {
-                                    event.target = entity;
+                                // The edit begins with the first change so that pressing without dragging sends no edit
+                                if !self.editing {
+                                    self.editing = true;
+                                    begin_edit(state, entity);
                                 }
 
-                                event.origin = entity;
-                                state.insert_event(event);
+                                perform_edit(state, entity, self.value, self.normalized(self.value));
                             }
 
-                            state.insert_event(
-                                Event::new(SliderEvent::ValueChanged(self.value)).target(entity),
-                            );
-
                             state.insert_event(
                                 Event::new(WindowEvent::Redraw).target(Entity::root()),
                             );
@@ -188,28 +326,6 @@ impl EventHandler for ControlKnob {
                     }
                 }
 
-                WindowEvent::KeyDown(keycode, _) => {
-                    if *keycode == keyboard_types::Code::ShiftLeft {
-                        if !self.shift_pressed {
-                            self.shift_pressed = true;
-                        }
-
-                        self.mouse_down_posy = state.mouse.cursory;
-                        self.temp = self.value;
-                    }
-                }
-
-                WindowEvent::KeyUp(keycode, _) => {
-                    if *keycode == keyboard_types::Code::ShiftLeft {
-                        if self.shift_pressed {
-                            self.shift_pressed = false;
-                        }
-
-                        self.mouse_down_posy = state.mouse.cursory;
-                        self.temp = self.value;
-                    }
-                }
-
                 _ => {}
             }
         }
@@ -263,77 +379,126 @@ impl EventHandler for ControlKnob {
         let width = state.data.get_width(entity);
         let height = state.data.get_height(entity);
 
+        // Uploads the image of the skin the first time it's drawn
+        if self.image_id.is_none() {
+            let image = match &self.skin {
+                KnobSkin::FilmStrip { image, .. } => Some(image),
+                KnobSkin::Rotary { image } => Some(image),
+                KnobSkin::Vector => None,
+            };
+
+            if let Some(image) = image {
+                let pixels = Img::new(image.data.as_rgba(), image.width, image.height);
+                self.image_id = canvas.create_image(pixels, ImageFlags::empty()).ok();
+                self.released_images = Some(state.released_images.clone());
+            }
+        }
+
+        let normalised = self.normalized(self.value).min(1.0).max(0.0);
+
+        // A film strip draws the frame for the value and nothing else
+        if let KnobSkin::FilmStrip { image, frames } = &self.skin {
+            if let Some(image_id) = self.image_id {
+                let frames = (*frames).max(1);
+                let frame = (normalised * (frames - 1) as f32).round();
+                let scale = width / image.width as f32;
+                let frame_height = image.height as f32 / frames as f32 * scale;
+                let top = posy + 0.5 * (height - frame_height);
+
+                let mut path = Path::new();
+                path.rect(posx, top, width, frame_height);
+                let paint = Paint::image(
+                    image_id,
+                    posx,
+                    top - frame * frame_height,
+                    width,
+                    image.height as f32 * scale,
+                    0.0,
+                    opacity,
+                );
+                canvas.fill_path(&mut path, paint);
+            }
+
+            return;
+        }
+
         let cx = posx + 0.5 * width;
         let cy = posy + 0.5 * height;
-        let r1 = width / 2.0;
-        let r0 = r1 - 10.0;
 
-        use std::f32::consts::PI;
-        let start = -(PI + PI / 4.0);
-        let end = PI / 4.0;
+        // Angles in the style are in degrees clockwise from the top
+        let start_angle = state.style.arc_start_angle.get(entity).cloned().unwrap_or(-135.0);
+        let end_angle = state.style.arc_end_angle.get(entity).cloned().unwrap_or(135.0);
+        let start = (start_angle - 90.0).to_radians();
+        let end = (end_angle - 90.0).to_radians();
 
-        let (min, max, value) = if self.is_log {
-            (self.min.log10(), self.max.log10(), self.value.log10())
-        //(self.min_value, self.max_value, self.value)
-        } else {
-            (self.min, self.max, self.value)
+        let arc_width = match state.style.arc_width.get(entity) {
+            Some(Length::Pixels(val)) => *val,
+            _ => 5.0,
         };
 
-        let zero_position = if self.is_log {
-            start
-        } else {
-            (-min / (max - min)) * (end - start) + start
-        };
-
-        let normalised = (value - min) / (max - min);
+        let r1 = width / 2.0;
+        let r0 = r1 - 2.0 * arc_width;
 
-        //println!("{}", zero_position);
+        // The arc is drawn from the center set by the style, or else from zero, or from the nearest end
+        // of the range if it doesn't contain zero
+        let center = match state.style.arc_center.get(entity) {
+            Some(center) => *center,
+            None => self.normalized(0.0),
+        };
+        let zero_position = center.min(1.0).max(0.0) * (end - start) + start;
 
         let current = normalised * (end - start) + start;
 
         canvas.save();
 
-        // Draw outer arc background
-        // let mut path = Path::new();
-        // path.arc(cx, cy, r0, start, end, Solidity::Hole);
-        // path.arc(cx, cy, r1, end, start, Solidity::Solid);
-        // path.close();
-        // let mut paint = Paint::color(back_color);
-        // canvas.fill_path(&mut path, paint);
-
         let mut path = Path::new();
-        path.arc(cx, cy, r1 - 2.5, end, start, Solidity::Solid);
+        path.arc(cx, cy, r1 - 0.5 * arc_width, end, start, Solidity::Solid);
         let mut paint = Paint::color(back_color);
-        paint.set_line_width(5.0);
+        paint.set_line_width(arc_width);
         paint.set_line_cap(LineCap::Round);
         canvas.stroke_path(&mut path, paint);
 
         if current != zero_position {
             let mut path = Path::new();
             if current > zero_position {
-                path.arc(cx, cy, r1 - 2.5, current, zero_position, Solidity::Solid);
+                path.arc(cx, cy, r1 - 0.5 * arc_width, current, zero_position, Solidity::Solid);
             } else {
-                path.arc(cx, cy, r1 - 2.5, zero_position, current, Solidity::Solid);
+                path.arc(cx, cy, r1 - 0.5 * arc_width, zero_position, current, Solidity::Solid);
             }
 
             let mut paint = Paint::color(slider_color);
-            paint.set_line_width(5.0);
+            paint.set_line_width(arc_width);
             paint.set_line_cap(LineCap::Round);
             canvas.stroke_path(&mut path, paint);
         }
 
-        // Draw outer arc fill
-        //if current != start {
-        //let mut path = Path::new();
-        //path.arc(cx, cy, r0, start, current, Solidity::Hole);
-        //path.arc(cx, cy, r1, current, start, Solidity::Solid);
-        //path.close();
-        // path.arc(cx, cy, r1 - 2.5, end, start, Solidity::Solid);
-        // let mut paint = Paint::color(back_color);
-        // paint.set_line_width(5.0);
-        // paint.set_line_cap(LineCap::Round);
-        // canvas.fill_path(&mut path, paint);
-        //}
+        // A rotary image is upright at the middle of the sweep and turns with the value
+        if let KnobSkin::Rotary { .. } = &self.skin {
+            if let Some(image_id) = self.image_id {
+                canvas.save();
+                canvas.translate(cx, cy);
+                canvas.rotate(current - 0.5 * (start + end));
+
+                let radius = r0 + 1.0;
+                let mut path = Path::new();
+                path.circle(0.0, 0.0, radius);
+                let paint = Paint::image(
+                    image_id,
+                    -radius,
+                    -radius,
+                    2.0 * radius,
+                    2.0 * radius,
+                    0.0,
+                    opacity,
+                );
+                canvas.fill_path(&mut path, paint);
+
+                canvas.restore();
+            }
+
+            canvas.restore();
+            return;
+        }
 
         // Draw knob
         let mut path = Path::new();
@@ -355,3 +520,16 @@ impl EventHandler for ControlKnob {
         canvas.restore();
     }
 }
+
+// Queues the uploaded image of the skin to be deleted from the canvas once the knob is removed
+impl Drop for ControlKnob {
+    fn drop(&mut self) {
+        if let (Some(image_id), Some(released_images)) =
+            (self.image_id.take(), &self.released_images)
+        {
+            if let Ok(mut released_images) = released_images.lock() {
+                released_images.push(image_id);
+            }
+        }
+    }
+}
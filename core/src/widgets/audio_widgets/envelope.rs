@@ -0,0 +1,575 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::style::{Display, Visibility};
+use crate::widgets::{draw_grid, Element, GridAxis};
+use crate::{BuildHandler, Event, EventHandler, PointerEvent, PropSet, Role, State, WindowEvent};
+
+use femtovg::{renderer::OpenGl, Canvas, LineCap, Paint, Path};
+
+/// A breakpoint of an envelope, with the tension of the segment to the next node
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EnvelopeNode {
+    pub time: f32,
+    pub level: f32,
+    // Bends the segment to the next node, from -1.0 to 1.0 with 0.0 for a straight line
+    pub tension: f32,
+}
+
+impl EnvelopeNode {
+    pub fn new(time: f32, level: f32) -> Self {
+        EnvelopeNode {
+            time,
+            level,
+            tension: 0.0,
+        }
+    }
+
+    pub fn with_tension(mut self, tension: f32) -> Self {
+        self.tension = tension;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvelopeEvent {
+    // Replaces the nodes of the envelope, e.g. when a preset is loaded
+    SetNodes(Vec<EnvelopeNode>),
+}
+
+// The part of the envelope under the cursor
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum EnvelopeTarget {
+    Node(usize),
+    // The tension handle of the segment starting at a node
+    Tension(usize),
+}
+
+const NODE_RADIUS: f32 = 5.0;
+const HANDLE_RADIUS: f32 = 3.0;
+// The distance in pixels within which a node or handle is under the cursor
+const HIT_DISTANCE: f32 = 8.0;
+// The number of lines drawn for each segment
+const SEGMENT_STEPS: usize = 24;
+
+/// Returns the position between the start and end of a segment at a fraction of its time, bent by its tension
+///
+/// A positive tension makes the segment change slowly at first and quickly at the end.
+pub fn envelope_curve(fraction: f32, tension: f32) -> f32 {
+    let c = tension * 8.0;
+
+    if c.abs() < 1e-3 {
+        fraction
+    } else {
+        ((c * fraction).exp() - 1.0) / (c.exp() - 1.0)
+    }
+}
+
+/// An editor for a multi-segment envelope drawn over a grid
+///
+/// Nodes are dragged to change their time and level, and stay between their neighbours so the
+/// nodes are always in time order. The handle in the middle of each segment is dragged up or down to
+/// bend the segment. Double clicking the envelope away from the nodes adds a node and double clicking
+/// a node deletes it, keeping at least two nodes. Changes made by the user send the `on_change`
+/// event with the nodes.
+///
+/// The axes are `GridAxis`es, so times and levels are mapped the same way as on a `Grid`. The curve
+/// is styled by the `.curve` class, the area under it by `.fill`, the nodes by `.node`, the node
+/// under the cursor by `.active` and the tension handles by `.handle`, each with its background color.
+pub struct EnvelopeEditor {
+    nodes: Vec<EnvelopeNode>,
+    x_axis: GridAxis,
+    y_axis: GridAxis,
+
+    hovered: Option<EnvelopeTarget>,
+    dragging: Option<EnvelopeTarget>,
+    // The normalized position of the dragged node, or the tension of the dragged handle, when the drag started
+    drag_start: (f32, f32),
+
+    on_change: Option<Box<dyn Fn(&[EnvelopeNode]) -> Event + Send>>,
+
+    line: Entity,
+    label: Entity,
+    curve: Entity,
+    fill: Entity,
+    node: Entity,
+    active: Entity,
+    handle: Entity,
+}
+
+impl EnvelopeEditor {
+    /// Creates an editor with an attack, decay, sustain and release envelope over times and levels from 0 to 1
+    pub fn new() -> Self {
+        EnvelopeEditor {
+            nodes: vec![
+                EnvelopeNode::new(0.0, 0.0),
+                EnvelopeNode::new(0.1, 1.0),
+                EnvelopeNode::new(0.3, 0.7),
+                EnvelopeNode::new(0.8, 0.7),
+                EnvelopeNode::new(1.0, 0.0),
+            ],
+            x_axis: GridAxis::default(),
+            y_axis: GridAxis::default(),
+
+            hovered: None,
+            dragging: None,
+            drag_start: (0.0, 0.0),
+
+            on_change: None,
+
+            line: Entity::null(),
+            label: Entity::null(),
+            curve: Entity::null(),
+            fill: Entity::null(),
+            node: Entity::null(),
+            active: Entity::null(),
+            handle: Entity::null(),
+        }
+    }
+
+    pub fn with_nodes(mut self, nodes: Vec<EnvelopeNode>) -> Self {
+        self.set_nodes(nodes);
+        self
+    }
+
+    /// Sets the axis of the times of the nodes
+    pub fn with_x_axis(mut self, axis: GridAxis) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    /// Sets the axis of the levels of the nodes
+    pub fn with_y_axis(mut self, axis: GridAxis) -> Self {
+        self.y_axis = axis;
+        self
+    }
+
+    /// Sets the event sent with the nodes when the user changes the envelope
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(&[EnvelopeNode]) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+        self
+    }
+
+    fn set_nodes(&mut self, mut nodes: Vec<EnvelopeNode>) {
+        nodes.sort_by(|a, b| {
+            a.time
+                .partial_cmp(&b.time)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        self.nodes = nodes;
+        self.hovered = None;
+        self.dragging = None;
+    }
+
+    fn notify(&self, state: &mut State, entity: Entity) {
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(&self.nodes);
+            if !event.target {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+
+            state.insert_event(event);
+        }
+
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    fn bounds(state: &State, entity: Entity) -> (f32, f32, f32, f32) {
+        (
+            state.data.get_posx(entity),
+            state.data.get_posy(entity),
+            state.data.get_width(entity),
+            state.data.get_height(entity),
+        )
+    }
+
+    // Returns the normalized position of a time and level
+    fn normalized(&self, time: f32, level: f32) -> (f32, f32) {
+        (self.x_axis.position(time), self.y_axis.position(level))
+    }
+
+    // Returns the window position of a time and level
+    fn to_window(
+        &self,
+        (posx, posy, width, height): (f32, f32, f32, f32),
+        time: f32,
+        level: f32,
+    ) -> (f32, f32) {
+        let (nx, ny) = self.normalized(time, level);
+
+        (posx + nx * width, posy + (1.0 - ny) * height)
+    }
+
+    // Returns the time and level of the middle of the segment starting at a node
+    fn handle_value(&self, index: usize) -> (f32, f32) {
+        let (start, end) = (self.nodes[index], self.nodes[index + 1]);
+
+        (
+            start.time + (end.time - start.time) * 0.5,
+            start.level + (end.level - start.level) * envelope_curve(0.5, start.tension),
+        )
+    }
+
+    // Returns the node or tension handle at a window position
+    fn hit_test(&self, state: &State, entity: Entity, x: f32, y: f32) -> Option<EnvelopeTarget> {
+        let bounds = Self::bounds(state, entity);
+
+        let distance = |(px, py): (f32, f32)| ((px - x).powi(2) + (py - y).powi(2)).sqrt();
+
+        let node = (0..self.nodes.len())
+            .map(|index| {
+                let node = self.nodes[index];
+                (index, distance(self.to_window(bounds, node.time, node.level)))
+            })
+            .filter(|(_, distance)| *distance <= HIT_DISTANCE)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some((index, _)) = node {
+            return Some(EnvelopeTarget::Node(index));
+        }
+
+        (0..self.nodes.len().saturating_sub(1))
+            .find(|index| {
+                let (time, level) = self.handle_value(*index);
+                distance(self.to_window(bounds, time, level)) <= HIT_DISTANCE
+            })
+            .map(EnvelopeTarget::Tension)
+    }
+
+    // Returns the time and level at a window position
+    fn value_at(&self, state: &State, entity: Entity, x: f32, y: f32) -> (f32, f32) {
+        let (posx, posy, width, height) = Self::bounds(state, entity);
+
+        if width <= 0.0 || height <= 0.0 {
+            return (self.x_axis.mapping.to_plain(0.0), self.y_axis.mapping.to_plain(0.0));
+        }
+
+        (
+            self.x_axis.mapping.to_plain((x - posx) / width),
+            self.y_axis.mapping.to_plain(1.0 - (y - posy) / height),
+        )
+    }
+
+    fn set_hovered(&mut self, state: &mut State, hovered: Option<EnvelopeTarget>) {
+        if self.hovered != hovered {
+            self.hovered = hovered;
+            state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+        }
+    }
+}
+
+impl BuildHandler for EnvelopeEditor {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Group);
+
+        let mut style_element = |class: &str| {
+            Element::new().build(state, entity, |builder| {
+                builder
+                    .set_hoverability(false)
+                    .set_display(Display::None)
+                    .class(class)
+            })
+        };
+
+        self.line = style_element("line");
+        self.label = style_element("label");
+        self.curve = style_element("curve");
+        self.fill = style_element("fill");
+        self.node = style_element("node");
+        self.active = style_element("active");
+        self.handle = style_element("handle");
+
+        state.style.insert_element(entity, "envelope");
+
+        entity
+    }
+}
+
+impl EventHandler for EnvelopeEditor {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(envelope_event) = event.message.downcast::<EnvelopeEvent>() {
+            match envelope_event {
+                EnvelopeEvent::SetNodes(nodes) => {
+                    if event.target == entity {
+                        self.set_nodes(nodes.clone());
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                    }
+                }
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                PointerEvent::DragMove {
+                    total_dx, total_dy, ..
+                } => {
+                    if event.target != entity {
+                        return;
+                    }
+
+                    let (_, _, width, height) = Self::bounds(state, entity);
+
+                    match self.dragging {
+                        Some(EnvelopeTarget::Node(index)) => {
+                            if width <= 0.0 || height <= 0.0 {
+                                return;
+                            }
+
+                            let nx = self.drag_start.0 + *total_dx / width;
+                            let ny = self.drag_start.1 - *total_dy / height;
+
+                            // Nodes stay between their neighbours to keep the nodes in time order
+                            let mut time = self.x_axis.mapping.to_plain(nx);
+                            if index > 0 {
+                                time = time.max(self.nodes[index - 1].time);
+                            }
+                            if index + 1 < self.nodes.len() {
+                                time = time.min(self.nodes[index + 1].time);
+                            }
+
+                            let level = self.y_axis.mapping.to_plain(ny);
+
+                            let node = &mut self.nodes[index];
+                            if node.time != time || node.level != level {
+                                node.time = time;
+                                node.level = level;
+                                self.notify(state, entity);
+                            }
+                        }
+
+                        Some(EnvelopeTarget::Tension(index)) => {
+                            // Dragging up bends the middle of the segment up, whichever way the segment goes
+                            let rising = self.nodes[index + 1].level >= self.nodes[index].level;
+                            let change = *total_dy / 100.0;
+                            let tension = if rising {
+                                self.drag_start.0 + change
+                            } else {
+                                self.drag_start.0 - change
+                            };
+
+                            let node = &mut self.nodes[index];
+                            let tension = tension.min(1.0).max(-1.0);
+                            if node.tension != tension {
+                                node.tension = tension;
+                                self.notify(state, entity);
+                            }
+                        }
+
+                        None => {}
+                    }
+
+                    event.consume();
+                }
+
+                // Double clicking a node deletes it and double clicking elsewhere adds a node
+                PointerEvent::Click { pointer, count } => {
+                    if event.target != entity || pointer.button != MouseButton::Left || *count != 2 {
+                        return;
+                    }
+
+                    match self.hit_test(state, entity, pointer.x, pointer.y) {
+                        Some(EnvelopeTarget::Node(index)) => {
+                            if self.nodes.len() > 2 {
+                                self.nodes.remove(index);
+                                self.hovered = None;
+                                self.notify(state, entity);
+                            }
+                        }
+
+                        Some(EnvelopeTarget::Tension(_)) => {}
+
+                        None => {
+                            let (time, level) = self.value_at(state, entity, pointer.x, pointer.y);
+                            let index = self.nodes.iter().take_while(|node| node.time <= time).count();
+
+                            self.nodes.insert(index, EnvelopeNode::new(time, level));
+                            self.hovered = Some(EnvelopeTarget::Node(index));
+                            self.notify(state, entity);
+                        }
+                    }
+
+                    event.consume();
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if event.target == entity {
+                        let (x, y) = state.mouse.left.pos_down;
+
+                        self.dragging = self.hit_test(state, entity, x, y);
+
+                        match self.dragging {
+                            Some(EnvelopeTarget::Node(index)) => {
+                                let node = self.nodes[index];
+                                self.drag_start = self.normalized(node.time, node.level);
+                            }
+
+                            Some(EnvelopeTarget::Tension(index)) => {
+                                self.drag_start = (self.nodes[index].tension, 0.0);
+                            }
+
+                            None => {}
+                        }
+
+                        state.capture(entity);
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if event.target == entity {
+                        self.dragging = None;
+                        state.release(entity);
+                    }
+                }
+
+                WindowEvent::MouseMove(x, y) => {
+                    if event.target == entity && self.dragging.is_none() {
+                        let hovered = self.hit_test(state, entity, *x, *y);
+                        self.set_hovered(state, hovered);
+                    }
+                }
+
+                WindowEvent::MouseOut => {
+                    if event.target == entity && self.dragging.is_none() {
+                        self.set_hovered(state, None);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let background_color = color(state, entity);
+        let curve_color = color(state, self.curve);
+        let fill_color = color(state, self.fill);
+        let node_color = color(state, self.node);
+        let active_color = color(state, self.active);
+        let handle_color = color(state, self.handle);
+
+        let bounds = Self::bounds(state, entity);
+        let (posx, posy, width, height) = bounds;
+
+        // Draw background
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
+
+        draw_grid(
+            state,
+            canvas,
+            &self.x_axis,
+            &self.y_axis,
+            (self.line, self.label),
+            bounds,
+            opacity,
+        );
+
+        if self.nodes.is_empty() {
+            return;
+        }
+
+        // Draw the curve through the nodes, sampling each segment along its time
+        let mut points = Vec::with_capacity(self.nodes.len() * SEGMENT_STEPS + 1);
+        let first = self.nodes[0];
+        points.push(self.to_window(bounds, first.time, first.level));
+
+        for segment in self.nodes.windows(2) {
+            let (start, end) = (segment[0], segment[1]);
+
+            for step in 1..=SEGMENT_STEPS {
+                let fraction = step as f32 / SEGMENT_STEPS as f32;
+                let time = start.time + (end.time - start.time) * fraction;
+                let level =
+                    start.level + (end.level - start.level) * envelope_curve(fraction, start.tension);
+
+                points.push(self.to_window(bounds, time, level));
+            }
+        }
+
+        let bottom = posy + height;
+
+        let mut path = Path::new();
+        path.move_to(points[0].0, bottom);
+        for (x, y) in points.iter() {
+            path.line_to(*x, *y);
+        }
+        path.line_to(points[points.len() - 1].0, bottom);
+        path.close();
+        canvas.fill_path(&mut path, Paint::color(fill_color));
+
+        let mut path = Path::new();
+        path.move_to(points[0].0, points[0].1);
+        for (x, y) in points.iter().skip(1) {
+            path.line_to(*x, *y);
+        }
+        let mut paint = Paint::color(curve_color);
+        paint.set_line_width(2.0);
+        paint.set_line_cap(LineCap::Round);
+        canvas.stroke_path(&mut path, paint);
+
+        let target = self.dragging.or(self.hovered);
+
+        // Draw tension handles
+        for index in 0..self.nodes.len() - 1 {
+            let (time, level) = self.handle_value(index);
+            let (x, y) = self.to_window(bounds, time, level);
+
+            let color = if target == Some(EnvelopeTarget::Tension(index)) {
+                active_color
+            } else {
+                handle_color
+            };
+
+            let mut path = Path::new();
+            path.circle(x, y, HANDLE_RADIUS);
+            canvas.fill_path(&mut path, Paint::color(color));
+        }
+
+        // Draw nodes
+        for (index, node) in self.nodes.iter().enumerate() {
+            let (x, y) = self.to_window(bounds, node.time, node.level);
+
+            let color = if target == Some(EnvelopeTarget::Node(index)) {
+                active_color
+            } else {
+                node_color
+            };
+
+            let mut path = Path::new();
+            path.circle(x, y, NODE_RADIUS);
+            canvas.fill_path(&mut path, Paint::color(color));
+        }
+    }
+}
@@ -1,33 +1,244 @@
 use crate::entity::Entity;
-use crate::State;
-use crate::{BuildHandler, Event, EventHandler};
+use crate::style::{Display, Visibility};
+use crate::widgets::{Element, LinearMapping, ValueMapping};
+use crate::{BuildHandler, Event, EventHandler, State};
 
-use femtovg::{renderer::OpenGl, Canvas};
+use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Paint, Path};
 
-pub struct Grid {}
+use std::sync::Arc;
+
+/// The scale, lines and labels along one side of a `Grid`
+///
+/// The axis maps plain values to positions with a `ValueMapping`, so a frequency axis can use a
+/// `LogMapping` and label its lines in Hz.
+#[derive(Clone)]
+pub struct GridAxis {
+    pub(crate) mapping: Arc<dyn ValueMapping>,
+    pub(crate) lines: Vec<f32>,
+    labels: bool,
+}
+
+impl GridAxis {
+    pub fn new<M: ValueMapping + 'static>(mapping: M) -> Self {
+        GridAxis {
+            mapping: Arc::new(mapping),
+            lines: Vec::new(),
+            labels: true,
+        }
+    }
+
+    /// Draws lines at plain values along the axis
+    pub fn with_lines(mut self, lines: &[f32]) -> Self {
+        self.lines = lines.to_vec();
+        self
+    }
+
+    /// Sets whether the lines are labelled with their values, which they are by default
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    // Returns the position of a plain value along the axis from 0 to 1
+    pub(crate) fn position(&self, value: f32) -> f32 {
+        self.mapping.to_normalized(value).min(1.0).max(0.0)
+    }
+
+    // Returns the normalized distance a key nudges a value along the axis, which is one step of a
+    // mapping with steps, or for a coarse nudge at least one step, so the value is never snapped back
+    pub(crate) fn nudge_step(&self, step: f32, coarse: bool) -> f32 {
+        match self.mapping.normalized_step() {
+            Some(mapping_step) if coarse => step.max(mapping_step),
+            Some(mapping_step) => mapping_step,
+            None => step,
+        }
+    }
+}
+
+impl Default for GridAxis {
+    fn default() -> Self {
+        GridAxis::new(LinearMapping::new(0.0, 1.0))
+            .with_lines(&[0.25, 0.5, 0.75])
+            .with_labels(false)
+    }
+}
+
+/// A backdrop of lines along a horizontal and vertical axis, e.g. behind a filter response or envelope
+///
+/// The x axis runs from left to right and the y axis from bottom to top. The lines are styled by the
+/// background color of the `.line` class and the labels by the font color of the `.label` class.
+pub struct Grid {
+    x_axis: GridAxis,
+    y_axis: GridAxis,
+
+    line: Entity,
+    label: Entity,
+}
 
 impl Grid {
     pub fn new() -> Self {
-        Grid {}
+        Grid {
+            x_axis: GridAxis::default(),
+            y_axis: GridAxis::default(),
+
+            line: Entity::null(),
+            label: Entity::null(),
+        }
+    }
+
+    pub fn with_x_axis(mut self, axis: GridAxis) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    pub fn with_y_axis(mut self, axis: GridAxis) -> Self {
+        self.y_axis = axis;
+        self
     }
 }
 
 impl BuildHandler for Grid {
     type Ret = Entity;
-    fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        self.line = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("line")
+        });
+        self.label = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("label")
+        });
+
+        state.style.insert_element(entity, "grid");
+
         entity
     }
 }
 
+// Draws the lines and labels of two axes within a rectangle, with the colors of the `.line` and `.label` elements
+pub(crate) fn draw_grid(
+    state: &State,
+    canvas: &mut Canvas<OpenGl>,
+    x_axis: &GridAxis,
+    y_axis: &GridAxis,
+    (line, label): (Entity, Entity),
+    (posx, posy, width, height): (f32, f32, f32, f32),
+    opacity: f32,
+) {
+    let mut line_color: femtovg::Color = state
+        .style
+        .background_color
+        .get(line)
+        .cloned()
+        .unwrap_or_default()
+        .into();
+    line_color.set_alphaf(line_color.a * opacity);
+
+    let mut label_color: femtovg::Color = state
+        .style
+        .font_color
+        .get(label)
+        .cloned()
+        .unwrap_or_default()
+        .into();
+    label_color.set_alphaf(label_color.a * opacity);
+
+    // Draw vertical lines
+    let mut path = Path::new();
+    for value in x_axis.lines.iter() {
+        // Lines are drawn on pixel centers so they stay one pixel wide
+        let x = (posx + x_axis.position(*value) * width).floor() + 0.5;
+        path.move_to(x, posy);
+        path.line_to(x, posy + height);
+    }
+
+    // Draw horizontal lines
+    for value in y_axis.lines.iter() {
+        let y = (posy + (1.0 - y_axis.position(*value)) * height).floor() + 0.5;
+        path.move_to(posx, y);
+        path.line_to(posx + width, y);
+    }
+
+    let mut paint = Paint::color(line_color);
+    paint.set_line_width(1.0);
+    canvas.stroke_path(&mut path, paint);
+
+    // Draw labels inside the grid, beside the bottom of the vertical lines and above the left of the horizontal lines
+    let font_id = match state.fonts.regular {
+        Some(font_id) => font_id,
+        None => return,
+    };
+
+    let label_paint = || {
+        let mut paint = Paint::color(label_color);
+        paint.set_font_size(10.0);
+        paint.set_font(&[font_id]);
+        paint.set_text_align(Align::Left);
+        paint.set_text_baseline(Baseline::Bottom);
+        paint.set_anti_alias(false);
+        paint
+    };
+
+    if x_axis.labels {
+        for value in x_axis.lines.iter() {
+            let x = posx + x_axis.position(*value) * width;
+            let text = x_axis.mapping.format(*value);
+
+            let _ = canvas.fill_text(x + 2.0, posy + height - 2.0, &text, label_paint());
+        }
+    }
+
+    if y_axis.labels {
+        for value in y_axis.lines.iter() {
+            let y = posy + (1.0 - y_axis.position(*value)) * height;
+            let text = y_axis.mapping.format(*value);
+
+            let _ = canvas.fill_text(posx + 2.0, y - 2.0, &text, label_paint());
+        }
+    }
+}
+
 impl EventHandler for Grid {
     fn on_event(&mut self, _state: &mut State, _entity: Entity, _event: &mut Event) {}
 
-    fn on_draw(&mut self, _state: &mut State, _entity: Entity, _canvas: &mut Canvas<OpenGl>) {
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
 
-        // Draw background
+        let opacity = state.data.get_opacity(entity);
+
+        let mut background_color: femtovg::Color = state
+            .style
+            .background_color
+            .get(entity)
+            .cloned()
+            .unwrap_or_default()
+            .into();
+        background_color.set_alphaf(background_color.a * opacity);
 
-        // Draw vertical lines
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        // Draw background
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
 
-        // Draw horizontal lines
+        draw_grid(
+            state,
+            canvas,
+            &self.x_axis,
+            &self.y_axis,
+            (self.line, self.label),
+            (posx, posy, width, height),
+            opacity,
+        );
     }
 }
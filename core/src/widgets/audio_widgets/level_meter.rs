@@ -0,0 +1,532 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::{BuildHandler, Event, EventHandler, WindowEvent};
+use crate::{MeterFeed, PropSet, Role, State};
+
+use crate::style::{Display, Length, Position, Visibility};
+
+use crate::widgets::{gain_to_db, Element};
+
+use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Paint, Path};
+
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelMeterEvent {
+    // Sets the peak and RMS level of a channel as linear gains, e.g. from a bound parameter
+    SetLevel { channel: usize, peak: f32, rms: f32 },
+    // Clears the clip indicator
+    ResetClip,
+    // Sent to every meter once per frame by `State::update_meters`
+    Frame,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    Vertical,
+    Horizontal,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MeterChannel {
+    // The levels to move towards, in dB
+    peak_target: f32,
+    rms_target: f32,
+
+    // The levels shown, in dB
+    peak: f32,
+    rms: f32,
+    hold: f32,
+    // Seconds since the hold marker was last raised
+    hold_age: f32,
+}
+
+/// A meter showing the peak and RMS levels of one or more channels of audio
+///
+/// Levels are shown in dB between the bottom and top of the range, with the peak bar styled by the
+/// `.peak` class, the RMS bar by `.rms`, the peak hold marker by `.hold` and the empty part of the
+/// bar by `.back`. The peak bar rises immediately and falls at the peak release rate, while the RMS
+/// bar moves towards the level with the RMS time constant. The hold marker stays at the highest peak
+/// for the hold time before falling. The clip indicator, an element with the `.clip` class, is checked
+/// when a level reaches full scale and stays checked until it is clicked.
+///
+/// The meter takes levels from a `MeterFeed` filled by the audio thread, or from `SetLevel` events.
+/// It updates on every frame, so it moves smoothly however often new levels arrive, and the channels
+/// of a feed which measures no samples, e.g. when playback stops, fall to the bottom of the range.
+pub struct LevelMeter {
+    channels: Vec<MeterChannel>,
+    feed: Option<MeterFeed>,
+
+    orientation: Orientation,
+    min_db: f32,
+    max_db: f32,
+    ticks: Vec<f32>,
+    show_rms: bool,
+
+    // Fall of the peak bar in dB per second
+    peak_release: f32,
+    // Time constant of the RMS bar in seconds
+    rms_time: f32,
+    // Time the hold marker stays at a peak in seconds
+    hold_time: f32,
+    // Fall of the hold marker in dB per second after the hold time
+    hold_release: f32,
+
+    clipped: bool,
+    last_frame: Option<Instant>,
+
+    clip: Entity,
+    back: Entity,
+    peak: Entity,
+    rms: Entity,
+    hold: Entity,
+    tick: Entity,
+}
+
+impl LevelMeter {
+    pub fn new(channels: usize) -> Self {
+        let min_db = -60.0;
+
+        LevelMeter {
+            channels: vec![
+                MeterChannel {
+                    peak_target: min_db,
+                    rms_target: min_db,
+                    peak: min_db,
+                    rms: min_db,
+                    hold: min_db,
+                    hold_age: 0.0,
+                };
+                channels.max(1)
+            ],
+            feed: None,
+
+            orientation: Orientation::Vertical,
+            min_db,
+            max_db: 0.0,
+            ticks: Vec::new(),
+            show_rms: true,
+
+            peak_release: 20.0,
+            rms_time: 0.3,
+            hold_time: 1.5,
+            hold_release: 20.0,
+
+            clipped: false,
+            last_frame: None,
+
+            clip: Entity::null(),
+            back: Entity::null(),
+            peak: Entity::null(),
+            rms: Entity::null(),
+            hold: Entity::null(),
+            tick: Entity::null(),
+        }
+    }
+
+    /// Takes levels from a feed filled by the audio thread
+    pub fn with_feed(mut self, feed: MeterFeed) -> Self {
+        self.feed = Some(feed);
+        self
+    }
+
+    pub fn with_orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Sets the levels in dB at the bottom and top of the meter, which are -60 dB and 0 dB by default
+    pub fn with_range(mut self, min_db: f32, max_db: f32) -> Self {
+        self.min_db = min_db;
+        self.max_db = max_db;
+
+        for channel in self.channels.iter_mut() {
+            channel.peak_target = min_db;
+            channel.rms_target = min_db;
+            channel.peak = min_db;
+            channel.rms = min_db;
+            channel.hold = min_db;
+        }
+
+        self
+    }
+
+    /// Shows labelled ticks at levels in dB beside the bars
+    pub fn with_ticks(mut self, ticks: &[f32]) -> Self {
+        self.ticks = ticks.to_vec();
+        self
+    }
+
+    /// Sets whether the RMS bar is shown in front of the peak bar
+    pub fn with_rms(mut self, show_rms: bool) -> Self {
+        self.show_rms = show_rms;
+        self
+    }
+
+    /// Sets how fast the peak bar falls in dB per second
+    pub fn with_peak_release(mut self, db_per_second: f32) -> Self {
+        self.peak_release = db_per_second;
+        self
+    }
+
+    /// Sets the time constant of the RMS bar in seconds, e.g. 0.3 for VU ballistics
+    pub fn with_rms_time(mut self, seconds: f32) -> Self {
+        self.rms_time = seconds;
+        self
+    }
+
+    /// Sets how long the hold marker stays at a peak in seconds, and how fast it then falls in dB per second
+    pub fn with_peak_hold(mut self, seconds: f32, db_per_second: f32) -> Self {
+        self.hold_time = seconds;
+        self.hold_release = db_per_second;
+        self
+    }
+
+    fn to_db(&self, gain: f32) -> f32 {
+        gain_to_db(gain.abs()).max(self.min_db)
+    }
+
+    fn set_clipped(&mut self, state: &mut State, clipped: bool) {
+        if self.clipped != clipped {
+            self.clipped = clipped;
+            self.clip.set_checked(state, clipped);
+        }
+    }
+
+    // Moves the shown levels towards their targets, returning whether anything moved
+    fn apply_ballistics(&mut self, dt: f32) -> bool {
+        let rms_coefficient = if self.rms_time > 0.0 {
+            1.0 - (-dt / self.rms_time).exp()
+        } else {
+            1.0
+        };
+
+        let mut moved = false;
+
+        for channel in self.channels.iter_mut() {
+            let previous = (channel.peak, channel.rms, channel.hold);
+
+            if channel.peak_target >= channel.peak {
+                channel.peak = channel.peak_target;
+            } else {
+                channel.peak = (channel.peak - self.peak_release * dt).max(channel.peak_target);
+            }
+
+            channel.rms += (channel.rms_target - channel.rms) * rms_coefficient;
+            // Stop the RMS bar creeping towards its target once the difference can't be seen
+            if (channel.rms_target - channel.rms).abs() < 0.01 {
+                channel.rms = channel.rms_target;
+            }
+
+            if channel.peak >= channel.hold {
+                channel.hold = channel.peak;
+                channel.hold_age = 0.0;
+            } else {
+                channel.hold_age += dt;
+
+                if channel.hold_age > self.hold_time {
+                    channel.hold = (channel.hold - self.hold_release * dt).max(channel.peak);
+                }
+            }
+
+            if (channel.peak, channel.rms, channel.hold) != previous {
+                moved = true;
+            }
+        }
+
+        moved
+    }
+
+    // Returns the position of a level from 0 at the bottom of the meter to 1 at the top
+    fn position(&self, db: f32) -> f32 {
+        if self.max_db == self.min_db {
+            return 0.0;
+        }
+
+        ((db - self.min_db) / (self.max_db - self.min_db)).min(1.0).max(0.0)
+    }
+}
+
+impl BuildHandler for LevelMeter {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Meter);
+
+        self.clip = Element::new().build(state, entity, |builder| {
+            builder.set_position(Position::Absolute).class("clip")
+        });
+
+        match self.orientation {
+            Orientation::Vertical => {
+                self.clip
+                    .set_top(state, Length::Pixels(0.0))
+                    .set_width(state, Length::Percentage(1.0));
+            }
+
+            Orientation::Horizontal => {
+                self.clip
+                    .set_right(state, Length::Pixels(0.0))
+                    .set_height(state, Length::Percentage(1.0));
+            }
+        }
+
+        self.back = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("back")
+        });
+        self.peak = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("peak")
+        });
+        self.rms = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("rms")
+        });
+        self.hold = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("hold")
+        });
+        self.tick = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("tick")
+        });
+
+        state.meters.add(entity, || Event::new(LevelMeterEvent::Frame));
+
+        state.style.insert_element(entity, "level_meter");
+
+        entity
+    }
+}
+
+impl EventHandler for LevelMeter {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(level_meter_event) = event.message.downcast::<LevelMeterEvent>() {
+            if event.target == entity {
+                match level_meter_event {
+                    LevelMeterEvent::SetLevel { channel, peak, rms } => {
+                        let (peak_db, rms_db) = (self.to_db(*peak), self.to_db(*rms));
+
+                        if let Some(channel) = self.channels.get_mut(*channel) {
+                            channel.peak_target = peak_db;
+                            channel.rms_target = rms_db;
+                        }
+
+                        if peak.abs() >= 1.0 {
+                            self.set_clipped(state, true);
+                        }
+                    }
+
+                    LevelMeterEvent::ResetClip => {
+                        self.set_clipped(state, false);
+                    }
+
+                    LevelMeterEvent::Frame => {
+                        // Channels without new samples, e.g. when playback stops, fall towards the
+                        // bottom of the range at the release rate
+                        if let Some(feed) = self.feed.clone() {
+                            for index in 0..self.channels.len() {
+                                let (peak_db, rms_db) = match feed.take(index) {
+                                    Some(reading) => {
+                                        if reading.clipped {
+                                            self.set_clipped(state, true);
+                                        }
+
+                                        (self.to_db(reading.peak), self.to_db(reading.rms))
+                                    }
+
+                                    None => (self.min_db, self.min_db),
+                                };
+
+                                self.channels[index].peak_target = peak_db;
+                                self.channels[index].rms_target = rms_db;
+                            }
+                        }
+
+                        let now = Instant::now();
+                        // A long gap between frames, e.g. while the window was hidden, is treated as one frame
+                        let dt = match self.last_frame {
+                            Some(last_frame) => (now - last_frame).as_secs_f32().min(0.1),
+                            None => 0.0,
+                        };
+                        self.last_frame = Some(now);
+
+                        if self.apply_ballistics(dt) {
+                            state.insert_event(
+                                Event::new(WindowEvent::Redraw).target(Entity::root()),
+                            );
+                        }
+                    }
+                }
+
+                event.consume();
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if event.target == self.clip {
+                        self.set_clipped(state, false);
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let back_color = color(state, self.back);
+        let peak_color = color(state, self.peak);
+        let rms_color = color(state, self.rms);
+        let hold_color = color(state, self.hold);
+
+        let mut tick_color: femtovg::Color = state
+            .style
+            .font_color
+            .get(self.tick)
+            .cloned()
+            .unwrap_or_default()
+            .into();
+        tick_color.set_alphaf(tick_color.a * opacity);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        let clip_width = state.data.get_width(self.clip);
+        let clip_height = state.data.get_height(self.clip);
+
+        // Space for the clip indicator at the top, or right, and the tick labels at the left, or bottom
+        let gap = 2.0;
+        let label_size = if self.ticks.is_empty() { 0.0 } else { 24.0 };
+
+        let (bars_x, bars_y, bars_w, bars_h) = match self.orientation {
+            Orientation::Vertical => (
+                posx + label_size,
+                posy + clip_height + gap,
+                width - label_size,
+                height - clip_height - gap,
+            ),
+
+            Orientation::Horizontal => (
+                posx,
+                posy,
+                width - clip_width - gap,
+                height - label_size.min(12.0),
+            ),
+        };
+
+        if bars_w <= 0.0 || bars_h <= 0.0 {
+            return;
+        }
+
+        let count = self.channels.len() as f32;
+
+        // The rectangle of a channel filled from the bottom, or left, of the meter up to a position
+        let rect = |index: usize, from: f32, to: f32| -> (f32, f32, f32, f32) {
+            match self.orientation {
+                Orientation::Vertical => {
+                    let w = (bars_w - gap * (count - 1.0)) / count;
+                    let x = bars_x + index as f32 * (w + gap);
+                    (x, bars_y + bars_h * (1.0 - to), w, bars_h * (to - from))
+                }
+
+                Orientation::Horizontal => {
+                    let h = (bars_h - gap * (count - 1.0)) / count;
+                    let y = bars_y + index as f32 * (h + gap);
+                    (bars_x + bars_w * from, y, bars_w * (to - from), h)
+                }
+            }
+        };
+
+        let fill = |canvas: &mut Canvas<OpenGl>, (x, y, w, h): (f32, f32, f32, f32), color: femtovg::Color| {
+            if w > 0.0 && h > 0.0 {
+                let mut path = Path::new();
+                path.rect(x, y, w, h);
+                canvas.fill_path(&mut path, Paint::color(color));
+            }
+        };
+
+        for (index, channel) in self.channels.iter().enumerate() {
+            fill(canvas, rect(index, 0.0, 1.0), back_color);
+
+            let peak = self.position(channel.peak);
+            fill(canvas, rect(index, 0.0, peak), peak_color);
+
+            if self.show_rms {
+                let rms = self.position(channel.rms);
+                fill(canvas, rect(index, 0.0, rms), rms_color);
+            }
+
+            if channel.hold > self.min_db {
+                let hold = self.position(channel.hold);
+                // The marker is two pixels thick, drawn below, or left of, its level
+                let thickness = match self.orientation {
+                    Orientation::Vertical => 2.0 / bars_h,
+                    Orientation::Horizontal => 2.0 / bars_w,
+                };
+                fill(canvas, rect(index, (hold - thickness).max(0.0), hold), hold_color);
+            }
+        }
+
+        if let Some(font_id) = state.fonts.regular {
+            for tick in self.ticks.iter() {
+                let position = self.position(*tick);
+                let label = format!("{}", tick.round());
+
+                let mut paint = Paint::color(tick_color);
+                paint.set_font_size(10.0);
+                paint.set_font(&[font_id]);
+                paint.set_anti_alias(false);
+
+                let (x, y) = match self.orientation {
+                    Orientation::Vertical => {
+                        paint.set_text_align(Align::Right);
+                        paint.set_text_baseline(Baseline::Middle);
+                        (bars_x - gap, bars_y + bars_h * (1.0 - position))
+                    }
+
+                    Orientation::Horizontal => {
+                        paint.set_text_align(Align::Center);
+                        paint.set_text_baseline(Baseline::Top);
+                        (bars_x + bars_w * position, bars_y + bars_h + gap)
+                    }
+                };
+
+                let _ = canvas.fill_text(x, y, &label, paint);
+            }
+        }
+    }
+}
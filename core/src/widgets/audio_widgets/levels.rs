@@ -1,5 +1,5 @@
 use crate::entity::Entity;
-use crate::State;
+use crate::{Role, State};
 use crate::{BuildHandler, Event, EventHandler};
 
 use crate::style::Length;
@@ -31,6 +31,8 @@ impl AudioLevelBar {
 impl BuildHandler for AudioLevelBar {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Meter);
+
         self.front = Element::new().build(state, entity, |builder| {
             builder.class("front")
             //.set_height(Length::Percentage(1.0))
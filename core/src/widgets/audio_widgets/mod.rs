@@ -4,8 +4,29 @@ pub use control_knob::*;
 pub mod levels;
 pub use levels::*;
 
+pub mod level_meter;
+pub use level_meter::*;
+
 pub mod value_knob;
 pub use value_knob::*;
 
 pub mod grid;
 pub use grid::*;
+
+pub mod xy_pad;
+pub use xy_pad::*;
+
+pub mod envelope;
+pub use envelope::*;
+
+pub mod waveform_view;
+pub use waveform_view::*;
+
+pub mod spectrum_view;
+pub use spectrum_view::*;
+
+pub mod piano_keyboard;
+pub use piano_keyboard::*;
+
+pub mod step_sequencer;
+pub use step_sequencer::*;
@@ -0,0 +1,464 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::style::{Display, Visibility};
+use crate::widgets::Element;
+use crate::{BuildHandler, Code, Event, EventHandler, WindowEvent};
+use crate::{PropSet, Role, State};
+
+use femtovg::{renderer::OpenGl, Canvas, Paint, Path};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PianoEvent {
+    // Sent when a key is pressed, with the MIDI note number and a velocity from 0.0 to 1.0
+    NoteOn { note: u8, velocity: f32 },
+    // Sent when a key is released
+    NoteOff { note: u8 },
+    // Highlights or clears a key, e.g. for a note the host is playing
+    Highlight { note: u8, highlighted: bool },
+    // Clears every highlighted key
+    ClearHighlights,
+}
+
+// The computer keys playing the notes of an octave and a half from the keyboard octave, laid out like piano keys
+const KEY_NOTES: [(Code, u8); 17] = [
+    (Code::KeyA, 0),
+    (Code::KeyW, 1),
+    (Code::KeyS, 2),
+    (Code::KeyE, 3),
+    (Code::KeyD, 4),
+    (Code::KeyF, 5),
+    (Code::KeyT, 6),
+    (Code::KeyG, 7),
+    (Code::KeyY, 8),
+    (Code::KeyH, 9),
+    (Code::KeyU, 10),
+    (Code::KeyJ, 11),
+    (Code::KeyK, 12),
+    (Code::KeyO, 13),
+    (Code::KeyL, 14),
+    (Code::KeyP, 15),
+    (Code::Semicolon, 16),
+];
+
+// The size of the black keys relative to the white keys
+const BLACK_WIDTH: f32 = 0.6;
+const BLACK_HEIGHT: f32 = 0.6;
+// The velocity at the top of a key, rising to 1.0 at the bottom
+const MIN_VELOCITY: f32 = 0.1;
+
+fn is_black(note: u8) -> bool {
+    matches!(note % 12, 1 | 3 | 6 | 8 | 10)
+}
+
+/// An on-screen piano keyboard which sends `PianoEvent::NoteOn` and `NoteOff` events
+///
+/// Clicking a key plays it with a velocity from how far down the key it was clicked, softest at the
+/// top. Dragging across the keys plays each key in turn. When focused, the computer keys from A to ;
+/// play an octave and a half from the keyboard octave with W, E, T, Y, U, O and P as the black keys,
+/// while Z and X move the keyboard octave down and up. Keys pressed with Ctrl, Alt or the logo key
+/// are left for shortcuts. Held notes are released when the window loses focus.
+///
+/// Note events are sent to the keyboard and reach its ancestors, with the keyboard as their origin.
+/// Notes played elsewhere, e.g. by the host, can be shown with `Highlight` events.
+///
+/// The white keys are styled by the background color of the `.white` class, the black keys by
+/// `.black`, pressed keys by `.pressed` and highlighted keys by `.highlight`. The background of the
+/// keyboard shows between the white keys.
+///
+/// # Examples
+/// ```ignore
+/// PianoKeyboard::new()
+///     .with_range(36, 84)
+///     .build(state, parent, |builder| builder.set_height(Length::Pixels(80.0)));
+///
+/// // In an ancestor
+/// if let Some(PianoEvent::NoteOn { note, velocity }) = event.message.downcast::<PianoEvent>() {
+///     synth.note_on(*note, *velocity);
+/// }
+/// ```
+pub struct PianoKeyboard {
+    lowest: u8,
+    highest: u8,
+
+    // The note played by the A key
+    keyboard_octave: u8,
+    keyboard_velocity: f32,
+
+    dragging: bool,
+    // The note held by the mouse
+    mouse_note: Option<u8>,
+    // The notes held by the computer keyboard, with the keys holding them
+    key_notes: Vec<(Code, u8)>,
+    highlighted: [bool; 128],
+
+    white: Entity,
+    black: Entity,
+    pressed: Entity,
+    highlight: Entity,
+}
+
+impl PianoKeyboard {
+    /// Creates a keyboard of two octaves from C3 to C5
+    pub fn new() -> Self {
+        PianoKeyboard {
+            lowest: 48,
+            highest: 72,
+
+            keyboard_octave: 48,
+            keyboard_velocity: 0.8,
+
+            dragging: false,
+            mouse_note: None,
+            key_notes: Vec::new(),
+            highlighted: [false; 128],
+
+            white: Entity::null(),
+            black: Entity::null(),
+            pressed: Entity::null(),
+            highlight: Entity::null(),
+        }
+    }
+
+    /// Sets the lowest and highest notes of the keyboard, which are widened to start and end on white keys
+    pub fn with_range(mut self, lowest: u8, highest: u8) -> Self {
+        let (lowest, highest) = (lowest.min(highest).min(127), highest.max(lowest).min(127));
+
+        self.lowest = if is_black(lowest) { lowest - 1 } else { lowest };
+        self.highest = if is_black(highest) { highest + 1 } else { highest };
+
+        // Start the computer keyboard on the lowest C of the keyboard
+        self.keyboard_octave = (self.lowest + 11) / 12 * 12;
+        self
+    }
+
+    /// Sets the note played by the A key, which is rounded down to a C
+    pub fn with_keyboard_octave(mut self, note: u8) -> Self {
+        self.keyboard_octave = note.min(127) / 12 * 12;
+        self
+    }
+
+    /// Sets the velocity of notes played with the computer keyboard, from 0.0 to 1.0
+    pub fn with_keyboard_velocity(mut self, velocity: f32) -> Self {
+        self.keyboard_velocity = velocity.min(1.0).max(0.0);
+        self
+    }
+
+    fn white_count(&self) -> usize {
+        (self.lowest..=self.highest).filter(|note| !is_black(*note)).count()
+    }
+
+    // Returns the rectangle of a key relative to the top left of the keyboard
+    fn key_rect(&self, note: u8, width: f32, height: f32) -> (f32, f32, f32, f32) {
+        let white_width = width / self.white_count().max(1) as f32;
+        let whites_below = (self.lowest..note).filter(|note| !is_black(*note)).count() as f32;
+
+        if is_black(note) {
+            let black_width = white_width * BLACK_WIDTH;
+            (
+                whites_below * white_width - black_width * 0.5,
+                0.0,
+                black_width,
+                height * BLACK_HEIGHT,
+            )
+        } else {
+            (whites_below * white_width, 0.0, white_width, height)
+        }
+    }
+
+    // Returns the note and velocity of the key at a window position
+    fn note_at(&self, state: &State, entity: Entity, x: f32, y: f32) -> Option<(u8, f32)> {
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+        let (x, y) = (x - state.data.get_posx(entity), y - state.data.get_posy(entity));
+
+        if x < 0.0 || x >= width || y < 0.0 || y >= height {
+            return None;
+        }
+
+        // Black keys lie over the white keys so they're checked first
+        let black = (self.lowest..=self.highest).filter(|note| is_black(*note));
+        let white = (self.lowest..=self.highest).filter(|note| !is_black(*note));
+
+        black.chain(white).find_map(|note| {
+            let (key_x, key_y, key_width, key_height) = self.key_rect(note, width, height);
+
+            if x >= key_x && x < key_x + key_width && y >= key_y && y < key_y + key_height {
+                let velocity = MIN_VELOCITY + (1.0 - MIN_VELOCITY) * (y - key_y) / key_height;
+                Some((note, velocity))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn is_pressed(&self, note: u8) -> bool {
+        self.mouse_note == Some(note) || self.key_notes.iter().any(|(_, held)| *held == note)
+    }
+
+    fn send(&self, state: &mut State, entity: Entity, piano_event: PianoEvent) {
+        // Repeated notes within a frame are all sent
+        let mut event = Event::new(piano_event).target(entity).origin(entity);
+        event.unique = false;
+
+        state.insert_event(event);
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    fn note_on(&self, state: &mut State, entity: Entity, note: u8, velocity: f32) {
+        self.send(state, entity, PianoEvent::NoteOn { note, velocity });
+    }
+
+    fn note_off(&self, state: &mut State, entity: Entity, note: u8) {
+        self.send(state, entity, PianoEvent::NoteOff { note });
+    }
+
+    // Releases every note held by the computer keyboard
+    fn release_keys(&mut self, state: &mut State, entity: Entity) {
+        for (_, note) in std::mem::take(&mut self.key_notes) {
+            self.note_off(state, entity, note);
+        }
+    }
+}
+
+impl BuildHandler for PianoKeyboard {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Group).set_tab_index(state, 0);
+
+        let mut style_element = |class: &str| {
+            Element::new().build(state, entity, |builder| {
+                builder
+                    .set_hoverability(false)
+                    .set_display(Display::None)
+                    .class(class)
+            })
+        };
+
+        self.white = style_element("white");
+        self.black = style_element("black");
+        self.pressed = style_element("pressed");
+        self.highlight = style_element("highlight");
+
+        state.style.insert_element(entity, "piano_keyboard");
+
+        entity
+    }
+}
+
+impl EventHandler for PianoKeyboard {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(piano_event) = event.message.downcast::<PianoEvent>() {
+            if event.target == entity {
+                match piano_event {
+                    PianoEvent::Highlight { note, highlighted } => {
+                        if let Some(key) = self.highlighted.get_mut(*note as usize) {
+                            *key = *highlighted;
+                        }
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                        event.consume();
+                    }
+
+                    PianoEvent::ClearHighlights => {
+                        self.highlighted = [false; 128];
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                        event.consume();
+                    }
+
+                    // Note events sent by the keyboard carry on to its ancestors
+                    _ => {}
+                }
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if event.target == entity {
+                        let (x, y) = state.mouse.left.pos_down;
+
+                        if let Some((note, velocity)) = self.note_at(state, entity, x, y) {
+                            self.mouse_note = Some(note);
+                            self.note_on(state, entity, note, velocity);
+                        }
+
+                        self.dragging = true;
+                        state.capture(entity);
+                        state.set_focus(entity);
+                        event.consume();
+                    }
+                }
+
+                // Dragging across the keys plays each key in turn
+                WindowEvent::MouseMove(x, y) => {
+                    if event.target == entity && self.dragging {
+                        let key = self.note_at(state, entity, *x, *y);
+
+                        if key.map(|(note, _)| note) != self.mouse_note {
+                            if let Some(note) = self.mouse_note.take() {
+                                self.note_off(state, entity, note);
+                            }
+
+                            if let Some((note, velocity)) = key {
+                                self.mouse_note = Some(note);
+                                self.note_on(state, entity, note, velocity);
+                            }
+                        }
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if event.target == entity {
+                        if let Some(note) = self.mouse_note.take() {
+                            self.note_off(state, entity, note);
+                        }
+
+                        self.dragging = false;
+                        state.release(entity);
+                    }
+                }
+
+                WindowEvent::KeyDown(code, _) => {
+                    if event.target != entity {
+                        return;
+                    }
+
+                    // Shortcuts such as Ctrl+Z aren't notes or octave changes
+                    if state.modifiers.ctrl || state.modifiers.alt || state.modifiers.logo {
+                        return;
+                    }
+
+                    match code {
+                        Code::KeyZ => {
+                            self.release_keys(state, entity);
+                            self.keyboard_octave = self.keyboard_octave.saturating_sub(12);
+                        }
+
+                        Code::KeyX => {
+                            self.release_keys(state, entity);
+                            if self.keyboard_octave + 12 <= 120 {
+                                self.keyboard_octave += 12;
+                            }
+                        }
+
+                        _ => {
+                            let offset = match KEY_NOTES.iter().find(|(key, _)| key == code) {
+                                Some((_, offset)) => *offset,
+                                None => return,
+                            };
+
+                            // Held keys repeat, which shouldn't play the note again
+                            if self.key_notes.iter().any(|(key, _)| key == code) {
+                                event.consume();
+                                return;
+                            }
+
+                            let note = self.keyboard_octave + offset;
+                            if note <= 127 {
+                                self.key_notes.push((*code, note));
+                                self.note_on(state, entity, note, self.keyboard_velocity);
+                            }
+                        }
+                    }
+
+                    event.consume();
+                }
+
+                WindowEvent::KeyUp(code, _) => {
+                    if event.target == entity {
+                        if let Some(index) = self.key_notes.iter().position(|(key, _)| key == code) {
+                            let (_, note) = self.key_notes.remove(index);
+                            self.note_off(state, entity, note);
+                            event.consume();
+                        }
+                    }
+                }
+
+                // Key releases aren't received without focus, so notes held by the computer keyboard are released
+                WindowEvent::FocusOut => {
+                    if event.target == entity {
+                        self.release_keys(state, entity);
+                    }
+                }
+
+                // Neither key nor mouse releases are received while another window has focus
+                WindowEvent::WindowFocus(false) => {
+                    self.release_keys(state, entity);
+
+                    if let Some(note) = self.mouse_note.take() {
+                        self.note_off(state, entity, note);
+                    }
+
+                    if self.dragging {
+                        self.dragging = false;
+                        state.release(entity);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let background_color = color(state, entity);
+        let white_color = color(state, self.white);
+        let black_color = color(state, self.black);
+        let pressed_color = color(state, self.pressed);
+        let highlight_color = color(state, self.highlight);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        // Draw background
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
+
+        let key_color = |note: u8| {
+            if self.is_pressed(note) {
+                pressed_color
+            } else if self.highlighted[note as usize] {
+                highlight_color
+            } else if is_black(note) {
+                black_color
+            } else {
+                white_color
+            }
+        };
+
+        // Draw the white keys with a pixel between them, then the black keys over them
+        let white = (self.lowest..=self.highest).filter(|note| !is_black(*note));
+        let black = (self.lowest..=self.highest).filter(|note| is_black(*note));
+
+        for note in white.chain(black) {
+            let (x, y, key_width, key_height) = self.key_rect(note, width, height);
+            let gap = if is_black(note) { 0.0 } else { 1.0 };
+
+            let mut path = Path::new();
+            path.rect(posx + x, posy + y, key_width - gap, key_height);
+            canvas.fill_path(&mut path, Paint::color(key_color(note)));
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use crate::entity::Entity;
+use crate::style::{Display, Visibility};
+use crate::widgets::{draw_grid, gain_to_db, Element, GridAxis, LinearMapping, LogMapping};
+use crate::{BuildHandler, Event, EventHandler, WindowEvent};
+use crate::{PropSet, Role, SpectrumBuffer, State};
+
+use femtovg::{renderer::OpenGl, Canvas, LineJoin, Paint, Path};
+
+use std::time::Instant;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpectrumEvent {
+    // Sent to every spectrum view once per frame by `State::update_meters`
+    Frame,
+}
+
+/// A view of the magnitudes in a `SpectrumBuffer` over a grid of frequencies and levels
+///
+/// The x axis maps frequencies, logarithmically from 20 Hz to 20 kHz by default, and the y axis maps
+/// levels in dB, from -90 dB to 0 dB by default. Where several bins fall in one column of pixels the
+/// loudest is shown. The level of each bin moves towards each new spectrum with the smoothing time
+/// constant, so the curve doesn't flicker between spectra.
+///
+/// The curve is styled by the background color of the `.curve` class and the area under it by
+/// `.fill`, with the grid styled by `.line` and `.label` as on a `Grid`.
+pub struct SpectrumView {
+    buffer: Option<SpectrumBuffer>,
+    x_axis: GridAxis,
+    y_axis: GridAxis,
+    // Time constant of the levels in seconds
+    smoothing: f32,
+
+    generation: usize,
+    last_frame: Option<Instant>,
+    // The latest magnitudes taken from the buffer
+    magnitudes: Vec<f32>,
+    // The levels to move towards and the levels shown, in dB
+    targets: Vec<f32>,
+    levels: Vec<f32>,
+
+    line: Entity,
+    label: Entity,
+    curve: Entity,
+    fill: Entity,
+}
+
+impl SpectrumView {
+    pub fn new() -> Self {
+        SpectrumView {
+            buffer: None,
+            x_axis: GridAxis::new(LogMapping::new(20.0, 20000.0).with_unit("Hz"))
+                .with_lines(&[50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0]),
+            y_axis: GridAxis::new(LinearMapping::new(-90.0, 0.0).with_unit("dB"))
+                .with_lines(&[-72.0, -54.0, -36.0, -18.0]),
+            smoothing: 0.1,
+
+            generation: 0,
+            last_frame: None,
+            magnitudes: Vec::new(),
+            targets: Vec::new(),
+            levels: Vec::new(),
+
+            line: Entity::null(),
+            label: Entity::null(),
+            curve: Entity::null(),
+            fill: Entity::null(),
+        }
+    }
+
+    /// Shows the spectra published to a buffer by the audio or analysis thread
+    pub fn with_buffer(mut self, buffer: SpectrumBuffer) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// Sets the axis of frequencies in Hz
+    pub fn with_x_axis(mut self, axis: GridAxis) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    /// Sets the axis of levels in dB
+    pub fn with_y_axis(mut self, axis: GridAxis) -> Self {
+        self.y_axis = axis;
+        self
+    }
+
+    /// Sets the time constant in seconds with which the levels follow new spectra, or 0.0 to show each spectrum as it is
+    pub fn with_smoothing(mut self, seconds: f32) -> Self {
+        self.smoothing = seconds.max(0.0);
+        self
+    }
+
+    // The level shown for silence, at the bottom of the view
+    fn floor(&self) -> f32 {
+        self.y_axis.mapping.min().min(self.y_axis.mapping.max())
+    }
+
+    // Moves the shown levels towards their targets, returning whether anything moved
+    fn smooth(&mut self, dt: f32) -> bool {
+        let coefficient = if self.smoothing > 0.0 {
+            1.0 - (-dt / self.smoothing).exp()
+        } else {
+            1.0
+        };
+
+        let mut moved = false;
+
+        for (level, target) in self.levels.iter_mut().zip(self.targets.iter()) {
+            let previous = *level;
+
+            *level += (target - *level) * coefficient;
+            // Stop the level creeping towards its target once the difference can't be seen
+            if (target - *level).abs() < 0.01 {
+                *level = *target;
+            }
+
+            if *level != previous {
+                moved = true;
+            }
+        }
+
+        moved
+    }
+}
+
+impl BuildHandler for SpectrumView {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Image);
+
+        let mut style_element = |class: &str| {
+            Element::new().build(state, entity, |builder| {
+                builder
+                    .set_hoverability(false)
+                    .set_display(Display::None)
+                    .class(class)
+            })
+        };
+
+        self.line = style_element("line");
+        self.label = style_element("label");
+        self.curve = style_element("curve");
+        self.fill = style_element("fill");
+
+        state.meters.add(entity, || Event::new(SpectrumEvent::Frame));
+
+        state.style.insert_element(entity, "spectrum_view");
+
+        entity
+    }
+}
+
+impl EventHandler for SpectrumView {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(spectrum_event) = event.message.downcast::<SpectrumEvent>() {
+            if event.target == entity {
+                match spectrum_event {
+                    SpectrumEvent::Frame => {
+                        if let Some(buffer) = self.buffer.clone() {
+                            let generation = buffer.generation();
+
+                            if generation != self.generation {
+                                self.generation = generation;
+
+                                buffer.read(&mut self.magnitudes);
+
+                                let floor = self.floor();
+                                self.targets.clear();
+                                self.targets.extend(
+                                    self.magnitudes
+                                        .iter()
+                                        .map(|magnitude| gain_to_db(magnitude.abs()).max(floor)),
+                                );
+
+                                self.levels.resize(self.targets.len(), floor);
+                            }
+                        }
+
+                        let now = Instant::now();
+                        // A long gap between frames, e.g. while the window was hidden, is treated as one frame
+                        let dt = match self.last_frame {
+                            Some(last_frame) => (now - last_frame).as_secs_f32().min(0.1),
+                            None => 0.0,
+                        };
+                        self.last_frame = Some(now);
+
+                        if self.smooth(dt) {
+                            state.insert_event(
+                                Event::new(WindowEvent::Redraw).target(Entity::root()),
+                            );
+                        }
+                    }
+                }
+
+                event.consume();
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let background_color = color(state, entity);
+        let curve_color = color(state, self.curve);
+        let fill_color = color(state, self.fill);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        // Draw background
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
+
+        draw_grid(
+            state,
+            canvas,
+            &self.x_axis,
+            &self.y_axis,
+            (self.line, self.label),
+            (posx, posy, width, height),
+            opacity,
+        );
+
+        let buffer = match &self.buffer {
+            Some(buffer) => buffer,
+            None => return,
+        };
+
+        let min_frequency = self.x_axis.mapping.min().min(self.x_axis.mapping.max());
+        let max_frequency = self.x_axis.mapping.min().max(self.x_axis.mapping.max());
+
+        // Find the loudest bin in each column, skipping the bin at 0 Hz which can't be shown on a log axis
+        let mut points: Vec<(f32, f32)> = Vec::new();
+        for (bin, level) in self.levels.iter().enumerate().skip(1) {
+            let frequency = buffer.frequency(bin);
+
+            if frequency < min_frequency || frequency > max_frequency {
+                continue;
+            }
+
+            let x = posx + self.x_axis.position(frequency) * width;
+            let y = posy + (1.0 - self.y_axis.position(*level)) * height;
+
+            match points.last_mut() {
+                Some(last) if x.floor() == last.0.floor() => {
+                    last.1 = last.1.min(y);
+                }
+
+                _ => points.push((x, y)),
+            }
+        }
+
+        if points.len() < 2 {
+            return;
+        }
+
+        let bottom = posy + height;
+
+        let mut path = Path::new();
+        path.move_to(points[0].0, bottom);
+        for (x, y) in points.iter() {
+            path.line_to(*x, *y);
+        }
+        path.line_to(points[points.len() - 1].0, bottom);
+        path.close();
+        canvas.fill_path(&mut path, Paint::color(fill_color));
+
+        let mut path = Path::new();
+        path.move_to(points[0].0, points[0].1);
+        for (x, y) in points.iter().skip(1) {
+            path.line_to(*x, *y);
+        }
+
+        let mut paint = Paint::color(curve_color);
+        paint.set_line_width(1.5);
+        paint.set_line_join(LineJoin::Round);
+        canvas.stroke_path(&mut path, paint);
+    }
+}
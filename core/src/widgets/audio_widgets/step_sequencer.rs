@@ -0,0 +1,627 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::style::{Display, Visibility};
+use crate::widgets::Element;
+use crate::{BuildHandler, Code, Event, EventHandler, WindowEvent};
+use crate::{PropSet, Role, State};
+
+use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Paint, Path};
+
+/// A cell of a `StepSequencer`, which plays when it is on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Step {
+    pub on: bool,
+    // From 0.0 to 1.0, kept while the step is off so it returns when the step is turned back on
+    pub velocity: f32,
+}
+
+impl Step {
+    pub fn on(velocity: f32) -> Self {
+        Step { on: true, velocity }
+    }
+
+    pub fn off() -> Self {
+        Step {
+            on: false,
+            velocity: 0.8,
+        }
+    }
+}
+
+/// A selected row or column of a `StepSequencer`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SequencerSelection {
+    Row(usize),
+    Column(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequencerEvent {
+    // Sets one step
+    SetStep { row: usize, step: usize, value: Step },
+    // Loads every step, with a vector of steps for each row
+    LoadMatrix(Vec<Vec<Step>>),
+    // Asks the sequencer to send its steps in a `Matrix` event to the origin of the request
+    StoreMatrix,
+    // The steps of every row, sent in reply to `StoreMatrix`
+    Matrix(Vec<Vec<Step>>),
+    // Moves the playhead to a step, or hides it
+    SetPlayhead(Option<usize>),
+    // Selects a row or column, or clears the selection
+    Select(Option<SequencerSelection>),
+}
+
+// What the mouse is doing since it was pressed on a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SequencerDrag {
+    // Pressed on a step which was on, until the drag shows whether it paints or sets the velocity
+    Pending { row: usize, step: usize },
+    // Turning on, or off, each step the mouse passes over
+    Paint { on: bool },
+    // Setting the velocity of a step, with the velocity it had when pressed
+    Velocity { row: usize, step: usize, velocity: f32 },
+}
+
+// The height of the step numbers above the steps
+const HEADER_HEIGHT: f32 = 14.0;
+// The vertical drag in pixels which changes the velocity from 0 to 1
+const VELOCITY_DRAG: f32 = 100.0;
+// The vertical movement in pixels before pressing a step starts setting its velocity
+const DRAG_THRESHOLD: f32 = 3.0;
+const MIN_VELOCITY: f32 = 0.05;
+
+/// A grid of steps in rows, e.g. one row for each drum sound, for a step sequencer
+///
+/// Clicking a step toggles it, like a `Checkbox`, and dragging from it turns every step the mouse
+/// passes over on or off to match. Dragging up or down from a step which is on sets its velocity,
+/// shown by how much of the step is filled. Clicking a row label selects the row, clicking a step
+/// number selects the column, and pressing Delete turns off the selected steps. A playhead column,
+/// set with `SetPlayhead` events, shows the step being played.
+///
+/// Each change made by the user sends the `on_change` event with the row, step and new value. The
+/// whole matrix is loaded with a `LoadMatrix` event and stored by sending `StoreMatrix`, which is
+/// answered with a `Matrix` event.
+///
+/// Steps are styled by the background color of the `.step` class, with the filled part of steps which
+/// are on styled by `.on`. The playhead is styled by `.playhead`, the selection by `.selection` and
+/// the labels and step numbers by the font color of `.label`.
+pub struct StepSequencer {
+    matrix: Vec<Vec<Step>>,
+    row_labels: Vec<String>,
+    label_width: f32,
+    default_velocity: f32,
+
+    playhead: Option<usize>,
+    selection: Option<SequencerSelection>,
+    drag: Option<SequencerDrag>,
+
+    on_change: Option<Box<dyn Fn(usize, usize, Step) -> Event + Send>>,
+    on_select: Option<Box<dyn Fn(Option<SequencerSelection>) -> Event + Send>>,
+
+    step: Entity,
+    on: Entity,
+    playhead_marker: Entity,
+    selected: Entity,
+    label: Entity,
+}
+
+impl StepSequencer {
+    pub fn new(rows: usize, steps: usize) -> Self {
+        StepSequencer {
+            matrix: vec![vec![Step::off(); steps.max(1)]; rows.max(1)],
+            row_labels: Vec::new(),
+            label_width: 0.0,
+            default_velocity: 0.8,
+
+            playhead: None,
+            selection: None,
+            drag: None,
+
+            on_change: None,
+            on_select: None,
+
+            step: Entity::null(),
+            on: Entity::null(),
+            playhead_marker: Entity::null(),
+            selected: Entity::null(),
+            label: Entity::null(),
+        }
+    }
+
+    /// Shows labels to the left of the rows, 60 pixels wide unless set with `with_label_width`
+    pub fn with_row_labels(mut self, labels: &[&str]) -> Self {
+        self.row_labels = labels.iter().map(|label| label.to_string()).collect();
+
+        if self.label_width == 0.0 {
+            self.label_width = 60.0;
+        }
+
+        self
+    }
+
+    pub fn with_label_width(mut self, width: f32) -> Self {
+        self.label_width = width;
+        self
+    }
+
+    /// Sets the steps of every row, keeping the number of rows and steps of the sequencer
+    pub fn with_matrix(mut self, matrix: Vec<Vec<Step>>) -> Self {
+        self.load(matrix);
+        self
+    }
+
+    /// Sets the velocity of steps which are turned on for the first time
+    pub fn with_default_velocity(mut self, velocity: f32) -> Self {
+        self.default_velocity = velocity.min(1.0).max(MIN_VELOCITY);
+
+        for step in self.matrix.iter_mut().flatten().filter(|step| !step.on) {
+            step.velocity = self.default_velocity;
+        }
+
+        self
+    }
+
+    /// Sets the event sent with the row, step and new value when the user changes a step
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(usize, usize, Step) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+        self
+    }
+
+    /// Sets the event sent when the user selects a row or column or clears the selection
+    pub fn on_select<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(Option<SequencerSelection>) -> Event + Send,
+    {
+        self.on_select = Some(Box::new(message));
+        self
+    }
+
+    fn rows(&self) -> usize {
+        self.matrix.len()
+    }
+
+    fn steps(&self) -> usize {
+        self.matrix[0].len()
+    }
+
+    // Copies a matrix into the steps, ignoring rows and steps past the end of the sequencer
+    fn load(&mut self, matrix: Vec<Vec<Step>>) {
+        for (row, steps) in self.matrix.iter_mut().zip(matrix) {
+            for (step, value) in row.iter_mut().zip(steps) {
+                *step = value;
+            }
+        }
+    }
+
+    // Sets a step, sending the change event if it changed
+    fn set_step(&mut self, state: &mut State, entity: Entity, row: usize, step: usize, value: Step) {
+        if self.matrix[row][step] == value {
+            return;
+        }
+
+        self.matrix[row][step] = value;
+
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(row, step, value);
+            if !event.target {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+            // Painting changes several steps within a frame, which are all sent
+            event.unique = false;
+            state.insert_event(event);
+        }
+
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    fn set_on(&mut self, state: &mut State, entity: Entity, row: usize, step: usize, on: bool) {
+        let value = Step {
+            on,
+            ..self.matrix[row][step]
+        };
+
+        self.set_step(state, entity, row, step, value);
+    }
+
+    fn select(&mut self, state: &mut State, entity: Entity, selection: Option<SequencerSelection>) {
+        self.selection = selection;
+
+        if let Some(on_select) = &self.on_select {
+            let mut event = (on_select)(selection);
+            if !event.target {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+            state.insert_event(event);
+        }
+
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Returns the position and size of the first step and the size of each step
+    fn layout(&self, state: &State, entity: Entity) -> (f32, f32, f32, f32) {
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        (
+            posx + self.label_width,
+            posy + HEADER_HEIGHT,
+            ((width - self.label_width) / self.steps() as f32).max(0.0),
+            ((height - HEADER_HEIGHT) / self.rows() as f32).max(0.0),
+        )
+    }
+
+    // Returns the row and step at a window position
+    fn step_at(&self, state: &State, entity: Entity, x: f32, y: f32) -> Option<(usize, usize)> {
+        let (left, top, step_width, step_height) = self.layout(state, entity);
+
+        if x < left || y < top || step_width <= 0.0 || step_height <= 0.0 {
+            return None;
+        }
+
+        let (row, step) = (
+            ((y - top) / step_height) as usize,
+            ((x - left) / step_width) as usize,
+        );
+
+        if row < self.rows() && step < self.steps() {
+            Some((row, step))
+        } else {
+            None
+        }
+    }
+
+    // Returns the row or column whose label or step number is at a window position
+    fn header_at(&self, state: &State, entity: Entity, x: f32, y: f32) -> Option<SequencerSelection> {
+        let (left, top, step_width, step_height) = self.layout(state, entity);
+
+        if y < top && x >= left && step_width > 0.0 {
+            let step = ((x - left) / step_width) as usize;
+            return Some(SequencerSelection::Column(step)).filter(|_| step < self.steps());
+        }
+
+        if x < left && y >= top && step_height > 0.0 {
+            let row = ((y - top) / step_height) as usize;
+            return Some(SequencerSelection::Row(row)).filter(|_| row < self.rows());
+        }
+
+        None
+    }
+}
+
+impl BuildHandler for StepSequencer {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Table).set_tab_index(state, 0);
+
+        let mut style_element = |class: &str| {
+            Element::new().build(state, entity, |builder| {
+                builder
+                    .set_hoverability(false)
+                    .set_display(Display::None)
+                    .class(class)
+            })
+        };
+
+        self.step = style_element("step");
+        self.on = style_element("on");
+        self.playhead_marker = style_element("playhead");
+        self.selected = style_element("selection");
+        self.label = style_element("label");
+
+        state.style.insert_element(entity, "step_sequencer");
+
+        entity
+    }
+}
+
+impl EventHandler for StepSequencer {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(sequencer_event) = event.message.downcast::<SequencerEvent>() {
+            if event.target == entity && event.origin != entity {
+                match sequencer_event {
+                    SequencerEvent::SetStep { row, step, value } => {
+                        if *row < self.rows() && *step < self.steps() {
+                            self.matrix[*row][*step] = *value;
+                        }
+                    }
+
+                    SequencerEvent::LoadMatrix(matrix) => {
+                        self.load(matrix.clone());
+                    }
+
+                    SequencerEvent::StoreMatrix => {
+                        // Without an origin the matrix goes to the ancestors of the sequencer
+                        let target = if event.origin == Entity::null() {
+                            entity
+                        } else {
+                            event.origin
+                        };
+
+                        state.insert_event(
+                            Event::new(SequencerEvent::Matrix(self.matrix.clone()))
+                                .target(target)
+                                .origin(entity),
+                        );
+                    }
+
+                    SequencerEvent::Matrix(_) => {}
+
+                    SequencerEvent::SetPlayhead(playhead) => {
+                        self.playhead = playhead.filter(|step| *step < self.steps());
+                    }
+
+                    SequencerEvent::Select(selection) => {
+                        self.selection = *selection;
+                    }
+                }
+
+                state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                event.consume();
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if event.target == entity {
+                        let (x, y) = state.mouse.left.pos_down;
+
+                        if let Some((row, step)) = self.step_at(state, entity, x, y) {
+                            if self.matrix[row][step].on {
+                                self.drag = Some(SequencerDrag::Pending { row, step });
+                            } else {
+                                self.drag = Some(SequencerDrag::Paint { on: true });
+                                self.set_on(state, entity, row, step, true);
+                            }
+
+                            state.capture(entity);
+                        } else if let Some(header) = self.header_at(state, entity, x, y) {
+                            // Clicking the selected row or column again clears the selection
+                            let selection = if self.selection == Some(header) {
+                                None
+                            } else {
+                                Some(header)
+                            };
+
+                            self.select(state, entity, selection);
+                        }
+
+                        state.set_focus(entity);
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseMove(x, y) => {
+                    if event.target != entity {
+                        return;
+                    }
+
+                    let drag = match self.drag {
+                        Some(drag) => drag,
+                        None => return,
+                    };
+
+                    let under = self.step_at(state, entity, *x, *y);
+                    let dy = *y - state.mouse.left.pos_down.1;
+
+                    match drag {
+                        SequencerDrag::Pending { row, step } => {
+                            if under.is_some() && under != Some((row, step)) {
+                                // Dragging to another step turns off every step passed over
+                                self.drag = Some(SequencerDrag::Paint { on: false });
+                                self.set_on(state, entity, row, step, false);
+
+                                if let Some((row, step)) = under {
+                                    self.set_on(state, entity, row, step, false);
+                                }
+                            } else if dy.abs() > DRAG_THRESHOLD {
+                                self.drag = Some(SequencerDrag::Velocity {
+                                    row,
+                                    step,
+                                    velocity: self.matrix[row][step].velocity,
+                                });
+                            }
+                        }
+
+                        SequencerDrag::Paint { on } => {
+                            if let Some((row, step)) = under {
+                                self.set_on(state, entity, row, step, on);
+                            }
+                        }
+
+                        SequencerDrag::Velocity {
+                            row,
+                            step,
+                            velocity,
+                        } => {
+                            // Dragging up makes the step louder
+                            let velocity = (velocity - dy / VELOCITY_DRAG).min(1.0).max(MIN_VELOCITY);
+
+                            self.set_step(state, entity, row, step, Step::on(velocity));
+                        }
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if event.target == entity {
+                        // Clicking a step which is on, without dragging, turns it off
+                        if let Some(SequencerDrag::Pending { row, step }) = self.drag {
+                            self.set_on(state, entity, row, step, false);
+                        }
+
+                        self.drag = None;
+                        state.release(entity);
+                    }
+                }
+
+                WindowEvent::KeyDown(code, _) => {
+                    if event.target != entity {
+                        return;
+                    }
+
+                    match code {
+                        Code::Delete | Code::Backspace => {
+                            let steps: Vec<(usize, usize)> = match self.selection {
+                                Some(SequencerSelection::Row(row)) => {
+                                    (0..self.steps()).map(|step| (row, step)).collect()
+                                }
+
+                                Some(SequencerSelection::Column(step)) => {
+                                    (0..self.rows()).map(|row| (row, step)).collect()
+                                }
+
+                                None => return,
+                            };
+
+                            for (row, step) in steps {
+                                self.set_on(state, entity, row, step, false);
+                            }
+
+                            event.consume();
+                        }
+
+                        Code::Escape => {
+                            if self.selection.is_some() {
+                                self.select(state, entity, None);
+                                event.consume();
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let background_color = color(state, entity);
+        let step_color = color(state, self.step);
+        let on_color = color(state, self.on);
+        let playhead_color = color(state, self.playhead_marker);
+        let selection_color = color(state, self.selected);
+
+        let mut label_color: femtovg::Color = state
+            .style
+            .font_color
+            .get(self.label)
+            .cloned()
+            .unwrap_or_default()
+            .into();
+        label_color.set_alphaf(label_color.a * opacity);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        let (left, top, step_width, step_height) = self.layout(state, entity);
+
+        let fill = |canvas: &mut Canvas<OpenGl>, (x, y, w, h): (f32, f32, f32, f32), color: femtovg::Color| {
+            if w > 0.0 && h > 0.0 {
+                let mut path = Path::new();
+                path.rect(x, y, w, h);
+                canvas.fill_path(&mut path, Paint::color(color));
+            }
+        };
+
+        // Draw background
+        fill(canvas, (posx, posy, width, height), background_color);
+
+        // Draw steps, with a pixel between them, filled from the bottom by their velocity when on
+        for (row, steps) in self.matrix.iter().enumerate() {
+            for (index, step) in steps.iter().enumerate() {
+                let x = left + index as f32 * step_width;
+                let y = top + row as f32 * step_height;
+                let (w, h) = (step_width - 1.0, step_height - 1.0);
+
+                fill(canvas, (x, y, w, h), step_color);
+
+                if step.on {
+                    let filled = h * step.velocity;
+                    fill(canvas, (x, y + h - filled, w, filled), on_color);
+                }
+            }
+        }
+
+        // Draw playhead
+        if let Some(playhead) = self.playhead {
+            let x = left + playhead as f32 * step_width;
+            fill(canvas, (x, top, step_width - 1.0, height - HEADER_HEIGHT), playhead_color);
+        }
+
+        // Draw selection over the steps and their label or number
+        match self.selection {
+            Some(SequencerSelection::Row(row)) if row < self.rows() => {
+                let y = top + row as f32 * step_height;
+                fill(canvas, (posx, y, width, step_height - 1.0), selection_color);
+            }
+
+            Some(SequencerSelection::Column(step)) if step < self.steps() => {
+                let x = left + step as f32 * step_width;
+                fill(canvas, (x, posy, step_width - 1.0, height), selection_color);
+            }
+
+            _ => {}
+        }
+
+        let font_id = match state.fonts.regular {
+            Some(font_id) => font_id,
+            None => return,
+        };
+
+        let label_paint = |align: Align| {
+            let mut paint = Paint::color(label_color);
+            paint.set_font_size(10.0);
+            paint.set_font(&[font_id]);
+            paint.set_text_align(align);
+            paint.set_text_baseline(Baseline::Middle);
+            paint.set_anti_alias(false);
+            paint
+        };
+
+        // Draw the numbers of the first step of each beat of four steps
+        for index in (0..self.steps()).step_by(4) {
+            let x = left + (index as f32 + 0.5) * step_width;
+            let text = (index + 1).to_string();
+
+            let _ = canvas.fill_text(x, posy + HEADER_HEIGHT * 0.5, &text, label_paint(Align::Center));
+        }
+
+        // Draw row labels
+        for (row, label) in self.row_labels.iter().enumerate().take(self.rows()) {
+            let y = top + (row as f32 + 0.5) * step_height;
+
+            let _ = canvas.fill_text(posx + 4.0, y, label, label_paint(Align::Left));
+        }
+    }
+}
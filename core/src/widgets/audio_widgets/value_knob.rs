@@ -2,11 +2,15 @@ use crate::state::{Entity, State};
 
 use crate::events::{BuildHandler, Event, EventHandler, Propagation};
 
-use crate::widgets::{ControlKnob, Label, SliderEvent, Textbox, TextboxEvent};
+use crate::widgets::{
+    reset_edit, ControlKnob, KnobSkin, Label, SliderEvent, Textbox, TextboxEvent,
+};
+use crate::widgets::{format_value, LinearMapping, LogMapping, ValueMapping};
 
 use crate::state::style::*;
 
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
+
 // const VALUE_SLIDER_STYLE: &str = r#"
 
 //     slider
@@ -31,26 +35,17 @@ use std::sync::{Arc, Mutex};
 //     }
 // "#;
 
+#[deprecated(note = "use `format_value` or a `ValueMapping` to format values instead")]
 pub struct FreqValue(pub f32);
 
+#[allow(deprecated)]
 impl std::fmt::Display for FreqValue {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if self.0.abs() < 10.0 {
-            write!(f, "{:.2}", self.0)
-        } else if self.0.abs() >= 10.0 && self.0.abs() < 100.0 {
-            write!(f, "{:.1}", self.0)
-        } else if self.0.abs() >= 100.0 && self.0.abs() < 1000.0 {
-            write!(f, "{:.0}", self.0)
-        } else if self.0.abs() >= 1000.0 && self.0.abs() < 10000.0 {
-            write!(f, "{:.2}", self.0 / 1000.0)
-        } else if self.0.abs() >= 10000.0 && self.0.abs() < 100000.0 {
-            write!(f, "{:.1}", self.0 / 1000.0)
-        } else {
-            write!(f, "{}", self.0)
-        }
+        write!(f, "{}", format_value(self.0, ""))
     }
 }
 
+#[allow(deprecated)]
 impl From<f32> for FreqValue {
     fn from(src: f32) -> FreqValue {
         FreqValue(src)
@@ -63,12 +58,15 @@ pub struct ValueKnob {
     pub textbox: Entity,
 
     init: f32,
-    min: f32,
-    max: f32,
+    default: f32,
 
+    mapping: Arc<dyn ValueMapping>,
+    // Set by the deprecated range options so they keep a logarithmic mapping
     is_log: bool,
 
-    pub on_change: Option<Arc<Mutex<dyn Fn(f32) -> Event + Send>>>,
+    skin: KnobSkin,
+
+    pub on_change: Option<Box<dyn Fn(f32) -> Event + Send>>,
 }
 
 impl ValueKnob {
@@ -79,29 +77,69 @@ impl ValueKnob {
             textbox: Entity::null(),
 
             init,
-            min,
-            max,
+            default: init,
 
+            mapping: Arc::new(LinearMapping::new(min, max)),
             is_log: false,
 
+            skin: KnobSkin::Vector,
+
             on_change: None,
         }
     }
 
+    /// Sets how the knob maps its position to a value and how the textbox shows and parses the value
+    pub fn with_mapping<M: ValueMapping + 'static>(mut self, mapping: M) -> Self {
+        self.mapping = Arc::new(mapping);
+        self.is_log = false;
+
+        self
+    }
+
+    /// Maps the position of the knob to a value logarithmically between min and max
+    #[deprecated(note = "use `with_mapping(LogMapping::new(min, max))` instead")]
     pub fn with_log_scale(mut self) -> Self {
         self.is_log = true;
+        self.set_range(self.mapping.min(), self.mapping.max());
 
         self
     }
 
+    /// Sets the minimum of the range, keeping a linear or logarithmic mapping
+    #[deprecated(note = "use `with_mapping` with the range of the mapping instead")]
     pub fn with_minium(mut self, min: f32) -> Self {
-        self.min = min;
+        self.set_range(min, self.mapping.max());
+
+        self
+    }
+
+    /// Sets the maximum of the range, keeping a linear or logarithmic mapping
+    #[deprecated(note = "use `with_mapping` with the range of the mapping instead")]
+    pub fn with_maximum(mut self, max: f32) -> Self {
+        self.set_range(self.mapping.min(), max);
+
+        self
+    }
+
+    // Replaces the mapping set by the deprecated options with one of the same kind over a new range
+    fn set_range(&mut self, min: f32, max: f32) {
+        if self.is_log {
+            self.mapping = Arc::new(LogMapping::new(min, max));
+        } else {
+            self.mapping = Arc::new(LinearMapping::new(min, max));
+        }
+    }
+
+    /// Sets the value the knob is reset to when double clicked, which is the initial value by default
+    pub fn with_default(mut self, default: f32) -> Self {
+        self.default = default;
 
         self
     }
 
-    pub fn with_maximum(mut self, min: f32) -> Self {
-        self.min = min;
+    /// Sets how the knob is drawn, which is with vector arcs by default
+    pub fn with_skin(mut self, skin: KnobSkin) -> Self {
+        self.skin = skin;
 
         self
     }
@@ -111,9 +149,17 @@ impl ValueKnob {
         F: Fn(f32) -> Event,
         F: 'static + Send,
     {
-        self.on_change = Some(Arc::new(Mutex::new(message)));
+        self.on_change = Some(Box::new(message));
         self
     }
+
+    fn set_text(&self, state: &mut State, value: f32) {
+        state.insert_event(
+            Event::new(TextboxEvent::SetValue(self.mapping.format(value)))
+                .target(self.textbox)
+                .propagate(Propagation::Direct),
+        );
+    }
 }
 
 impl BuildHandler for ValueKnob {
@@ -125,10 +171,11 @@ impl BuildHandler for ValueKnob {
                 .set_text_justify(Justify::Center)
         });
 
-        let mut knob = ControlKnob::new(self.init, self.min, self.max);
+        let mut knob = ControlKnob::new(self.init, self.mapping.min(), self.mapping.max())
+            .with_default(self.default)
+            .with_skin(self.skin.clone());
 
-        knob.on_change = self.on_change.clone();
-        knob.is_log = self.is_log;
+        knob.mapping = self.mapping.clone();
 
         self.knob = knob.build(state, entity, |builder| {
             builder
@@ -136,9 +183,8 @@ impl BuildHandler for ValueKnob {
                 .set_height(Length::Pixels(50.0))
         });
 
-        //let val_str = format!("{:3}!", self.init);
-        let freq_val: FreqValue = self.init.into();
-        self.textbox = Textbox::new(&freq_val.to_string()).build(state, entity, |builder| {
+        let text = self.mapping.format(self.mapping.snap(self.init));
+        self.textbox = Textbox::new(&text).build(state, entity, |builder| {
             builder
                 .set_height(Length::Pixels(25.0))
                 .set_margin_left(Length::Pixels(2.5))
@@ -157,28 +203,21 @@ impl EventHandler for ValueKnob {
         if let Some(slider_event) = event.message.downcast::<SliderEvent>() {
             match slider_event {
                 SliderEvent::ValueChanged(val) => {
-                    //println!("Slider Value Changed: {} {}", self.label, val);
                     if event.target == self.knob {
-                        // let val_str = format!("{:3}!", val);
-                        let freq_val: FreqValue = (*val).into();
-                        //println!("val_str: {} {}", self.label, val_str);
-                        state.insert_event(
-                            Event::new(TextboxEvent::SetValue(freq_val.to_string()))
-                                .target(self.textbox)
-                                .propagate(Propagation::Direct),
-                        );
+                        self.set_text(state, *val);
+
+                        if let Some(on_change) = &self.on_change {
+                            let mut event = (on_change)(*val);
+                            event.origin = entity;
+
+                            state.insert_event(event);
+                        }
                     }
                 }
 
                 SliderEvent::SetValue(val) => {
                     if event.target == entity {
-                        let freq_val: FreqValue = (*val).into();
-                        //println!("val_str: {} {}", self.label, val_str);
-                        state.insert_event(
-                            Event::new(TextboxEvent::SetValue(freq_val.to_string()))
-                                .target(self.textbox)
-                                .propagate(Propagation::Direct),
-                        );
+                        self.set_text(state, self.mapping.snap(*val));
 
                         state.insert_event(
                             Event::new(SliderEvent::SetValue(*val))
@@ -190,33 +229,33 @@ impl EventHandler for ValueKnob {
             }
         }
 
-        /*
         if let Some(textbox_event) = event.message.downcast::<TextboxEvent>() {
             match textbox_event {
                 TextboxEvent::ValueChanged(text) => {
-                    //println!("Textbox Value Changed:{}", text);
-                    if event.target == self.value {
-                        if let Ok(value) = text.parse::<f32>() {
-                            let val = (value.min(self.max_value)).max(self.min_value);
-
-                            let val_str = format!("{:3}!", val);
-                            state.insert_event(
-                                Event::new(TextboxEvent::SetValue(val_str))
-                                    .target(self.value)
-                                    .propagate(Propagation::Direct),
-                            );
-
-                            state.insert_event(
-                                Event::new(SliderEvent::SetValue(self.slider, val))
-                                    .target(self.slider)
-                                    .propagate(Propagation::Direct),
-                            );
-                        } else {
-                            state.insert_event(
-                                Event::new(TextboxEvent::ResetValue)
-                                    .target(self.value)
-                                    .propagate(Propagation::Direct),
-                            );
+                    if event.target == self.textbox {
+                        match self.mapping.parse(text) {
+                            Some(val) => {
+                                state.insert_event(
+                                    Event::new(SliderEvent::SetValue(val))
+                                        .target(self.knob)
+                                        .propagate(Propagation::Direct),
+                                );
+
+                                // A typed value is a complete edit of the knob
+                                reset_edit(state, self.knob, val, self.mapping.to_normalized(val));
+
+                                state.insert_event(
+                                    Event::new(SliderEvent::ValueChanged(val)).target(self.knob),
+                                );
+                            }
+
+                            None => {
+                                state.insert_event(
+                                    Event::new(TextboxEvent::ResetValue)
+                                        .target(self.textbox)
+                                        .propagate(Propagation::Direct),
+                                );
+                            }
                         }
                     }
                 }
@@ -224,6 +263,5 @@ impl EventHandler for ValueKnob {
                 _ => {}
             }
         }
-        */
     }
 }
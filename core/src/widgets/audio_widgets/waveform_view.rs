@@ -0,0 +1,476 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::style::{Display, Visibility};
+use crate::widgets::Element;
+use crate::{BuildHandler, Event, EventHandler, WindowEvent};
+use crate::{PropSet, Role, State, WaveformBuffer};
+
+use femtovg::{renderer::OpenGl, Canvas, Paint, Path};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WaveformEvent {
+    // Sets the number of samples shown by each pixel, zooming around the left edge of the view
+    SetZoom(f32),
+    // Zooms out to show the whole buffer, and keeps doing so as samples are added
+    ZoomToFit,
+    // Scrolls so the sample at an index is at the left edge of the view
+    SetOffset(f32),
+    // Selects the samples from the first index up to the second
+    SetSelection(Option<(usize, usize)>),
+    // Sent to every waveform view once per frame by `State::update_meters`
+    Frame,
+}
+
+// The fewest samples shown by a pixel when zoomed in, so each sample is 32 pixels apart
+const MIN_SAMPLES_PER_PIXEL: f32 = 1.0 / 32.0;
+// The distance scrolled by one line of the mouse wheel, in pixels
+const SCROLL_PIXELS: f32 = 40.0;
+
+/// A view of the samples in a `WaveformBuffer`, with a selection and playhead
+///
+/// When zoomed out each column of pixels shows the lowest and highest sample it covers, so peaks are
+/// never lost however far the view is zoomed out. When zoomed in past one sample per pixel the
+/// samples are joined by lines. The view fits the whole buffer until it is zoomed.
+///
+/// The mouse wheel scrolls the view, and zooms around the cursor while control is held. Dragging
+/// selects a range of samples, sending the `on_select` event when released, and clicking clears the
+/// selection. The playhead is taken from the buffer, and with `with_follow_playhead` the view scrolls
+/// to keep it in sight.
+///
+/// The samples are styled by the background color of the `.wave` class, the selection by `.selection`
+/// and the playhead by `.playhead`.
+pub struct WaveformView {
+    buffer: Option<WaveformBuffer>,
+
+    // The samples shown by each pixel, or None to fit the whole buffer
+    samples_per_pixel: Option<f32>,
+    // The index of the sample at the left edge, which may fall between samples when zoomed in
+    offset: f32,
+    follow_playhead: bool,
+
+    selection: Option<(usize, usize)>,
+    // The sample where the selection drag started
+    anchor: Option<usize>,
+
+    generation: usize,
+    playhead: Option<usize>,
+
+    // The lowest and highest sample of each column, with the generation, offset, zoom and width they were found for
+    peaks: Vec<(f32, f32)>,
+    peaks_key: Option<(usize, f32, f32, usize)>,
+
+    on_select: Option<Box<dyn Fn(Option<(usize, usize)>) -> Event + Send>>,
+
+    wave: Entity,
+    selected: Entity,
+    playhead_marker: Entity,
+}
+
+impl WaveformView {
+    pub fn new() -> Self {
+        WaveformView {
+            buffer: None,
+
+            samples_per_pixel: None,
+            offset: 0.0,
+            follow_playhead: false,
+
+            selection: None,
+            anchor: None,
+
+            generation: 0,
+            playhead: None,
+
+            peaks: Vec::new(),
+            peaks_key: None,
+
+            on_select: None,
+
+            wave: Entity::null(),
+            selected: Entity::null(),
+            playhead_marker: Entity::null(),
+        }
+    }
+
+    /// Shows the samples of a buffer filled by the audio thread or loaded from a file
+    pub fn with_buffer(mut self, buffer: WaveformBuffer) -> Self {
+        self.buffer = Some(buffer);
+        self
+    }
+
+    /// Sets the number of samples shown by each pixel, instead of fitting the whole buffer
+    pub fn with_zoom(mut self, samples_per_pixel: f32) -> Self {
+        self.samples_per_pixel = Some(samples_per_pixel.max(MIN_SAMPLES_PER_PIXEL));
+        self
+    }
+
+    /// Sets whether the view scrolls to keep the playhead in sight
+    pub fn with_follow_playhead(mut self, follow_playhead: bool) -> Self {
+        self.follow_playhead = follow_playhead;
+        self
+    }
+
+    /// Sets the event sent with the selected range of samples when the user changes the selection
+    pub fn on_select<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(Option<(usize, usize)>) -> Event + Send,
+    {
+        self.on_select = Some(Box::new(message));
+        self
+    }
+
+    fn len(&self) -> usize {
+        self.buffer.as_ref().map(|buffer| buffer.len()).unwrap_or(0)
+    }
+
+    // Returns the samples shown by each pixel for a width of view
+    fn zoom(&self, width: f32) -> f32 {
+        match self.samples_per_pixel {
+            Some(samples_per_pixel) => samples_per_pixel,
+            None if width > 0.0 => (self.len() as f32 / width).max(MIN_SAMPLES_PER_PIXEL),
+            None => 1.0,
+        }
+    }
+
+    // Keeps the view within the buffer
+    fn clamp_offset(&mut self, width: f32) {
+        let visible = width * self.zoom(width);
+        let max_offset = (self.len() as f32 - visible).max(0.0);
+        self.offset = self.offset.min(max_offset).max(0.0);
+    }
+
+    // Returns the index of the sample under a window x coordinate
+    fn sample_at(&self, state: &State, entity: Entity, x: f32) -> usize {
+        let posx = state.data.get_posx(entity);
+        let width = state.data.get_width(entity);
+
+        let index = self.offset + (x - posx) * self.zoom(width);
+        (index.max(0.0) as usize).min(self.len())
+    }
+
+    fn select(&mut self, state: &mut State, entity: Entity, selection: Option<(usize, usize)>) {
+        self.selection = selection;
+
+        if let Some(on_select) = &self.on_select {
+            let mut event = (on_select)(selection);
+            if !event.target {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+            state.insert_event(event);
+        }
+
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Finds the lowest and highest sample of each column of the view
+    fn decimate(&mut self, buffer: &WaveformBuffer, width: f32) {
+        let samples_per_pixel = self.zoom(width);
+        let columns = width.max(0.0) as usize;
+        let key = (buffer.generation(), self.offset, samples_per_pixel, columns);
+
+        if self.peaks_key == Some(key) {
+            return;
+        }
+
+        let len = buffer.len();
+
+        self.peaks.clear();
+        for column in 0..columns {
+            let start = (self.offset + column as f32 * samples_per_pixel) as usize;
+            let end = ((self.offset + (column + 1) as f32 * samples_per_pixel) as usize)
+                .max(start + 1)
+                .min(len);
+
+            if start >= end {
+                break;
+            }
+
+            let mut peak = (f32::MAX, f32::MIN);
+            for index in start..end {
+                let sample = buffer.sample(index);
+                peak = (peak.0.min(sample), peak.1.max(sample));
+            }
+
+            self.peaks.push(peak);
+        }
+
+        self.peaks_key = Some(key);
+    }
+}
+
+impl BuildHandler for WaveformView {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Group);
+
+        self.wave = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("wave")
+        });
+        self.selected = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("selection")
+        });
+        self.playhead_marker = Element::new().build(state, entity, |builder| {
+            builder
+                .set_hoverability(false)
+                .set_display(Display::None)
+                .class("playhead")
+        });
+
+        state.meters.add(entity, || Event::new(WaveformEvent::Frame));
+
+        state.style.insert_element(entity, "waveform_view");
+
+        entity
+    }
+}
+
+impl EventHandler for WaveformView {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let width = state.data.get_width(entity);
+
+        if let Some(waveform_event) = event.message.downcast::<WaveformEvent>() {
+            if event.target == entity {
+                match waveform_event {
+                    WaveformEvent::SetZoom(samples_per_pixel) => {
+                        self.samples_per_pixel = Some(samples_per_pixel.max(MIN_SAMPLES_PER_PIXEL));
+                        self.clamp_offset(width);
+                    }
+
+                    WaveformEvent::ZoomToFit => {
+                        self.samples_per_pixel = None;
+                        self.offset = 0.0;
+                    }
+
+                    WaveformEvent::SetOffset(offset) => {
+                        self.offset = *offset;
+                        self.clamp_offset(width);
+                    }
+
+                    WaveformEvent::SetSelection(selection) => {
+                        self.selection = *selection;
+                    }
+
+                    WaveformEvent::Frame => {
+                        let (generation, playhead) = match &self.buffer {
+                            Some(buffer) => (buffer.generation(), buffer.playhead()),
+                            None => return,
+                        };
+
+                        if generation == self.generation && playhead == self.playhead {
+                            return;
+                        }
+
+                        self.generation = generation;
+                        self.playhead = playhead;
+
+                        // Jump a page when the playhead leaves the view, so the view doesn't scroll every frame
+                        if let Some(playhead) = playhead.filter(|_| self.follow_playhead) {
+                            let visible = width * self.zoom(width);
+                            let playhead = playhead as f32;
+
+                            if playhead < self.offset || playhead >= self.offset + visible {
+                                self.offset = playhead - visible * 0.1;
+                                self.clamp_offset(width);
+                            }
+                        }
+                    }
+                }
+
+                state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                event.consume();
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if event.target == entity {
+                        let index = self.sample_at(state, entity, state.mouse.left.pos_down.0);
+
+                        self.anchor = Some(index);
+                        state.capture(entity);
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseMove(x, _) => {
+                    if event.target == entity {
+                        if let Some(anchor) = self.anchor {
+                            let index = self.sample_at(state, entity, *x);
+                            let selection = (anchor.min(index), anchor.max(index));
+
+                            // The selection is sent when the drag ends
+                            if self.selection != Some(selection) {
+                                self.selection = Some(selection);
+                                state.insert_event(
+                                    Event::new(WindowEvent::Redraw).target(Entity::root()),
+                                );
+                            }
+                        }
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if event.target == entity {
+                        if let Some(anchor) = self.anchor.take() {
+                            let index = self.sample_at(state, entity, state.mouse.cursorx);
+
+                            // Clicking without dragging clears the selection
+                            let selection = if index == anchor {
+                                None
+                            } else {
+                                Some((anchor.min(index), anchor.max(index)))
+                            };
+
+                            self.select(state, entity, selection);
+                        }
+
+                        state.release(entity);
+                    }
+                }
+
+                WindowEvent::MouseScroll(x, y) => {
+                    if event.target == entity {
+                        let samples_per_pixel = self.zoom(width);
+
+                        if state.modifiers.ctrl {
+                            // Zoom around the sample under the cursor
+                            let cursor = state.mouse.cursorx - state.data.get_posx(entity);
+                            let index = self.offset + cursor * samples_per_pixel;
+                            let fit = (self.len() as f32 / width.max(1.0)).max(MIN_SAMPLES_PER_PIXEL);
+
+                            let zoom = (samples_per_pixel * 0.8f32.powf(*y))
+                                .min(fit)
+                                .max(MIN_SAMPLES_PER_PIXEL);
+
+                            self.samples_per_pixel = Some(zoom);
+                            self.offset = index - cursor * zoom;
+                        } else {
+                            self.offset -= (*x + *y) * SCROLL_PIXELS * samples_per_pixel;
+                        }
+
+                        self.clamp_offset(width);
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn on_draw(&mut self, state: &mut State, entity: Entity, canvas: &mut Canvas<OpenGl>) {
+        if state.data.get_visibility(entity) == Visibility::Invisible {
+            return;
+        }
+
+        let opacity = state.data.get_opacity(entity);
+
+        let color = |state: &State, entity: Entity| {
+            let mut color: femtovg::Color = state
+                .style
+                .background_color
+                .get(entity)
+                .cloned()
+                .unwrap_or_default()
+                .into();
+            color.set_alphaf(color.a * opacity);
+            color
+        };
+
+        let background_color = color(state, entity);
+        let wave_color = color(state, self.wave);
+        let selection_color = color(state, self.selected);
+        let playhead_color = color(state, self.playhead_marker);
+
+        let posx = state.data.get_posx(entity);
+        let posy = state.data.get_posy(entity);
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        // Draw background
+        let mut path = Path::new();
+        path.rect(posx, posy, width, height);
+        canvas.fill_path(&mut path, Paint::color(background_color));
+
+        let buffer = match self.buffer.clone() {
+            Some(buffer) => buffer,
+            None => return,
+        };
+
+        let samples_per_pixel = self.zoom(width);
+        let offset = self.offset;
+        let to_x = |index: f32| posx + (index - offset) / samples_per_pixel;
+        let to_y = |sample: f32| posy + height * 0.5 * (1.0 - sample.min(1.0).max(-1.0));
+
+        // Draw selection
+        if let Some((start, end)) = self.selection {
+            let left = to_x(start as f32).max(posx);
+            let right = to_x(end as f32).min(posx + width);
+
+            if right > left {
+                let mut path = Path::new();
+                path.rect(left, posy, right - left, height);
+                canvas.fill_path(&mut path, Paint::color(selection_color));
+            }
+        }
+
+        // Draw samples
+        let mut path = Path::new();
+        if samples_per_pixel >= 1.0 {
+            self.decimate(&buffer, width);
+
+            for (column, (min, max)) in self.peaks.iter().enumerate() {
+                let x = posx + column as f32 + 0.5;
+                let (top, bottom) = (to_y(*max), to_y(*min));
+
+                // Quiet columns are still drawn a pixel high
+                path.move_to(x, top.min(bottom - 1.0));
+                path.line_to(x, bottom);
+            }
+        } else {
+            let len = buffer.len();
+            let first = offset.floor() as usize;
+            let last = ((offset + width * samples_per_pixel).ceil() as usize + 1).min(len);
+
+            for index in first..last {
+                let (x, y) = (to_x(index as f32), to_y(buffer.sample(index)));
+
+                if index == first {
+                    path.move_to(x, y);
+                } else {
+                    path.line_to(x, y);
+                }
+            }
+        }
+
+        let mut paint = Paint::color(wave_color);
+        paint.set_line_width(1.0);
+        canvas.stroke_path(&mut path, paint);
+
+        // Draw playhead
+        if let Some(playhead) = self.playhead {
+            let x = to_x(playhead as f32).floor() + 0.5;
+
+            if x >= posx && x <= posx + width {
+                let mut path = Path::new();
+                path.move_to(x, posy);
+                path.line_to(x, posy + height);
+
+                let mut paint = Paint::color(playhead_color);
+                paint.set_line_width(1.0);
+                canvas.stroke_path(&mut path, paint);
+            }
+        }
+    }
+}
@@ -0,0 +1,526 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::style::{Display, Length, Position};
+use crate::widgets::{begin_edit, end_edit, perform_edit, reset_edit};
+use crate::widgets::{Element, Grid, GridAxis};
+use crate::{BuildHandler, Event, EventHandler, Key, WindowEvent};
+use crate::{ParamId, PointerEvent, PropSet, Role, State};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum XYPadEvent {
+    // Sets the x value of the point at an index, e.g. from a bound parameter
+    SetX(usize, f32),
+    // Sets the y value of the point at an index
+    SetY(usize, f32),
+}
+
+/// The initial position of a point on an `XYPad` and the parameters it is bound to
+#[derive(Debug, Clone, PartialEq)]
+pub struct XYPoint {
+    x: f32,
+    y: f32,
+    params: Option<(ParamId, ParamId)>,
+}
+
+impl XYPoint {
+    pub fn new(x: f32, y: f32) -> Self {
+        XYPoint { x, y, params: None }
+    }
+
+    /// Binds the x and y values of the point to two parameters of the `ParamStore`
+    pub fn with_params(mut self, x: ParamId, y: ParamId) -> Self {
+        self.params = Some((x, y));
+        self
+    }
+}
+
+struct PadPoint {
+    x: f32,
+    y: f32,
+    default: (f32, f32),
+    params: Option<(ParamId, ParamId)>,
+
+    handle: Entity,
+    // Hidden entities bound to the parameters of each coordinate, as a binding belongs to one entity
+    x_axis: Entity,
+    y_axis: Entity,
+}
+
+// The distance in pixels within which a dragged point snaps to a grid line
+const SNAP_DISTANCE: f32 = 6.0;
+
+/// A pad on a grid with one or more points which can be dragged in two dimensions
+///
+/// Points are elements with the `.point` class, positioned with the mappings of the x and y axes of
+/// the grid. Dragging a point moves it, snapping to the grid lines if snapping is enabled unless Alt
+/// is held, and pressing the pad away from the points moves the nearest point to the cursor. A
+/// focused point is nudged with the arrow keys, finely with Shift and coarsely with Ctrl, or by one
+/// step along an axis whose mapping has steps, and double clicking a point moves it back to its
+/// initial position. The x and y values of a point can be bound to two parameters with
+/// `XYPoint::with_params`.
+pub struct XYPad {
+    x_axis: GridAxis,
+    y_axis: GridAxis,
+    points: Vec<PadPoint>,
+    snap: bool,
+
+    grid: Entity,
+
+    // The point being dragged and the offset from the cursor to its center
+    dragging: Option<usize>,
+    drag_offset: (f32, f32),
+    // Whether the drag has changed each coordinate and so begun its edit
+    editing: (bool, bool),
+
+    on_change: Option<Box<dyn Fn(usize, f32, f32) -> Event + Send>>,
+}
+
+impl XYPad {
+    pub fn new() -> Self {
+        XYPad {
+            x_axis: GridAxis::default(),
+            y_axis: GridAxis::default(),
+            points: Vec::new(),
+            snap: false,
+
+            grid: Entity::null(),
+
+            dragging: None,
+            drag_offset: (0.0, 0.0),
+            editing: (false, false),
+
+            on_change: None,
+        }
+    }
+
+    pub fn with_x_axis(mut self, axis: GridAxis) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    pub fn with_y_axis(mut self, axis: GridAxis) -> Self {
+        self.y_axis = axis;
+        self
+    }
+
+    pub fn with_point(mut self, point: XYPoint) -> Self {
+        self.points.push(PadPoint {
+            x: point.x,
+            y: point.y,
+            default: (point.x, point.y),
+            params: point.params,
+
+            handle: Entity::null(),
+            x_axis: Entity::null(),
+            y_axis: Entity::null(),
+        });
+
+        self
+    }
+
+    /// Sets whether dragged points snap to the grid lines
+    pub fn with_snap(mut self, snap: bool) -> Self {
+        self.snap = snap;
+        self
+    }
+
+    /// Sets the event sent when a point is moved by the user, given its index and x and y values
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(usize, f32, f32) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+        self
+    }
+
+    fn handle_position(&self, entity: Entity) -> Option<usize> {
+        self.points.iter().position(|point| point.handle == entity)
+    }
+
+    fn normalized(&self, index: usize) -> (f32, f32) {
+        let point = &self.points[index];
+
+        (self.x_axis.position(point.x), self.y_axis.position(point.y))
+    }
+
+    // Positions the handle of a point over its values
+    fn place(&self, state: &mut State, entity: Entity, index: usize) {
+        let (nx, ny) = self.normalized(index);
+        let point = &self.points[index];
+
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+        let handle_width = state.data.get_width(point.handle);
+        let handle_height = state.data.get_height(point.handle);
+
+        point
+            .handle
+            .set_left(state, Length::Pixels(nx * width - handle_width / 2.0))
+            .set_top(state, Length::Pixels((1.0 - ny) * height - handle_height / 2.0));
+
+        let value = format!(
+            "{}, {}",
+            self.x_axis.mapping.format(point.x),
+            self.y_axis.mapping.format(point.y)
+        );
+        point.handle.set_accessible_value(state, &value);
+    }
+
+    // Snaps a normalized position to the nearest line of an axis within the snap distance
+    fn snap_to_lines(&self, axis: &GridAxis, position: f32, size: f32) -> f32 {
+        axis.lines
+            .iter()
+            .map(|line| axis.position(*line))
+            .filter(|line| (line - position).abs() * size <= SNAP_DISTANCE)
+            .min_by(|a, b| {
+                (*a - position)
+                    .abs()
+                    .partial_cmp(&(*b - position).abs())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or(position)
+    }
+
+    // Moves a point to a normalized position, returning whether its x and y values changed
+    fn move_point(
+        &mut self,
+        state: &mut State,
+        entity: Entity,
+        index: usize,
+        nx: f32,
+        ny: f32,
+    ) -> (bool, bool) {
+        let x = self.x_axis.mapping.to_plain(nx);
+        let y = self.y_axis.mapping.to_plain(ny);
+
+        let point = &mut self.points[index];
+        let changed = (x != point.x, y != point.y);
+
+        point.x = x;
+        point.y = y;
+
+        if changed.0 || changed.1 {
+            self.place(state, entity, index);
+
+            if let Some(on_change) = &self.on_change {
+                let mut event = (on_change)(index, x, y);
+                event.origin = entity;
+
+                state.insert_event(event);
+            }
+
+            state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+        }
+
+        changed
+    }
+
+    // Moves a point as one complete edit of each coordinate which changed, e.g. when nudged with a key
+    fn set_point(&mut self, state: &mut State, entity: Entity, index: usize, nx: f32, ny: f32) {
+        let (x_changed, y_changed) = self.move_point(state, entity, index, nx, ny);
+        let (nx, ny) = self.normalized(index);
+        let point = &self.points[index];
+
+        if x_changed {
+            reset_edit(state, point.x_axis, point.x, nx);
+        }
+
+        if y_changed {
+            reset_edit(state, point.y_axis, point.y, ny);
+        }
+    }
+
+    fn start_drag(&mut self, state: &mut State, entity: Entity, index: usize) {
+        self.dragging = Some(index);
+        self.editing = (false, false);
+
+        state.capture(entity);
+        state.set_focus(self.points[index].handle);
+    }
+
+    // Sends the coordinates changed by a drag as part of their edits, beginning the edit of each
+    // coordinate with its first change so that pressing without moving a point sends no edit
+    fn perform_drag_edit(&mut self, state: &mut State, index: usize, changed: (bool, bool)) {
+        let (nx, ny) = self.normalized(index);
+        let point = &self.points[index];
+
+        if changed.0 {
+            if !self.editing.0 {
+                self.editing.0 = true;
+                begin_edit(state, point.x_axis);
+            }
+
+            perform_edit(state, point.x_axis, point.x, nx);
+        }
+
+        if changed.1 {
+            if !self.editing.1 {
+                self.editing.1 = true;
+                begin_edit(state, point.y_axis);
+            }
+
+            perform_edit(state, point.y_axis, point.y, ny);
+        }
+    }
+
+    // Returns the point nearest to a position in the pad
+    fn nearest_point(&self, state: &State, entity: Entity, x: f32, y: f32) -> Option<usize> {
+        let width = state.data.get_width(entity);
+        let height = state.data.get_height(entity);
+
+        (0..self.points.len()).min_by(|a, b| {
+            let distance = |index: usize| {
+                let (nx, ny) = self.normalized(index);
+                let dx = nx * width - x;
+                let dy = (1.0 - ny) * height - y;
+                dx * dx + dy * dy
+            };
+
+            distance(*a)
+                .partial_cmp(&distance(*b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+    }
+}
+
+impl BuildHandler for XYPad {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        self.grid = Grid::new()
+            .with_x_axis(self.x_axis.clone())
+            .with_y_axis(self.y_axis.clone())
+            .build(state, entity, |builder| {
+                builder
+                    .set_position(Position::Absolute)
+                    .set_width(Length::Percentage(1.0))
+                    .set_height(Length::Percentage(1.0))
+            });
+
+        for index in 0..self.points.len() {
+            let handle = Element::new().build(state, entity, |builder| {
+                builder
+                    .set_position(Position::Absolute)
+                    .set_tab_index(0)
+                    .set_role(Role::Slider)
+                    .class("point")
+            });
+
+            let x_axis = Element::new().build(state, handle, |builder| {
+                builder.set_display(Display::None).set_hoverability(false)
+            });
+            let y_axis = Element::new().build(state, handle, |builder| {
+                builder.set_display(Display::None).set_hoverability(false)
+            });
+
+            let point = &mut self.points[index];
+            point.handle = handle;
+            point.x_axis = x_axis;
+            point.y_axis = y_axis;
+
+            if let Some((x_param, y_param)) = point.params {
+                state.bind_param_with(x_axis, x_param, move |value| {
+                    Event::new(XYPadEvent::SetX(index, value))
+                });
+                state.bind_param_with(y_axis, y_param, move |value| {
+                    Event::new(XYPadEvent::SetY(index, value))
+                });
+            }
+
+            self.place(state, entity, index);
+        }
+
+        entity.set_role(state, Role::Group);
+
+        state.style.insert_element(entity, "xy_pad");
+
+        entity
+    }
+}
+
+impl EventHandler for XYPad {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let target = event.target;
+
+        if let Some(xy_pad_event) = event.message.downcast::<XYPadEvent>() {
+            // Sent to the pad, or to the hidden entities bound to parameters
+            let ours = target == entity
+                || self
+                    .points
+                    .iter()
+                    .any(|point| point.x_axis == target || point.y_axis == target);
+
+            if ours {
+                match xy_pad_event {
+                    XYPadEvent::SetX(index, value) => {
+                        if let Some(point) = self.points.get_mut(*index) {
+                            point.x = self.x_axis.mapping.snap(*value);
+                            self.place(state, entity, *index);
+                        }
+                    }
+
+                    XYPadEvent::SetY(index, value) => {
+                        if let Some(point) = self.points.get_mut(*index) {
+                            point.y = self.y_axis.mapping.snap(*value);
+                            self.place(state, entity, *index);
+                        }
+                    }
+                }
+
+                state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                event.consume();
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                // Double clicking a point moves it back to its initial position
+                PointerEvent::Click { pointer, count } => {
+                    if pointer.button == MouseButton::Left && *count == 2 {
+                        if let Some(index) = self.handle_position(target) {
+                            let (x, y) = self.points[index].default;
+                            let nx = self.x_axis.mapping.to_normalized(x);
+                            let ny = self.y_axis.mapping.to_normalized(y);
+
+                            self.set_point(state, entity, index, nx, ny);
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::GeometryChanged(geometry_changed) => {
+                    let resized = geometry_changed.width || geometry_changed.height;
+                    if resized && (target == entity || self.handle_position(target).is_some()) {
+                        for index in 0..self.points.len() {
+                            self.place(state, entity, index);
+                        }
+                    }
+                }
+
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    let (cursor_x, cursor_y) = state.mouse.left.pos_down;
+                    let posx = state.data.get_posx(entity);
+                    let posy = state.data.get_posy(entity);
+
+                    if let Some(index) = self.handle_position(target) {
+                        // Keep the point under the cursor where it was grabbed
+                        let handle = self.points[index].handle;
+                        let center_x = state.data.get_posx(handle) + state.data.get_width(handle) / 2.0;
+                        let center_y = state.data.get_posy(handle) + state.data.get_height(handle) / 2.0;
+
+                        self.drag_offset = (center_x - cursor_x, center_y - cursor_y);
+                        self.start_drag(state, entity, index);
+                        event.consume();
+                    } else if target == entity || target == self.grid {
+                        let (x, y) = (cursor_x - posx, cursor_y - posy);
+
+                        if let Some(index) = self.nearest_point(state, entity, x, y) {
+                            self.drag_offset = (0.0, 0.0);
+                            self.start_drag(state, entity, index);
+
+                            let width = state.data.get_width(entity);
+                            let height = state.data.get_height(entity);
+
+                            if width > 0.0 && height > 0.0 {
+                                let changed = self.move_point(
+                                    state,
+                                    entity,
+                                    index,
+                                    x / width,
+                                    1.0 - y / height,
+                                );
+
+                                self.perform_drag_edit(state, index, changed);
+                            }
+                        }
+
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseMove(x, y) => {
+                    if let Some(index) = self.dragging {
+                        let width = state.data.get_width(entity);
+                        let height = state.data.get_height(entity);
+
+                        if width <= 0.0 || height <= 0.0 {
+                            return;
+                        }
+
+                        let px = *x + self.drag_offset.0 - state.data.get_posx(entity);
+                        let py = *y + self.drag_offset.1 - state.data.get_posy(entity);
+
+                        let mut nx = (px / width).min(1.0).max(0.0);
+                        let mut ny = (1.0 - py / height).min(1.0).max(0.0);
+
+                        if self.snap && !state.modifiers.alt {
+                            nx = self.snap_to_lines(&self.x_axis, nx, width);
+                            ny = self.snap_to_lines(&self.y_axis, ny, height);
+                        }
+
+                        let changed = self.move_point(state, entity, index, nx, ny);
+
+                        self.perform_drag_edit(state, index, changed);
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if let Some(index) = self.dragging.take() {
+                        let point = &self.points[index];
+
+                        if self.editing.0 {
+                            end_edit(state, point.x_axis);
+                        }
+
+                        if self.editing.1 {
+                            end_edit(state, point.y_axis);
+                        }
+
+                        self.editing = (false, false);
+
+                        state.release(entity);
+                    }
+                }
+
+                WindowEvent::KeyDown(_, key) => {
+                    if let Some(index) = self.handle_position(target) {
+                        // Nudge by a hundredth of the pad, finely with Shift and coarsely with Ctrl, or by
+                        // a step along an axis with steps
+                        let coarse = !state.modifiers.shift && state.modifiers.ctrl;
+                        let step = if state.modifiers.shift {
+                            0.001
+                        } else if coarse {
+                            0.1
+                        } else {
+                            0.01
+                        };
+
+                        let (x_step, y_step) = (
+                            self.x_axis.nudge_step(step, coarse),
+                            self.y_axis.nudge_step(step, coarse),
+                        );
+
+                        let (dx, dy) = match key {
+                            Some(Key::ArrowLeft) => (-x_step, 0.0),
+                            Some(Key::ArrowRight) => (x_step, 0.0),
+                            Some(Key::ArrowDown) => (0.0, -y_step),
+                            Some(Key::ArrowUp) => (0.0, y_step),
+                            _ => return,
+                        };
+
+                        let (nx, ny) = self.normalized(index);
+                        self.set_point(state, entity, index, nx + dx, ny + dy);
+
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
@@ -4,7 +4,7 @@ use crate::entity::Entity;
 use crate::mouse::*;
 
 use crate::{BuildHandler, Event, EventHandler, Propagation, WindowEvent};
-use crate::{Code, PropSet, State};
+use crate::{Code, PropSet, Role, State};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ButtonEvent {
@@ -57,6 +57,9 @@ impl Button {
 impl BuildHandler for Button {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Button);
+
         if let Some(text) = &self.text {
             entity.set_text(state, text);
         }
@@ -95,6 +95,9 @@ impl Checkbox {
 impl BuildHandler for Checkbox {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::CheckBox);
+
         entity
             .set_font(state, "icons")
             .set_text_justify(state, Justify::Center)
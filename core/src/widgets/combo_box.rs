@@ -0,0 +1,453 @@
+use crate::entity::Entity;
+use crate::mouse::*;
+use crate::{BuildHandler, Event, EventHandler, Key, WindowEvent};
+use crate::{PopupAnchor, PopupEvent, PopupPlacement, PropSet, Role, State};
+
+use crate::state::style::*;
+use crate::widgets::{Element, Textbox, TextboxEvent};
+
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+
+const ICON_DOWN_DIR: &str = "\u{25be}";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComboBoxEvent {
+    // Sent by the textbox of the combo box when its text is edited
+    Filter(String),
+    // Adds an item to the end of the list
+    AddItem(String),
+    // Removes the first item with the given text
+    RemoveItem(String),
+    // Replaces all of the items
+    SetItems(Vec<String>),
+    Clear,
+}
+
+/// An editable dropdown which filters its items by the text typed into its textbox
+///
+/// Typing opens the list and shows the items which fuzzy match the text, best match first. Up and
+/// Down move the highlighted item, Page Up and Page Down move it by a page, and Enter or a click
+/// selects it. The list only builds `max_rows` row entities which are reused as the list scrolls,
+/// so it can hold thousands of items. Items can be changed at runtime by sending a `ComboBoxEvent`
+/// to the combo box. The combo box is styled through the `combo_box` element.
+pub struct ComboBox {
+    text: String,
+    items: Vec<String>,
+    // The current filter text
+    filter: String,
+    // Indices of the items which match the filter, best match first
+    filtered: Vec<usize>,
+    // Position in `filtered` of the highlighted item
+    highlighted: Option<usize>,
+    // Position in `filtered` of the item shown by the first row
+    first: usize,
+    // Index of the selected item
+    selected: Option<usize>,
+    max_rows: usize,
+    open: bool,
+
+    matcher: SkimMatcherV2,
+
+    textbox: Entity,
+    button: Entity,
+    popup: Entity,
+    rows: Vec<Entity>,
+
+    on_select: Option<Box<dyn Fn(usize, &str) -> Event + Send>>,
+}
+
+impl ComboBox {
+    pub fn new(text: &str) -> Self {
+        ComboBox {
+            text: text.to_string(),
+            items: Vec::new(),
+            filter: String::new(),
+            filtered: Vec::new(),
+            highlighted: None,
+            first: 0,
+            selected: None,
+            max_rows: 8,
+            open: false,
+
+            matcher: SkimMatcherV2::default(),
+
+            textbox: Entity::null(),
+            button: Entity::null(),
+            popup: Entity::null(),
+            rows: Vec::new(),
+
+            on_select: None,
+        }
+    }
+
+    pub fn with_items(mut self, items: Vec<String>) -> Self {
+        self.items = items;
+
+        self
+    }
+
+    /// Sets the number of rows shown by the list before it scrolls
+    pub fn with_max_rows(mut self, max_rows: usize) -> Self {
+        self.max_rows = max_rows.max(1);
+
+        self
+    }
+
+    /// Sets a callback which is called with the index and text of an item when it is selected
+    pub fn on_select<F>(mut self, on_select: F) -> Self
+    where
+        F: 'static + Fn(usize, &str) -> Event + Send,
+    {
+        self.on_select = Some(Box::new(on_select));
+
+        self
+    }
+
+    // Filters the items by the filter text, keeping the order of the items when there is no filter
+    fn apply_filter(&mut self) {
+        if self.filter.is_empty() {
+            self.filtered = (0..self.items.len()).collect();
+            self.highlighted = self
+                .selected
+                .and_then(|selected| self.filtered.iter().position(|index| *index == selected));
+        } else {
+            let mut scored = self
+                .items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| {
+                    self.matcher
+                        .fuzzy_match(item, &self.filter)
+                        .map(|score| (index, score))
+                })
+                .collect::<Vec<_>>();
+
+            // The sort is stable so items with the same score keep their order
+            scored.sort_by(|a, b| b.1.cmp(&a.1));
+
+            self.filtered = scored.into_iter().map(|(index, _)| index).collect();
+            self.highlighted = if self.filtered.is_empty() { None } else { Some(0) };
+        }
+
+        self.first = 0;
+        self.scroll_to_highlighted();
+    }
+
+    // Scrolls the list so the highlighted item is shown
+    fn scroll_to_highlighted(&mut self) {
+        if let Some(highlighted) = self.highlighted {
+            if highlighted < self.first {
+                self.first = highlighted;
+            } else if highlighted >= self.first + self.max_rows {
+                self.first = highlighted + 1 - self.max_rows;
+            }
+        }
+    }
+
+    fn scroll(&mut self, state: &mut State, rows: isize) {
+        let max_first = self.filtered.len().saturating_sub(self.max_rows) as isize;
+
+        self.first = (self.first as isize + rows).max(0).min(max_first) as usize;
+
+        self.update_rows(state);
+    }
+
+    fn move_highlight(&mut self, state: &mut State, offset: isize) {
+        if self.filtered.is_empty() {
+            return;
+        }
+
+        let last = self.filtered.len() as isize - 1;
+
+        self.highlighted = Some(match self.highlighted {
+            Some(highlighted) => (highlighted as isize + offset).max(0).min(last) as usize,
+            None if offset > 0 => 0,
+            None => last as usize,
+        });
+
+        self.scroll_to_highlighted();
+        self.update_rows(state);
+    }
+
+    // Shows the filtered items in the rows
+    fn update_rows(&self, state: &mut State) {
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let position = self.first + row_index;
+
+            match self.filtered.get(position) {
+                Some(index) => {
+                    row.set_text(state, &self.items[*index])
+                        .set_display(state, Display::Flexbox)
+                        .set_checked(state, self.highlighted == Some(position));
+                }
+
+                None => {
+                    row.set_display(state, Display::None);
+                }
+            }
+        }
+
+        state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+    }
+
+    fn open(&mut self, state: &mut State, entity: Entity) {
+        if !self.open {
+            self.open = true;
+
+            state.open_popup(self.popup, PopupAnchor::Entity(entity), PopupPlacement::Below);
+            state.accessibility.expanded.insert(entity, Some(true));
+        }
+
+        self.update_rows(state);
+    }
+
+    fn close(&mut self, state: &mut State) {
+        if self.open {
+            state.close_popup(self.popup);
+        }
+    }
+
+    fn select(&mut self, state: &mut State, entity: Entity, position: usize) {
+        let index = match self.filtered.get(position) {
+            Some(index) => *index,
+            None => return,
+        };
+
+        self.selected = Some(index);
+
+        let text = self.items[index].clone();
+
+        state.insert_event(Event::new(TextboxEvent::SetValue(text.clone())).target(self.textbox));
+
+        // The next time the list opens it shows every item
+        self.filter.clear();
+        self.apply_filter();
+
+        self.close(state);
+
+        if let Some(on_select) = &self.on_select {
+            let mut event = (on_select)(index, &text);
+
+            if event.target == Entity::null() {
+                event.target = entity;
+            }
+
+            event.origin = entity;
+
+            state.insert_event(event);
+        }
+    }
+
+    // Updates the filtered items and selection after the items change
+    fn items_changed(&mut self, state: &mut State) {
+        self.apply_filter();
+
+        if self.open {
+            self.update_rows(state);
+        }
+    }
+}
+
+impl BuildHandler for ComboBox {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Row);
+
+        self.textbox = Textbox::new(&self.text)
+            .on_change(|text| Event::new(ComboBoxEvent::Filter(text.to_string())))
+            .build(state, entity, |builder| builder.set_flex_grow(1.0));
+
+        self.button = Element::new().build(state, entity, |builder| {
+            builder
+                .set_font("icons")
+                .set_text(ICON_DOWN_DIR)
+                .set_text_justify(Justify::Center)
+                .class("icon")
+        });
+
+        // The list is shown on the popup layer so it isn't clipped by the combo box's ancestors
+        self.popup = Element::new().build(state, entity, |builder| {
+            builder
+                .set_flex_direction(FlexDirection::Column)
+                .set_display(Display::None)
+                .set_role(Role::ListBox)
+                .class("popup")
+        });
+
+        for _ in 0..self.max_rows {
+            let row = Element::new().build(state, self.popup, |builder| {
+                builder
+                    .set_display(Display::None)
+                    .set_role(Role::ListItem)
+                    .class("item")
+            });
+
+            self.rows.push(row);
+        }
+
+        self.apply_filter();
+
+        entity.set_role(state, Role::ComboBox);
+        state.accessibility.expanded.insert(entity, Some(false));
+
+        entity.set_element(state, "combo_box")
+    }
+}
+
+impl EventHandler for ComboBox {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let target = event.target;
+
+        if let Some(combo_box_event) = event.message.downcast::<ComboBoxEvent>() {
+            match combo_box_event {
+                ComboBoxEvent::Filter(text) => {
+                    if target == self.textbox {
+                        self.filter = text.clone();
+                        self.apply_filter();
+                        self.open(state, entity);
+
+                        event.consume();
+                    }
+                }
+
+                ComboBoxEvent::AddItem(item) => {
+                    if target == entity {
+                        self.items.push(item.clone());
+                        self.items_changed(state);
+                    }
+                }
+
+                ComboBoxEvent::RemoveItem(item) => {
+                    if target == entity {
+                        if let Some(index) = self.items.iter().position(|other| other == item) {
+                            self.items.remove(index);
+
+                            self.selected = match self.selected {
+                                Some(selected) if selected == index => None,
+                                Some(selected) if selected > index => Some(selected - 1),
+                                selected => selected,
+                            };
+
+                            self.items_changed(state);
+                        }
+                    }
+                }
+
+                ComboBoxEvent::SetItems(items) => {
+                    if target == entity {
+                        self.items = items.clone();
+                        self.selected = None;
+                        self.items_changed(state);
+                    }
+                }
+
+                ComboBoxEvent::Clear => {
+                    if target == entity {
+                        self.items.clear();
+                        self.selected = None;
+                        self.items_changed(state);
+                    }
+                }
+            }
+        }
+
+        if let Some(popup_event) = event.message.downcast::<PopupEvent>() {
+            match popup_event {
+                PopupEvent::Closed => {
+                    if target == self.popup {
+                        self.open = false;
+                        state.accessibility.expanded.insert(entity, Some(false));
+
+                        event.consume();
+                    }
+                }
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if target == self.button {
+                        if self.open {
+                            self.close(state);
+                        } else {
+                            self.filter.clear();
+                            self.apply_filter();
+                            self.open(state, entity);
+                        }
+
+                        event.consume();
+                    } else if let Some(row_index) = self.rows.iter().position(|row| *row == target) {
+                        self.select(state, entity, self.first + row_index);
+
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseOver => {
+                    if let Some(row_index) = self.rows.iter().position(|row| *row == target) {
+                        self.highlighted = Some(self.first + row_index);
+                        self.update_rows(state);
+                    }
+                }
+
+                WindowEvent::MouseScroll(_, y) => {
+                    if self.open && (target == self.popup || self.rows.contains(&target)) {
+                        self.scroll(state, if *y > 0.0 { -1 } else { 1 });
+
+                        event.consume();
+                    }
+                }
+
+                // Seen before the textbox so that the list keys don't move its cursor
+                WindowEvent::KeyDown(_, key) => {
+                    if state.focused != self.textbox {
+                        return;
+                    }
+
+                    let page = self.max_rows as isize;
+
+                    match key {
+                        Some(Key::ArrowDown) if !self.open => {
+                            self.open(state, entity);
+                            event.consume();
+                        }
+
+                        Some(Key::ArrowDown) => {
+                            self.move_highlight(state, 1);
+                            event.consume();
+                        }
+
+                        Some(Key::ArrowUp) if self.open => {
+                            self.move_highlight(state, -1);
+                            event.consume();
+                        }
+
+                        Some(Key::PageDown) if self.open => {
+                            self.move_highlight(state, page);
+                            event.consume();
+                        }
+
+                        Some(Key::PageUp) if self.open => {
+                            self.move_highlight(state, -page);
+                            event.consume();
+                        }
+
+                        // The textbox still sees Enter so that it stops editing
+                        Some(Key::Enter) if self.open => {
+                            if let Some(highlighted) = self.highlighted {
+                                self.select(state, entity, highlighted);
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
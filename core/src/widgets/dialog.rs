@@ -1,31 +1,324 @@
-#![allow(dead_code)]
-
 use crate::entity::Entity;
-use crate::mouse::*;
-use crate::{BuildHandler, Event, EventHandler, Length, WindowEvent};
-use crate::{PropSet, State};
+use crate::{BuildHandler, Event, EventHandler, Key, Length, Propagation, WindowEvent};
+use crate::{HierarchyTree, PropSet, Role, State};
 
 use crate::state::style::*;
 
-use crate::widgets::{Element, Button};
+use crate::widgets::{Button, Element, HBox, Label};
+
+/// The standard buttons of a dialogue
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DialogueButton {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+}
 
+impl DialogueButton {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DialogueButton::Ok => "OK",
+            DialogueButton::Cancel => "Cancel",
+            DialogueButton::Yes => "Yes",
+            DialogueButton::No => "No",
+        }
+    }
+}
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum DialogueEvent {
+    // Shows the dialogue and traps focus inside it
+    Open,
+    // Closes the dialogue with the result of a button
+    Close(DialogueButton),
+    // Emitted by the dialogue when it is closed, with the button which closed it
+    Result(DialogueButton),
+}
+
+/// A modal dialogue with a title bar, a content area and a row of standard buttons
+///
+/// The dialogue covers the whole window with an overlay which blocks the widgets behind it, so it
+/// should be built as a child of the window. While open, keyboard focus is trapped inside the
+/// dialogue. Enter presses the focused button, or the first OK/Yes button, and Escape presses the
+/// Cancel or No button. Building a dialogue returns the dialogue and its content area.
 pub struct Dialogue {
-    
+    title: String,
+    message: Option<String>,
+    buttons: Vec<DialogueButton>,
+
+    open: bool,
+    // Removes the dialogue after it closes, used by the message box helpers
+    remove_on_close: bool,
+
+    content: Entity,
+    button_entities: Vec<(Entity, DialogueButton)>,
+
+    on_result: Option<Box<dyn Fn(DialogueButton) -> Event + Send>>,
 }
 
 impl Dialogue {
-    pub fn new() -> Self {
+    pub fn new(title: &str) -> Self {
         Dialogue {
+            title: title.to_string(),
+            message: None,
+            buttons: vec![DialogueButton::Ok, DialogueButton::Cancel],
+
+            open: false,
+            remove_on_close: false,
+
+            content: Entity::null(),
+            button_entities: Vec::new(),
+
+            on_result: None,
+        }
+    }
+
+    /// Creates a dialogue showing a message with an OK button
+    ///
+    /// The dialogue opens when it is built and removes itself when it is closed.
+    pub fn message_box(title: &str, message: &str) -> Self {
+        Dialogue::new(title)
+            .with_message(message)
+            .with_buttons(&[DialogueButton::Ok])
+            .with_open(true)
+            .with_remove_on_close(true)
+    }
+
+    /// Creates a dialogue asking a question with Yes and No buttons
+    ///
+    /// The dialogue opens when it is built and removes itself when it is closed.
+    pub fn confirm(title: &str, question: &str) -> Self {
+        Dialogue::new(title)
+            .with_message(question)
+            .with_buttons(&[DialogueButton::Yes, DialogueButton::No])
+            .with_open(true)
+            .with_remove_on_close(true)
+    }
+
+    pub fn with_message(mut self, message: &str) -> Self {
+        self.message = Some(message.to_string());
+
+        self
+    }
+
+    pub fn with_buttons(mut self, buttons: &[DialogueButton]) -> Self {
+        self.buttons = buttons.to_vec();
+
+        self
+    }
+
+    pub fn with_open(mut self, open: bool) -> Self {
+        self.open = open;
+
+        self
+    }
+
+    pub fn with_remove_on_close(mut self, remove_on_close: bool) -> Self {
+        self.remove_on_close = remove_on_close;
+
+        self
+    }
+
+    pub fn on_result<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(DialogueButton) -> Event + Send,
+    {
+        self.on_result = Some(Box::new(message));
+
+        self
+    }
+
+    // The button pressed by Enter when focus is not on a button
+    fn default_button(&self) -> Option<DialogueButton> {
+        self.buttons
+            .iter()
+            .find(|button| **button == DialogueButton::Ok || **button == DialogueButton::Yes)
+            .or(self.buttons.first())
+            .cloned()
+    }
+
+    // The button pressed by Escape
+    fn escape_button(&self) -> Option<DialogueButton> {
+        if self.buttons.len() == 1 {
+            return self.buttons.first().cloned();
+        }
+
+        self.buttons
+            .iter()
+            .find(|button| **button == DialogueButton::Cancel)
+            .or(self.buttons.iter().find(|button| **button == DialogueButton::No))
+            .cloned()
+    }
+
+    fn open(&mut self, state: &mut State, entity: Entity) {
+        self.open = true;
+
+        entity.set_display(state, Display::Flexbox);
 
+        state.push_focus_scope(entity);
+
+        // The buttons aren't focusable until the dialogue has been laid out, so focus the default button directly
+        if !state.focused.is_descendant_of(&state.hierarchy, entity) {
+            let default_button = self.default_button();
+            let focus = self
+                .button_entities
+                .iter()
+                .find(|(_, button)| Some(*button) == default_button)
+                .map(|(button_entity, _)| *button_entity)
+                .unwrap_or(entity);
+
+            state.set_focus(focus);
+        }
+    }
+
+    fn close(&mut self, state: &mut State, entity: Entity, button: DialogueButton) {
+        if !self.open {
+            return;
+        }
+
+        self.open = false;
+
+        entity.set_display(state, Display::None);
+
+        state.pop_focus_scope(entity);
+
+        if let Some(on_result) = &self.on_result {
+            let mut event = (on_result)(button);
+            event.origin = entity;
+
+            if event.target == Entity::null() {
+                event.target = entity;
+            }
+
+            state.insert_event(event);
+        }
+
+        state.insert_event(
+            Event::new(DialogueEvent::Result(button))
+                .target(entity)
+                .origin(entity)
+                .propagate(Propagation::Up),
+        );
+
+        if self.remove_on_close {
+            state.remove(entity);
         }
     }
 }
 
 impl BuildHandler for Dialogue {
+    type Ret = (Entity, Entity);
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        // The dialogue entity is the overlay covering the window
+        entity
+            .set_position(state, Position::Absolute)
+            .set_left(state, Length::Pixels(0.0))
+            .set_top(state, Length::Pixels(0.0))
+            .set_width(state, Length::Percentage(1.0))
+            .set_height(state, Length::Percentage(1.0))
+            .set_justify_content(state, JustifyContent::Center)
+            .set_align_items(state, AlignItems::Center)
+            .set_z_order(state, 10)
+            .set_display(state, Display::None);
+
+        let frame = Element::new().build(state, entity, |builder| {
+            builder
+                .set_flex_direction(FlexDirection::Column)
+                .class("frame")
+        });
+
+        Label::new(&self.title).build(state, frame, |builder| builder.class("title"));
+
+        self.content = Element::new().build(state, frame, |builder| {
+            builder.set_flex_grow(1.0).class("content")
+        });
+
+        if let Some(message) = &self.message {
+            Label::new(message).build(state, self.content, |builder| builder.class("message"));
+        }
+
+        let button_row = HBox::new().build(state, frame, |builder| builder.class("buttons"));
+
+        let default_button = self.default_button();
 
+        for button in self.buttons.iter() {
+            let button_entity = Button::with_label(button.label())
+                .on_release(Event::new(DialogueEvent::Close(*button)).target(entity))
+                .build(state, button_row, |builder| builder);
+
+            if Some(*button) == default_button {
+                button_entity.class(state, "default");
+            }
+
+            self.button_entities.push((button_entity, *button));
+        }
+
+        // Allows the dialogue to hold focus when it has no buttons
+        entity.set_tab_index(state, -1);
+        entity.set_role(state, Role::Dialog);
+        entity.set_accessible_name(state, &self.title);
+
+        entity.set_element(state, "dialogue");
+
+        if self.open {
+            self.open(state, entity);
+        }
+
+        (entity, self.content)
+    }
 }
 
 impl EventHandler for Dialogue {
-    
-}
\ No newline at end of file
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(dialogue_event) = event.message.downcast::<DialogueEvent>() {
+            match dialogue_event {
+                DialogueEvent::Open => {
+                    if event.target == entity && !self.open {
+                        self.open(state, entity);
+                    }
+                }
+
+                DialogueEvent::Close(button) => {
+                    if event.target == entity {
+                        let button = *button;
+                        self.close(state, entity, button);
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::KeyDown(_, key) => {
+                    if !self.open || !state.focused.is_descendant_of(&state.hierarchy, entity) {
+                        return;
+                    }
+
+                    let button = match key {
+                        Some(Key::Enter) => self
+                            .button_entities
+                            .iter()
+                            .find(|(button_entity, _)| *button_entity == state.focused)
+                            .map(|(_, button)| *button)
+                            .or(self.default_button()),
+
+                        Some(Key::Escape) => self.escape_button(),
+
+                        _ => None,
+                    };
+
+                    if let Some(button) = button {
+                        self.close(state, entity, button);
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
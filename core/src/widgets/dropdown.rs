@@ -3,7 +3,7 @@
 use crate::entity::Entity;
 use crate::mouse::*;
 use crate::{AnimationState, BuildHandler, Event, EventHandler, Propagation, WindowEvent};
-use crate::{PropSet, State};
+use crate::{PopupAnchor, PopupEvent, PopupPlacement, PropSet, Role, State};
 
 use crate::state::style::*;
 use crate::widgets::{Element, Label};
@@ -38,6 +38,8 @@ impl Item {
 impl BuildHandler for Item {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ListItem);
+
         entity
             .set_flex_grow(state, 1.0)
             .set_text(state, &self.text)
@@ -139,6 +141,9 @@ impl Dropdown {
 impl BuildHandler for Dropdown {
     type Ret = (Entity, Entity, Entity);
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::ComboBox);
+
         self.header = Element::new().build(state, entity, |builder| {
             builder
                 //.set_background_color(Color::rgb(100,100,50))
@@ -167,15 +172,14 @@ impl BuildHandler for Dropdown {
                 .class("icon")
         });
 
+        // The list is shown on the popup layer so it isn't clipped by the dropdown's ancestors
         self.container = Element::new().build(state, entity, |builder| {
             builder
                 .set_position(Position::Absolute)
                 //.set_top(Length::Percentage(1.0))
                 //.set_width(Length::Percentage(1.0))
                 //.set_height(Length::Pixels(0.0))
-                .set_opacity(0.0)
-                .set_z_order(1)
-                .set_clip_widget(Entity::root())
+                .set_display(Display::None)
                 //.set_visibility(Visibility::Invisible)
                 //.set_background_color(Color::rgb(100, 50, 50))
                 .class("container")
@@ -245,6 +249,23 @@ impl BuildHandler for Dropdown {
     }
 }
 
+impl Dropdown {
+    fn open(&mut self, state: &mut State, entity: Entity) {
+        self.open = true;
+
+        self.header.set_enabled(state, true);
+
+        state.open_popup(self.container, PopupAnchor::Entity(entity), PopupPlacement::Below);
+
+        state
+            .style
+            .opacity
+            .play_animation(self.container, self.fade_in_animation);
+
+        self.container.set_opacity(state, 1.0);
+    }
+}
+
 impl EventHandler for Dropdown {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
         if let Some(dropdown_event) = event.message.downcast::<DropdownEvent>() {
@@ -255,7 +276,7 @@ impl EventHandler for Dropdown {
                     //Check here if it's an event from a child (TODO)
                     self.label.set_text(state, proxy);
                     //self.container.set_visibility(state, Visibility::Invisible);
-                    self.open = false;
+                    state.close_popup(self.container);
                     //state.style.height.play_animation(self.container, self.collapse_animation);
                     //state.style.opacity.play_animation(self.other_container, self.fade_out_animation);
                     // Temp until persistent animations work
@@ -273,76 +294,35 @@ impl EventHandler for Dropdown {
             //}
         }
 
-        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
-            match window_event {
-                WindowEvent::MouseDown(button) => match button {
-                    MouseButton::Left => {
-                        if event.target == entity || event.target == self.header {
-            
-                            //if state.hovered.is_child_of(&state.hierarchy, self.container) {
-                            if state.hovered != entity {
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseDown(*button))
-                                        .target(state.hovered)
-                                        .propagate(Propagation::Direct),
-                                );
-                            }
+        if let Some(popup_event) = event.message.downcast::<PopupEvent>() {
+            match popup_event {
+                PopupEvent::Closed => {
+                    if event.target == self.container {
+                        self.open = false;
 
-                            //}
+                        self.header.set_disabled(state, true);
 
-                            //return true;
-                        }
-                    }
-                    _ => {}
-                },
-
-                WindowEvent::MouseCaptureOutEvent => {
-   
-                    self.open = false;
-
-                    self.header.set_disabled(state, true);
-
-                    state
-                        .style
-                        .opacity
-                        .play_animation(self.container, self.fade_out_animation);
-
-                    self.container.set_opacity(state, 0.0);
-                }
-
-                WindowEvent::MouseCaptureEvent => {
-                    self.open = true;
+                        self.container.set_opacity(state, 0.0);
 
-                    self.header.set_enabled(state, true);
-
-                    state
-                        .style
-                        .opacity
-                        .play_animation(self.container, self.fade_in_animation);
-
-                    self.container.set_opacity(state, 1.0);
-                    // Shouldn't need to do this but it's required for some reason. TODO: Investigate
-                    self.container.set_z_order(state, 1);
+                        event.consume();
+                    }
                 }
+            }
+        }
 
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
                 WindowEvent::MouseUp(button) => match button {
                     MouseButton::Left => {
-                        if (event.target == entity || event.target == self.header)
-                            && event.origin != entity
-                        {
+                        if event.target == entity || event.target == self.header {
                             if state.mouse.left.pressed == state.hovered {
                                 if !self.open {
-                                    state.capture(entity);
+                                    self.open(state, entity);
                                 } else {
-                                    state.release(entity);
+                                    state.close_popup(self.container);
                                 }
 
-                                state.insert_event(
-                                    Event::new(WindowEvent::MouseUp(*button))
-                                        .target(state.hovered)
-                                        .origin(entity)
-                                        .propagate(Propagation::Direct),
-                                );
+                                event.consume();
                             }
                         }
                     }
@@ -17,6 +17,8 @@ impl Label {
 impl BuildHandler for Label {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::Label);
+
         entity
             .set_text(state, &self.text)
             .set_element(state, "label")
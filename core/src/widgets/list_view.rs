@@ -0,0 +1,411 @@
+use crate::widgets::*;
+use crate::state::style::*;
+use crate::{HierarchyTree, ScrollEvent};
+
+// Number of rows built above and below the visible rows so that scrolling doesn't show gaps
+const OVERSCAN: usize = 2;
+
+/// Provides the rows of a `ListView`
+///
+/// Only the visible rows of a list view have entities. Row entities are recycled while scrolling,
+/// so `build_row` is called once per row entity to create its child widgets and `update_row` is
+/// called every time the row is assigned a different item.
+pub trait ListDataSource: Send {
+    /// Returns the number of items in the list
+    fn count(&self) -> usize;
+
+    /// Builds the child widgets of a new row entity
+    fn build_row(&mut self, state: &mut State, row: Entity);
+
+    /// Updates the widgets of a row entity to show the item at the given index
+    fn update_row(&mut self, state: &mut State, row: Entity, index: usize);
+
+    /// Returns the height of the item at the given index when the list uses measured row heights
+    ///
+    /// Returning None uses the estimated row height.
+    fn measure_row(&mut self, _state: &State, _index: usize) -> Option<f32> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowHeight {
+    // Every row has the same height in pixels
+    Fixed(f32),
+    // Rows are measured by the data source as they come into view.
+    // The value is the estimated height used for rows which have not been measured yet.
+    Measured(f32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListViewEvent {
+    // Reloads the list after items have been added, removed or changed in the data source
+    Reload,
+}
+
+// Keeps track of the row entities of a virtualized list.
+// Shared by `ListView` and `TableView`.
+pub(crate) struct VirtualRows {
+    row_height: RowHeight,
+    row_role: Role,
+    heights: Vec<f32>,
+    measured: Vec<bool>,
+    // Offset of the top of each row, with the total height as the last element
+    offsets: Vec<f32>,
+
+    // Rows currently showing an item, as (item index, row entity)
+    active: Vec<(usize, Entity)>,
+    // Rows which are hidden and ready to be reused
+    pool: Vec<Entity>,
+
+    pub scroll: f32,
+}
+
+impl VirtualRows {
+    pub fn new(row_height: RowHeight, row_role: Role) -> Self {
+        VirtualRows {
+            row_height,
+            row_role,
+            heights: Vec::new(),
+            measured: Vec::new(),
+            offsets: vec![0.0],
+
+            active: Vec::new(),
+            pool: Vec::new(),
+
+            scroll: 0.0,
+        }
+    }
+
+    pub fn total_height(&self) -> f32 {
+        self.offsets.last().cloned().unwrap_or_default()
+    }
+
+    // Returns the row entities currently showing an item, as (item index, row entity)
+    pub fn active(&self) -> &[(usize, Entity)] {
+        &self.active
+    }
+
+    // Returns every row entity, including hidden rows waiting to be reused
+    pub fn rows(&self) -> Vec<Entity> {
+        self.active
+            .iter()
+            .map(|(_, row)| *row)
+            .chain(self.pool.iter().cloned())
+            .collect()
+    }
+
+    // Returns the index of the item shown by a row entity or one of its descendants
+    pub fn index_of(&self, state: &State, entity: Entity) -> Option<usize> {
+        self.active
+            .iter()
+            .find(|(_, row)| entity.is_descendant_of(&state.hierarchy, *row))
+            .map(|(index, _)| *index)
+    }
+
+    // Returns the row entity showing the item at the given index, if it is visible
+    pub fn row(&self, index: usize) -> Option<Entity> {
+        self.active
+            .iter()
+            .find(|(i, _)| *i == index)
+            .map(|(_, row)| *row)
+    }
+
+    // Resets the row heights after the number of items changes and updates every visible row
+    pub fn reload(&mut self, state: &mut State, content: Entity, source: &mut dyn ListDataSource) {
+        let count = source.count();
+        let estimate = match self.row_height {
+            RowHeight::Fixed(height) => height,
+            RowHeight::Measured(estimate) => estimate,
+        };
+
+        self.heights = vec![estimate; count];
+        self.measured = vec![false; count];
+        self.compute_offsets();
+
+        // Release every row so they are reassigned and updated
+        let active = std::mem::replace(&mut self.active, Vec::new());
+        for (_, row) in active {
+            state.style.display.insert(row, Display::None);
+            self.pool.push(row);
+        }
+
+        state
+            .style
+            .height
+            .insert(content, Length::Pixels(self.total_height()));
+
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Updates every visible row after the items of the data source change without changing their count
+    pub fn refresh(&mut self, state: &mut State, source: &mut dyn ListDataSource) {
+        for (index, row) in self.active.clone() {
+            source.update_row(state, row, index);
+        }
+
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Assigns row entities to the items in view, building new rows when there are none to reuse
+    pub fn update(
+        &mut self,
+        state: &mut State,
+        content: Entity,
+        viewport: f32,
+        source: &mut dyn ListDataSource,
+    ) {
+        let count = self.heights.len();
+
+        let (first, last) = if count == 0 || viewport <= 0.0 {
+            (0, 0)
+        } else {
+            let top = self.scroll.max(0.0);
+            let bottom = top + viewport;
+
+            // The first row whose bottom is below the top of the viewport
+            let first = match self
+                .offsets
+                .binary_search_by(|offset| offset.total_cmp(&top))
+            {
+                Ok(index) => index,
+                Err(index) => index.saturating_sub(1),
+            }
+            .min(count - 1);
+
+            let mut last = first;
+            while last < count && self.offsets[last] < bottom {
+                last += 1;
+            }
+
+            (first.saturating_sub(OVERSCAN), (last + OVERSCAN).min(count))
+        };
+
+        let mut changed = false;
+
+        // Measure the rows coming into view
+        if let RowHeight::Measured(_) = self.row_height {
+            let mut remeasured = false;
+            for index in first..last {
+                if !self.measured[index] {
+                    self.measured[index] = true;
+                    // A measured height which isn't a number would break the search for the visible rows
+                    if let Some(height) = source.measure_row(state, index).filter(|h| h.is_finite()) {
+                        if height != self.heights[index] {
+                            self.heights[index] = height;
+                            remeasured = true;
+                        }
+                    }
+                }
+            }
+
+            if remeasured {
+                self.compute_offsets();
+                state
+                    .style
+                    .height
+                    .insert(content, Length::Pixels(self.total_height()));
+                changed = true;
+            }
+        }
+
+        // Release the rows which have scrolled out of view
+        let mut index = 0;
+        while index < self.active.len() {
+            let (item, row) = self.active[index];
+            if item < first || item >= last {
+                self.active.remove(index);
+                state.style.display.insert(row, Display::None);
+                self.pool.push(row);
+                changed = true;
+            } else {
+                index += 1;
+            }
+        }
+
+        // Assign rows to the items which have scrolled into view
+        for item in first..last {
+            let row = match self.row(item) {
+                Some(row) => row,
+                None => {
+                    let row = match self.pool.pop() {
+                        Some(row) => row,
+                        None => {
+                            let row_role = self.row_role;
+                            let row = Element::new().build(state, content, |builder| {
+                                builder
+                                    .set_position(Position::Absolute)
+                                    .set_left(Length::Pixels(0.0))
+                                    .set_width(Length::Percentage(1.0))
+                                    .set_role(row_role)
+                                    .class("row")
+                            });
+                            source.build_row(state, row);
+                            row
+                        }
+                    };
+
+                    state.style.display.insert(row, Display::Flexbox);
+                    source.update_row(state, row, item);
+                    self.active.push((item, row));
+                    changed = true;
+                    row
+                }
+            };
+
+            let top = Length::Pixels(self.offsets[item]);
+            if state.style.top.get(row) != Some(&top) {
+                state.style.top.insert(row, top);
+                changed = true;
+            }
+
+            let height = Length::Pixels(self.heights[item]);
+            if state.style.height.get(row) != Some(&height) {
+                state.style.height.insert(row, height);
+                changed = true;
+            }
+        }
+
+        if changed {
+            state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+            state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+            state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+        }
+    }
+
+    // Returns how far to scroll, in pixels, to bring the item at the given index fully into view
+    pub fn scroll_into_view(&self, index: usize, viewport: f32) -> f32 {
+        let (top, bottom) = match (self.offsets.get(index), self.offsets.get(index + 1)) {
+            (Some(top), Some(bottom)) => (*top, *bottom),
+            _ => return 0.0,
+        };
+
+        if top < self.scroll {
+            top - self.scroll
+        } else if bottom > self.scroll + viewport {
+            // A row taller than the viewport is aligned to its top
+            (bottom - viewport).min(top) - self.scroll
+        } else {
+            0.0
+        }
+    }
+
+    fn compute_offsets(&mut self) {
+        self.offsets.clear();
+        self.offsets.reserve(self.heights.len() + 1);
+
+        let mut offset = 0.0;
+        for height in self.heights.iter() {
+            self.offsets.push(offset);
+            offset += height;
+        }
+
+        self.offsets.push(offset);
+    }
+}
+
+/// A vertical list which only creates entities for the visible rows
+///
+/// Rows are placed inside a `ScrollContainer` and their contents are provided by a `ListDataSource`.
+pub struct ListView {
+    source: Box<dyn ListDataSource>,
+    rows: VirtualRows,
+
+    scroll: Entity,
+    content: Entity,
+}
+
+impl ListView {
+    pub fn new<S: ListDataSource + 'static>(source: S) -> Self {
+        ListView {
+            source: Box::new(source),
+            rows: VirtualRows::new(RowHeight::Fixed(30.0), Role::ListItem),
+
+            scroll: Entity::null(),
+            content: Entity::null(),
+        }
+    }
+
+    pub fn with_row_height(mut self, row_height: RowHeight) -> Self {
+        self.rows = VirtualRows::new(row_height, Role::ListItem);
+
+        self
+    }
+
+    fn update_rows(&mut self, state: &mut State) {
+        let viewport = state.data.get_height(self.scroll);
+        self.rows
+            .update(state, self.content, viewport, self.source.as_mut());
+    }
+}
+
+impl BuildHandler for ListView {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Column);
+
+        self.content = ScrollContainer::new().build(state, entity, |builder| builder.set_flex_grow(1.0));
+
+        self.scroll = state
+            .hierarchy
+            .get_parent(self.content)
+            .unwrap_or(Entity::null());
+
+        self.content
+            .set_left(state, Length::Pixels(0.0))
+            .set_width(state, Length::Percentage(1.0));
+
+        self.rows.reload(state, self.content, self.source.as_mut());
+
+        entity.set_role(state, Role::ListBox);
+
+        entity.set_element(state, "list_view")
+    }
+}
+
+impl EventHandler for ListView {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(list_view_event) = event.message.downcast::<ListViewEvent>() {
+            match list_view_event {
+                ListViewEvent::Reload => {
+                    if event.target == entity {
+                        self.rows.reload(state, self.content, self.source.as_mut());
+                        self.update_rows(state);
+                    }
+                }
+            }
+        }
+
+        if let Some(scroll_event) = event.message.downcast::<ScrollEvent>() {
+            match scroll_event {
+                ScrollEvent::ScrollV(top) => {
+                    if event.target == self.scroll {
+                        // The scroll container reports the top of its content as a fraction of its height
+                        self.rows.scroll = -*top * state.data.get_height(self.scroll);
+                        self.update_rows(state);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::GeometryChanged(_) => {
+                    if event.target == entity
+                        || event.target == self.scroll
+                        || event.target == self.content
+                    {
+                        self.rows.scroll =
+                            state.data.get_posy(self.scroll) - state.data.get_posy(self.content);
+                        self.update_rows(state);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
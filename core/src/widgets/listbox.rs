@@ -1,44 +1,440 @@
 use crate::widgets::*;
-use crate::Key;
+use crate::{HierarchyTree, IntoBranchIterator, IntoChildIterator, Key, MouseButton, PointerEvent};
+
+use std::time::{Duration, Instant};
+
+// Time after the last typed character before a type-ahead search starts again
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_millis(1000);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ListboxEvent {
-    CheckNext(Entity),
-    CheckPrev(Entity),
+    // Sets the selected item indices of the listbox
+    SetSelection(Vec<usize>),
+    // Emitted by the listbox when the selection is changed by the user
+    SelectionChanged(Vec<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionMode {
+    // Only one item can be selected
+    Single,
+    // Clicking an item toggles its selection
+    Multi,
+    // Clicking selects one item, ctrl-click toggles an item and shift-click selects a range
+    Range,
+}
+
+// The selected item indices of a list, shared by `Listbox` and `TableView`
+pub(crate) struct Selection {
+    pub mode: SelectionMode,
+    pub selected: Vec<usize>,
+    // The item used as the start of a range selection
+    pub anchor: Option<usize>,
+}
+
+impl Selection {
+    pub fn new(mode: SelectionMode) -> Self {
+        Selection {
+            mode,
+            selected: Vec::new(),
+            anchor: None,
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    // Replaces the selection, returning true if it changed
+    pub fn set(&mut self, mut selected: Vec<usize>) -> bool {
+        selected.sort();
+        selected.dedup();
+
+        if self.mode == SelectionMode::Single {
+            selected.truncate(1);
+        }
+
+        if selected == self.selected {
+            return false;
+        }
+
+        self.selected = selected;
+
+        true
+    }
+
+    // Removes selected indices which are no longer in the list
+    pub fn clamp(&mut self, len: usize) {
+        self.selected.retain(|index| *index < len);
+        self.anchor = self.anchor.filter(|anchor| *anchor < len);
+    }
+
+    // Selects an item in response to a click or key press with the given modifiers.
+    // Toggle is false for keyboard navigation, which never deselects an item.
+    // Returns true if the selection changed.
+    pub fn select(&mut self, index: usize, shift: bool, ctrl: bool, toggle: bool) -> bool {
+        match self.mode {
+            SelectionMode::Single => {
+                self.anchor = Some(index);
+                self.set(vec![index])
+            }
+
+            SelectionMode::Multi => {
+                self.anchor = Some(index);
+
+                if toggle {
+                    self.toggle(index)
+                } else {
+                    false
+                }
+            }
+
+            SelectionMode::Range => {
+                if shift {
+                    let anchor = self.anchor.unwrap_or(index);
+                    let mut selected = if ctrl { self.selected.clone() } else { Vec::new() };
+                    selected.extend(anchor.min(index)..=anchor.max(index));
+                    self.set(selected)
+                } else if ctrl {
+                    self.anchor = Some(index);
+
+                    if toggle {
+                        self.toggle(index)
+                    } else {
+                        false
+                    }
+                } else {
+                    self.anchor = Some(index);
+                    self.set(vec![index])
+                }
+            }
+        }
+    }
+
+    fn toggle(&mut self, index: usize) -> bool {
+        let mut selected = self.selected.clone();
+
+        if let Some(position) = selected.iter().position(|i| *i == index) {
+            selected.remove(position);
+        } else {
+            selected.push(index);
+        }
+
+        self.set(selected)
+    }
 }
 
-pub struct ListboxItem {}
+// An item of a listbox. Child widgets can be added to an item to build custom rows.
+pub struct ListboxItem {
+    text: String,
+}
 
-impl ListboxItem {}
+impl ListboxItem {
+    pub fn new() -> Self {
+        ListboxItem {
+            text: String::new(),
+        }
+    }
 
-pub struct Listbox {}
+    pub fn with_label(text: &str) -> Self {
+        ListboxItem {
+            text: text.to_string(),
+        }
+    }
+}
+
+impl BuildHandler for ListboxItem {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        // Items can be focused with the arrow keys but are skipped by Tab
+        entity.set_tab_index(state, -1);
+        entity.set_role(state, Role::ListItem);
+
+        if !self.text.is_empty() {
+            entity.set_text(state, &self.text);
+        }
+
+        entity.set_element(state, "listbox_item")
+    }
+}
+
+impl EventHandler for ListboxItem {}
+
+pub struct Listbox {
+    selection: Selection,
+
+    search: String,
+    last_search: Instant,
+
+    on_change: Option<Box<dyn Fn(Vec<usize>) -> Event + Send>>,
+}
 
 impl Listbox {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            selection: Selection::new(SelectionMode::Single),
+
+            search: String::new(),
+            last_search: Instant::now(),
+
+            on_change: None,
+        }
+    }
+
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        self.selection.mode = mode;
+
+        self
+    }
+
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(Vec<usize>) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+
+        self
+    }
+
+    // Returns the items of the listbox, which are its children
+    fn items(&self, state: &State, entity: Entity) -> Vec<Entity> {
+        entity.child_iter(&state.hierarchy).collect()
+    }
+
+    // Returns the index of the item containing the target entity
+    fn item_index(&self, state: &State, entity: Entity, target: Entity) -> Option<usize> {
+        let items = self.items(state, entity);
+
+        items
+            .iter()
+            .position(|item| target.is_descendant_of(&state.hierarchy, *item))
+    }
+
+    // Returns the index of the item which contains keyboard focus
+    fn cursor(&self, state: &State, entity: Entity) -> Option<usize> {
+        self.item_index(state, entity, state.focused)
+    }
+
+    // Applies a new selection, updating the items and emitting a selection changed event
+    fn select(&mut self, state: &mut State, entity: Entity, selected: Vec<usize>) {
+        if self.selection.set(selected) {
+            self.selection_changed(state, entity);
+        }
+    }
+
+    // Selects an item in response to a click or key press with the current modifiers
+    fn select_item(&mut self, state: &mut State, entity: Entity, index: usize, toggle: bool) {
+        let shift = state.modifiers.shift;
+        let ctrl = state.modifiers.ctrl || state.modifiers.logo;
+
+        if self.selection.select(index, shift, ctrl, toggle) {
+            self.selection_changed(state, entity);
+        }
+    }
+
+    fn selection_changed(&mut self, state: &mut State, entity: Entity) {
+        self.update_items(state, entity);
+
+        let selected = self.selection.selected.clone();
+
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(selected.clone());
+            event.origin = entity;
+
+            if event.target == Entity::null() {
+                event.target = entity;
+            }
+
+            state.insert_event(event);
+        }
+
+        state.insert_event(
+            Event::new(ListboxEvent::SelectionChanged(selected))
+                .target(entity)
+                .origin(entity),
+        );
+    }
+
+    // Sets the checked pseudo-class of the selected items
+    fn update_items(&mut self, state: &mut State, entity: Entity) {
+        let items = self.items(state, entity);
+
+        self.selection.clamp(items.len());
+
+        for (index, item) in items.into_iter().enumerate() {
+            item.set_checked(state, self.selection.contains(index));
+        }
+    }
+
+    // Moves keyboard focus to an item and updates the selection
+    fn move_cursor(&mut self, state: &mut State, entity: Entity, index: usize) {
+        let items = self.items(state, entity);
+
+        if let Some(item) = items.get(index) {
+            state.set_focus(*item);
+
+            // In multi selection mode the arrow keys only move focus, space toggles the selection
+            if self.selection.mode != SelectionMode::Multi {
+                self.select_item(state, entity, index, false);
+            }
+        }
+    }
+
+    // Returns the index of the next item whose text starts with the type-ahead search string
+    fn search_item(&self, state: &State, entity: Entity) -> Option<usize> {
+        let items = self.items(state, entity);
+
+        if items.is_empty() {
+            return None;
+        }
+
+        let search = self.search.to_lowercase();
+
+        // Typing the same character repeatedly cycles through the items starting with it
+        let start = match self.cursor(state, entity) {
+            Some(cursor) if self.search.chars().count() == 1 => cursor + 1,
+            Some(cursor) => cursor,
+            None => 0,
+        };
+
+        (0..items.len())
+            .map(|offset| (start + offset) % items.len())
+            .find(|index| {
+                items[*index]
+                    .branch_iter(&state.hierarchy)
+                    .filter_map(|e| state.style.text.get(e))
+                    .find(|text| !text.text.is_empty())
+                    .map(|text| text.text.to_lowercase().starts_with(&search))
+                    .unwrap_or(false)
+            })
     }
 }
 
 impl BuildHandler for Listbox {
     type Ret = Entity;
-    fn on_build(&mut self, _state: &mut State, entity: Entity) -> Self::Ret {
-        entity
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::ListBox);
+
+        entity.set_element(state, "listbox")
     }
 }
 
 impl EventHandler for Listbox {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(listbox_event) = event.message.downcast::<ListboxEvent>() {
+            match listbox_event {
+                ListboxEvent::SetSelection(selected) => {
+                    if event.target == entity {
+                        self.selection.set(selected.clone());
+                        self.selection.anchor = self.selection.selected.first().cloned();
+                        self.update_items(state, entity);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                PointerEvent::Click { pointer, .. } => {
+                    if pointer.button == MouseButton::Left {
+                        if let Some(index) = self.item_index(state, entity, event.target) {
+                            let items = self.items(state, entity);
+
+                            // Don't take focus from a child widget of the item, such as a textbox
+                            if !state.focused.is_descendant_of(&state.hierarchy, items[index]) {
+                                state.set_focus(items[index]);
+                            }
+
+                            self.select_item(state, entity, index, true);
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
-                WindowEvent::KeyDown(_, key) => match key {
-                    Some(Key::ArrowDown) => state.insert_event(
-                        Event::new(ListboxEvent::CheckNext(Entity::null()))
-                            .target(entity)
-                            .propagate(Propagation::Fall),
-                    ),
-
-                    _ => {}
-                },
+                WindowEvent::KeyDown(_, key) => {
+                    if state.focused == entity || state.focused.is_child_of(&state.hierarchy, entity) {
+                        let len = self.items(state, entity).len();
+
+                        if len == 0 {
+                            return;
+                        }
+
+                        let cursor = self.cursor(state, entity);
+
+                        match key {
+                            Some(Key::ArrowDown) => {
+                                let index = cursor.map(|c| (c + 1).min(len - 1)).unwrap_or(0);
+                                self.move_cursor(state, entity, index);
+                                event.consume();
+                            }
+
+                            Some(Key::ArrowUp) => {
+                                let index = cursor.map(|c| c.saturating_sub(1)).unwrap_or(len - 1);
+                                self.move_cursor(state, entity, index);
+                                event.consume();
+                            }
+
+                            Some(Key::Home) => {
+                                self.move_cursor(state, entity, 0);
+                                event.consume();
+                            }
+
+                            Some(Key::End) => {
+                                self.move_cursor(state, entity, len - 1);
+                                event.consume();
+                            }
+
+                            Some(Key::Character(c)) if c == " " => {
+                                if let Some(cursor) = cursor {
+                                    self.select_item(state, entity, cursor, true);
+                                    event.consume();
+                                }
+                            }
+
+                            Some(Key::Character(c))
+                                if (c == "a" || c == "A")
+                                    && (state.modifiers.ctrl || state.modifiers.logo)
+                                    && self.selection.mode != SelectionMode::Single =>
+                            {
+                                self.select(state, entity, (0..len).collect());
+                                event.consume();
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+
+                // Typing selects the next item whose text starts with the typed characters
+                WindowEvent::CharInput(c) => {
+                    if (state.focused == entity || state.focused.is_child_of(&state.hierarchy, entity))
+                        && !c.is_control()
+                        && *c != ' '
+                        && !state.modifiers.ctrl
+                        && !state.modifiers.logo
+                    {
+                        let now = Instant::now();
+                        if now.duration_since(self.last_search) > TYPE_AHEAD_TIMEOUT {
+                            self.search.clear();
+                        }
+                        self.last_search = now;
+                        self.search.push(*c);
+
+                        if let Some(index) = self.search_item(state, entity) {
+                            self.move_cursor(state, entity, index);
+                        }
+
+                        event.consume();
+                    }
+                }
 
                 _ => {}
             }
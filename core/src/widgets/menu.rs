@@ -2,11 +2,11 @@
 
 use crate::entity::Entity;
 use crate::mouse::*;
-use crate::{BuildHandler, Event, EventHandler, HierarchyTree, Propagation, WindowEvent};
-use crate::{PropSet, State};
+use crate::{BuildHandler, Code, Event, EventHandler, HierarchyTree, Key, Propagation, WindowEvent};
+use crate::{PropSet, Role, State};
 
 use crate::state::style::*;
-use crate::widgets::Button;
+use crate::widgets::{Button, Element};
 
 use crate::state::hierarchy::IntoChildIterator;
 
@@ -56,6 +56,18 @@ impl Menu {
         }
     }
 
+    // A submenu is a menu placed inside the container of another open menu
+    fn is_submenu(&self, state: &State, entity: Entity) -> bool {
+        if let Some(parent) = entity.parent(&state.hierarchy) {
+            state
+                .focus_scopes
+                .iter()
+                .any(|focus_scope| focus_scope.scope == parent)
+        } else {
+            false
+        }
+    }
+
     // pub fn add_item(mut self, name: &str, event: Option<Event>) -> Self {
     //     self.options.push((name.to_string(), event));
 
@@ -67,6 +79,9 @@ impl Menu {
 impl BuildHandler for Menu {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Menu);
+
         entity
             .set_text(state, &self.text)
             .set_flex_direction(state, FlexDirection::Column);
@@ -131,6 +146,7 @@ impl EventHandler for Menu {
                         state.capture(entity);
                         entity.set_checked(state, true);
                         self.open = true;
+                        state.push_focus_scope(self.container);
                     } else {
                         self.open_on_hover = true;
                     }
@@ -144,6 +160,7 @@ impl EventHandler for Menu {
                         //state.style.checked.set(entity, false);
                         entity.set_checked(state, false);
                         self.open = false;
+                        state.pop_focus_scope(self.container);
                     }
                     // else {
                     //     state.capture(entity);
@@ -158,6 +175,7 @@ impl EventHandler for Menu {
                     //state.style.checked.set(entity, false);
                     entity.set_checked(state, false);
                     self.open = false;
+                    state.pop_focus_scope(self.container);
 
                     state.release(entity);
                 }
@@ -226,6 +244,7 @@ impl EventHandler for Menu {
                                         state.insert_event(
                                             Event::new(WindowEvent::MouseDown(*button))
                                                 .target(state.hovered)
+                                                .origin(event.target)
                                                 .propagate(Propagation::Direct),
                                         );
 
@@ -347,6 +366,55 @@ impl EventHandler for Menu {
                     //println!("Mouse over menu");
                 }
 
+                WindowEvent::KeyDown(_, key) => {
+                    if state.focused == entity && !self.open {
+                        // Open the menu from its header
+                        let is_submenu = self.is_submenu(state, entity);
+                        let open = match key {
+                            Some(Key::Enter) => true,
+                            Some(Key::ArrowDown) => !is_submenu,
+                            Some(Key::ArrowRight) => is_submenu,
+                            _ => false,
+                        };
+
+                        if open {
+                            state.insert_event(
+                                Event::new(MenuEvent::Open(entity))
+                                    .target(entity)
+                                    .propagate(Propagation::Fall),
+                            );
+
+                            event.consume();
+                        }
+                    } else if state.focused.is_child_of(&state.hierarchy, self.container) {
+                        // Navigate between the items of the open menu
+                        match key {
+                            Some(Key::ArrowDown) => {
+                                state.focus_child(self.container, true, true);
+                                event.consume();
+                            }
+
+                            Some(Key::ArrowUp) => {
+                                state.focus_child(self.container, false, true);
+                                event.consume();
+                            }
+
+                            Some(Key::ArrowLeft) | Some(Key::Escape) => {
+                                if *key == Some(Key::Escape) || self.is_submenu(state, entity) {
+                                    state.insert_event(
+                                        Event::new(MenuEvent::Close(entity))
+                                            .target(entity)
+                                            .propagate(Propagation::Direct),
+                                    );
+                                    event.consume();
+                                }
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+
                 WindowEvent::MouseOut => {
                     //println!("Mouse over menu");
                     // state.insert_event(
@@ -361,3 +429,120 @@ impl EventHandler for Menu {
         }
     }
 }
+
+/// An item of a menu which sends an event when pressed
+///
+/// If a shortcut is bound to the same event in `state.shortcuts`, its accelerator text is shown
+/// at the end of the item. The text is refreshed whenever the parent menu is opened.
+pub struct MenuItem {
+    text: String,
+    event: Event,
+    shortcut: Entity,
+}
+
+impl MenuItem {
+    pub fn new(text: &str, event: Event) -> Self {
+        MenuItem {
+            text: text.to_string(),
+            event,
+            shortcut: Entity::null(),
+        }
+    }
+
+    fn update_shortcut(&self, state: &mut State) {
+        let text = state.shortcuts.shortcut_text(&self.event).unwrap_or_default();
+
+        self.shortcut.set_text(state, &text);
+    }
+}
+
+impl BuildHandler for MenuItem {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::MenuItem);
+
+        entity.set_text(state, &self.text);
+
+        self.shortcut = Element::new().build(state, entity, |builder| {
+            builder
+                .set_position(Position::Absolute)
+                .set_width(Length::Percentage(1.0))
+                .set_height(Length::Percentage(1.0))
+                .set_text_justify(Justify::End)
+                .set_hoverability(false)
+                .class("shortcut")
+        });
+
+        self.update_shortcut(state);
+
+        entity.set_element(state, "menu_item")
+    }
+}
+
+impl EventHandler for MenuItem {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(menu_event) = event.message.downcast::<MenuEvent>() {
+            match menu_event {
+                MenuEvent::Open(_) => {
+                    self.update_shortcut(state);
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(button) => match button {
+                    MouseButton::Left => {
+                        if event.target == entity && !entity.is_disabled(state) {
+                            let mut item_event = self.event.clone();
+
+                            if item_event.target == Entity::null() {
+                                item_event.target = entity;
+                            }
+
+                            item_event.origin = entity;
+
+                            state.insert_event(item_event);
+                        }
+                    }
+
+                    _ => {}
+                },
+
+                WindowEvent::KeyDown(code, _) => match code {
+                    Code::Enter | Code::Space => {
+                        if state.focused == entity {
+                            if !entity.is_disabled(state) {
+                                let mut item_event = self.event.clone();
+
+                                if item_event.target == Entity::null() {
+                                    item_event.target = entity;
+                                }
+
+                                item_event.origin = entity;
+
+                                state.insert_event(item_event);
+                            }
+
+                            // Close the menu and any parent menus
+                            state.insert_event(
+                                Event::new(MenuEvent::CloseAll(entity))
+                                    .target(entity)
+                                    .propagate(Propagation::Up),
+                            );
+
+                            event.consume();
+                        }
+                    }
+
+                    _ => {}
+                },
+
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::{BuildHandler, Code, Event, EventHandler, Key, WindowEvent};
+use crate::{HierarchyTree, PopupAnchor, PopupEvent, PopupPlacement, PropSet, Role, State};
+
+use crate::state::style::*;
+
+use crate::widgets::{first_item, parse_mnemonic, Element, MenuDescription, PopupMenu};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MenuBarEvent {
+    // Opens the menu at an index and focuses its first item
+    Open(usize),
+    // Focuses the header of the first menu without opening it
+    Focus,
+}
+
+struct MenuBarMenu {
+    label: String,
+    description: MenuDescription,
+    mnemonic: Option<char>,
+
+    header: Entity,
+    popup: Entity,
+}
+
+/// A horizontal bar of menu headers, each opening a popup menu built from a `MenuDescription`
+///
+/// Clicking a header opens its menu and, once a menu is open, hovering another header switches to
+/// its menu. Alt together with the mnemonic of a header, marked with '&' in its label, opens the
+/// menu with its first item focused and F10 focuses the bar. Left and Right move between the menus,
+/// while Down, Enter or Space opens the menu of the focused header. Items without an event target
+/// send their event to the menu bar. The bar is styled through the `menu_bar` element.
+///
+/// # Examples
+/// ```ignore
+/// MenuBar::new()
+///     .with_menu("&File", MenuDescription::new()
+///         .with_item(MenuItemDescription::new("&Open", Event::new(AppEvent::Open)).with_shortcut("Ctrl+O"))
+///         .with_separator()
+///         .with_item(MenuItemDescription::new("E&xit", Event::new(WindowEvent::WindowClose))))
+///     .with_menu("&View", MenuDescription::new()
+///         .with_item(MenuItemDescription::new("&Grid", Event::new(AppEvent::ToggleGrid)).with_checked(true)))
+///     .build(state, window, |builder| builder);
+/// ```
+pub struct MenuBar {
+    menus: Vec<MenuBarMenu>,
+    // The index of the open menu
+    open: Option<usize>,
+}
+
+impl MenuBar {
+    pub fn new() -> Self {
+        MenuBar {
+            menus: Vec::new(),
+            open: None,
+        }
+    }
+
+    pub fn with_menu(mut self, label: &str, description: MenuDescription) -> Self {
+        let (label, mnemonic) = parse_mnemonic(label);
+
+        self.menus.push(MenuBarMenu {
+            label,
+            description,
+            mnemonic,
+
+            header: Entity::null(),
+            popup: Entity::null(),
+        });
+
+        self
+    }
+
+    fn header_position(&self, entity: Entity) -> Option<usize> {
+        self.menus.iter().position(|menu| menu.header == entity)
+    }
+
+    fn open_menu(&mut self, state: &mut State, index: usize, focus_item: bool) {
+        if let Some(open) = self.open {
+            if open != index {
+                state.close_popup(self.menus[open].popup);
+            }
+        }
+
+        let menu = &self.menus[index];
+
+        state.open_popup(menu.popup, PopupAnchor::Entity(menu.header), PopupPlacement::Below);
+
+        menu.header.set_checked(state, true);
+        state.accessibility.expanded.insert(menu.header, Some(true));
+
+        match first_item(state, menu.popup) {
+            Some(first) if focus_item => state.set_focus(first),
+            _ => state.set_focus(menu.popup),
+        }
+
+        self.open = Some(index);
+    }
+
+    // Moves to the previous or next menu, opening it if a menu is already open
+    fn move_menu(&mut self, state: &mut State, current: usize, forward: bool) {
+        let len = self.menus.len();
+
+        let next = if forward {
+            (current + 1) % len
+        } else {
+            (current + len - 1) % len
+        };
+
+        if self.open.is_some() {
+            self.open_menu(state, next, true);
+        } else {
+            state.set_focus(self.menus[next].header);
+        }
+    }
+}
+
+impl BuildHandler for MenuBar {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Row);
+
+        for (index, menu) in self.menus.iter_mut().enumerate() {
+            menu.header = Element::new().build(state, entity, |builder| {
+                builder
+                    .set_text(&menu.label)
+                    .set_tab_index(-1)
+                    .set_role(Role::MenuItem)
+                    .set_accessible_name(&menu.label)
+                    .class("header")
+            });
+
+            state.accessibility.expanded.insert(menu.header, Some(false));
+
+            menu.popup = PopupMenu::new(menu.description.clone())
+                .with_owner(entity)
+                .build(state, menu.header, |builder| builder);
+
+            if let Some(mnemonic) = menu.mnemonic {
+                // A mnemonic which clashes with an existing shortcut is left to the shortcut
+                let _ = state.shortcuts.bind(
+                    &format!("Alt+{}", mnemonic),
+                    Entity::root(),
+                    Event::new(MenuBarEvent::Open(index)).target(entity),
+                );
+            }
+        }
+
+        let _ = state.shortcuts.bind(
+            "F10",
+            Entity::root(),
+            Event::new(MenuBarEvent::Focus).target(entity),
+        );
+
+        entity.set_role(state, Role::MenuBar);
+
+        entity.set_element(state, "menu_bar")
+    }
+}
+
+impl EventHandler for MenuBar {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let target = event.target;
+
+        if let Some(menu_bar_event) = event.message.downcast::<MenuBarEvent>() {
+            if target == entity {
+                match menu_bar_event {
+                    MenuBarEvent::Open(index) => {
+                        if *index < self.menus.len() {
+                            self.open_menu(state, *index, true);
+                        }
+                    }
+
+                    MenuBarEvent::Focus => {
+                        if let Some(menu) = self.menus.first() {
+                            state.set_focus(menu.header);
+                        }
+                    }
+                }
+
+                event.consume();
+            }
+        }
+
+        if let Some(popup_event) = event.message.downcast::<PopupEvent>() {
+            match popup_event {
+                PopupEvent::Closed => {
+                    if let Some(index) = self.menus.iter().position(|menu| menu.popup == target) {
+                        let header = self.menus[index].header;
+
+                        header.set_checked(state, false);
+                        state.accessibility.expanded.insert(header, Some(false));
+
+                        // A menu closed while switching to another menu is no longer the open one
+                        if self.open == Some(index) {
+                            self.open = None;
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseDown(MouseButton::Left) => {
+                    if let Some(index) = self.header_position(target) {
+                        if self.open == Some(index) {
+                            state.close_popup(self.menus[index].popup);
+                        } else {
+                            self.open_menu(state, index, false);
+                        }
+
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::MouseOver => {
+                    if let Some(index) = self.header_position(target) {
+                        if self.open.is_some() && self.open != Some(index) {
+                            self.open_menu(state, index, false);
+                        }
+                    }
+                }
+
+                WindowEvent::KeyDown(code, key) => {
+                    let focused = state.focused;
+
+                    // Keys pressed on a header
+                    if let Some(index) = self.header_position(focused) {
+                        let open = *code == Code::Enter
+                            || *code == Code::Space
+                            || *key == Some(Key::ArrowDown);
+
+                        if open {
+                            self.open_menu(state, index, true);
+                            event.consume();
+                            return;
+                        }
+
+                        match key {
+                            Some(Key::ArrowRight) => {
+                                self.move_menu(state, index, true);
+                                event.consume();
+                            }
+
+                            Some(Key::ArrowLeft) => {
+                                self.move_menu(state, index, false);
+                                event.consume();
+                            }
+
+                            Some(Key::Escape) => {
+                                state.set_focus(Entity::root());
+                                event.consume();
+                            }
+
+                            _ => {}
+                        }
+
+                        return;
+                    }
+
+                    // Left and Right in the open menu move between menus, unless a submenu handles them
+                    if let Some(index) = self.open {
+                        let popup = self.menus[index].popup;
+
+                        let in_menu = focused == popup || focused.is_child_of(&state.hierarchy, popup);
+
+                        if !in_menu {
+                            return;
+                        }
+
+                        let has_submenu = match state.accessibility.expanded.get(focused) {
+                            Some(expanded) => expanded.is_some() && focused != popup,
+                            None => false,
+                        };
+
+                        match key {
+                            Some(Key::ArrowRight) if !has_submenu => {
+                                self.move_menu(state, index, true);
+                                event.consume();
+                            }
+
+                            Some(Key::ArrowLeft) => {
+                                self.move_menu(state, index, false);
+                                event.consume();
+                            }
+
+                            _ => {}
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
@@ -12,6 +12,12 @@ pub use scrollbar::*;
 pub mod slider;
 pub use slider::*;
 
+pub mod param_edit;
+pub use param_edit::*;
+
+pub mod value_mapping;
+pub use value_mapping::*;
+
 pub mod progress;
 pub use progress::*;
 
@@ -30,12 +36,18 @@ pub use textbox::*;
 pub mod dropdown;
 pub use dropdown::*;
 
-pub mod context_menu;
-pub use context_menu::*;
+pub mod combo_box;
+pub use combo_box::*;
+
+pub mod popup_menu;
+pub use popup_menu::*;
 
 pub mod menu;
 pub use menu::*;
 
+pub mod menu_bar;
+pub use menu_bar::*;
+
 pub mod scroll_container;
 pub use scroll_container::*;
 
@@ -69,9 +81,21 @@ pub use window::WindowWidget;
 pub mod listbox;
 pub use listbox::*;
 
+pub mod list_view;
+pub use list_view::*;
+
+pub mod table_view;
+pub use table_view::*;
+
+pub mod tree_view;
+pub use tree_view::*;
+
 pub mod tooltip;
 pub use tooltip::*;
 
+pub mod dialog;
+pub use dialog::*;
+
 // Audio Widgets
 pub mod audio_widgets;
 pub use audio_widgets::*;
@@ -80,3 +104,4 @@ pub use crate::entity::Entity;
 pub use crate::events::{BuildHandler, Event, EventHandler, Propagation, WindowEvent};
 pub use crate::state::State;
 pub use crate::PropSet;
+pub use crate::Role;
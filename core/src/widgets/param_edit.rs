@@ -0,0 +1,52 @@
+use crate::{Entity, Event, Propagation, State};
+
+/// The gestures of a value widget, such as a knob or slider, being edited by the user
+///
+/// Hosts use the begin and end of an edit to group the values in between into one automation
+/// recording or undo step. Value widgets send these events to themselves and they propagate up, so
+/// a parent widget can listen for the edits of its children. Widgets bound to a parameter with
+/// `state.bind_param` also pass the gestures to the `ParamStore`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamEditEvent {
+    // The user started changing the value, e.g. began dragging a knob
+    BeginEdit,
+    // The value changed during an edit, with the plain value and the value normalized to 0..1
+    SetValue { value: f32, normalized: f32 },
+    // The user stopped changing the value
+    EndEdit,
+}
+
+pub(crate) fn begin_edit(state: &mut State, entity: Entity) {
+    send_edit_event(state, entity, ParamEditEvent::BeginEdit);
+
+    state.begin_param_edit(entity);
+}
+
+pub(crate) fn perform_edit(state: &mut State, entity: Entity, value: f32, normalized: f32) {
+    send_edit_event(state, entity, ParamEditEvent::SetValue { value, normalized });
+
+    state.perform_param_edit(entity, value);
+}
+
+pub(crate) fn end_edit(state: &mut State, entity: Entity) {
+    send_edit_event(state, entity, ParamEditEvent::EndEdit);
+
+    state.end_param_edit(entity);
+}
+
+// Sends a value change as one complete edit, used when a value widget is double clicked to reset it
+// to its default or a value is typed in
+pub(crate) fn reset_edit(state: &mut State, entity: Entity, value: f32, normalized: f32) {
+    begin_edit(state, entity);
+    perform_edit(state, entity, value, normalized);
+    end_edit(state, entity);
+}
+
+fn send_edit_event(state: &mut State, entity: Entity, edit_event: ParamEditEvent) {
+    state.insert_event(
+        Event::new(edit_event)
+            .target(entity)
+            .origin(entity)
+            .propagate(Propagation::Up),
+    );
+}
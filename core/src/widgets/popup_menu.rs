@@ -0,0 +1,643 @@
+use crate::entity::Entity;
+use crate::mouse::MouseButton;
+use crate::{BuildHandler, Code, Event, EventHandler, Key, WindowEvent};
+use crate::{PopupAnchor, PopupEvent, PopupPlacement, PropSet, Role, State};
+
+use crate::state::hierarchy::IntoChildIterator;
+use crate::state::style::*;
+
+use crate::widgets::{Element, Label};
+
+const ICON_CHECK: &str = "\u{2713}";
+const ICON_RADIO: &str = "\u{2022}";
+const ICON_RIGHT_DIR: &str = "\u{25b8}";
+
+/// An entry of a `MenuDescription`
+#[derive(Debug, Clone)]
+pub enum MenuEntry {
+    Item(MenuItemDescription),
+    Separator,
+}
+
+/// A description of the items of a menu, used to build popup menus such as context menus and the
+/// menus of a `MenuBar`
+///
+/// # Examples
+/// ```ignore
+/// let menu = MenuDescription::new()
+///     .with_item(MenuItemDescription::new("Cut", Event::new(EditEvent::Cut)).with_shortcut("Ctrl+X"))
+///     .with_item(MenuItemDescription::new("Paste", Event::new(EditEvent::Paste)).with_enabled(false))
+///     .with_separator()
+///     .with_item(MenuItemDescription::new("Snap to Grid", Event::new(EditEvent::ToggleSnap)).with_checked(true))
+///     .with_submenu("Transform", MenuDescription::new()
+///         .with_item(MenuItemDescription::new("Reverse", Event::new(EditEvent::Reverse))));
+///
+/// state.set_context_menu(clip, menu);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MenuDescription {
+    pub entries: Vec<MenuEntry>,
+}
+
+impl MenuDescription {
+    pub fn new() -> Self {
+        MenuDescription {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_item(mut self, item: MenuItemDescription) -> Self {
+        self.entries.push(MenuEntry::Item(item));
+
+        self
+    }
+
+    pub fn with_separator(mut self) -> Self {
+        self.entries.push(MenuEntry::Separator);
+
+        self
+    }
+
+    pub fn with_submenu(self, label: &str, submenu: MenuDescription) -> Self {
+        self.with_item(MenuItemDescription::submenu(label, submenu))
+    }
+
+    /// Returns the item at a path of entry indices, where each index but the last is a submenu
+    pub fn item_mut(&mut self, path: &[usize]) -> Option<&mut MenuItemDescription> {
+        let (index, rest) = path.split_first()?;
+
+        match self.entries.get_mut(*index)? {
+            MenuEntry::Item(item) => {
+                if rest.is_empty() {
+                    Some(item)
+                } else {
+                    item.submenu.as_mut()?.item_mut(rest)
+                }
+            }
+
+            MenuEntry::Separator => None,
+        }
+    }
+}
+
+/// An item of a `MenuDescription`
+///
+/// A '&' in the label marks the next character as the mnemonic of the item, which activates the
+/// item when its key is pressed while the menu is open. Use "&&" for a literal '&'.
+#[derive(Debug, Clone)]
+pub struct MenuItemDescription {
+    pub label: String,
+    // Icon text shown before the label of items which aren't checked
+    pub icon: Option<String>,
+    // Sent when the item is activated, targeting the entity the menu belongs to if no target is set
+    pub event: Option<Event>,
+    // Shortcut text shown at the end of the item, taken from `state.shortcuts` if not set
+    pub shortcut: Option<String>,
+    // The checked state of a checkable item, None if the item can't be checked
+    pub checked: Option<bool>,
+    // Radio items are checked as a group, the group is a run of radio items between separators
+    pub radio: bool,
+    pub enabled: bool,
+    pub submenu: Option<MenuDescription>,
+}
+
+impl MenuItemDescription {
+    pub fn new(label: &str, event: Event) -> Self {
+        MenuItemDescription {
+            label: label.to_string(),
+            icon: None,
+            event: Some(event),
+            shortcut: None,
+            checked: None,
+            radio: false,
+            enabled: true,
+            submenu: None,
+        }
+    }
+
+    pub fn submenu(label: &str, submenu: MenuDescription) -> Self {
+        MenuItemDescription {
+            label: label.to_string(),
+            icon: None,
+            event: None,
+            shortcut: None,
+            checked: None,
+            radio: false,
+            enabled: true,
+            submenu: Some(submenu),
+        }
+    }
+
+    pub fn with_icon(mut self, icon: &str) -> Self {
+        self.icon = Some(icon.to_string());
+
+        self
+    }
+
+    pub fn with_shortcut(mut self, shortcut: &str) -> Self {
+        self.shortcut = Some(shortcut.to_string());
+
+        self
+    }
+
+    /// Makes the item checkable, activating it toggles the check mark before sending its event
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+
+        self
+    }
+
+    /// Makes the item a radio item, activating it checks it and unchecks the others in its group
+    pub fn with_radio(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self.radio = true;
+
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+
+        self
+    }
+
+    // The text shown on the item with the mnemonic markers removed
+    fn text(&self) -> String {
+        parse_mnemonic(&self.label).0
+    }
+
+    fn mark(&self) -> &str {
+        match self.checked {
+            Some(true) if self.radio => ICON_RADIO,
+            Some(true) => ICON_CHECK,
+            _ => self.icon.as_deref().unwrap_or(""),
+        }
+    }
+}
+
+// Splits a label into the text to show and its mnemonic, the lowercase character after the first '&'
+pub(crate) fn parse_mnemonic(label: &str) -> (String, Option<char>) {
+    let mut text = String::with_capacity(label.len());
+    let mut mnemonic = None;
+
+    let mut chars = label.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('&') => text.push('&'),
+
+            Some(next) => {
+                if mnemonic.is_none() {
+                    mnemonic = next.to_lowercase().next();
+                }
+
+                text.push(next);
+            }
+
+            None => {}
+        }
+    }
+
+    (text, mnemonic)
+}
+
+// The first item of a menu which can be focused
+pub(crate) fn first_item(state: &mut State, menu: Entity) -> Option<Entity> {
+    let children = menu.child_iter(&state.hierarchy).collect::<Vec<_>>();
+
+    children
+        .into_iter()
+        .find(|child| state.style.tab_index.get(*child).is_some() && !child.is_disabled(state))
+}
+
+struct PopupMenuItem {
+    entity: Entity,
+    // Index of the item in the entries of the description
+    index: usize,
+    check: Entity,
+    submenu: Entity,
+    mnemonic: Option<char>,
+}
+
+/// A menu shown on the popup layer, built from a `MenuDescription`
+///
+/// Submenus are built as popup menus inside their items and open to the right of the item when
+/// hovered or when Right or Enter is pressed. Up and Down move between the enabled items, Left
+/// closes a submenu and Escape closes the innermost open menu. Pressing the mnemonic key of an item
+/// activates it. Activating an item closes all menus and sends its event. Popup menus are styled
+/// through the `popup_menu` element.
+pub struct PopupMenu {
+    description: MenuDescription,
+    // The entity the menu belongs to, which receives the events of items without a target
+    owner: Entity,
+    // Entry indices leading to this menu from the outermost menu
+    path: Vec<usize>,
+    remove_on_close: bool,
+
+    items: Vec<PopupMenuItem>,
+}
+
+impl PopupMenu {
+    pub fn new(description: MenuDescription) -> Self {
+        PopupMenu {
+            description,
+            owner: Entity::null(),
+            path: Vec::new(),
+            remove_on_close: false,
+
+            items: Vec::new(),
+        }
+    }
+
+    pub fn with_owner(mut self, owner: Entity) -> Self {
+        self.owner = owner;
+
+        self
+    }
+
+    /// Removes the menu when it is closed, used for menus which are built each time they open
+    pub fn with_remove_on_close(mut self, remove_on_close: bool) -> Self {
+        self.remove_on_close = remove_on_close;
+
+        self
+    }
+
+    fn item_position(&self, entity: Entity) -> Option<usize> {
+        self.items.iter().position(|item| item.entity == entity)
+    }
+
+    fn open_submenu(&mut self, state: &mut State, position: usize, focus: bool) {
+        self.close_submenus(state, Some(position));
+
+        let item = &self.items[position];
+
+        if item.submenu == Entity::null() || item.entity.is_disabled(state) {
+            return;
+        }
+
+        state.open_popup(
+            item.submenu,
+            PopupAnchor::Entity(item.entity),
+            PopupPlacement::Right,
+        );
+
+        state.accessibility.expanded.insert(item.entity, Some(true));
+
+        if focus {
+            if let Some(first) = first_item(state, item.submenu) {
+                state.set_focus(first);
+            }
+        }
+    }
+
+    // Closes the open submenus of the items except one
+    fn close_submenus(&mut self, state: &mut State, except: Option<usize>) {
+        for (position, item) in self.items.iter().enumerate() {
+            if Some(position) != except && state.popups.is_open(item.submenu) {
+                state.close_popup(item.submenu);
+            }
+        }
+    }
+
+    fn activate(&mut self, state: &mut State, position: usize) {
+        let entity = self.items[position].entity;
+
+        if entity.is_disabled(state) {
+            return;
+        }
+
+        if self.items[position].submenu != Entity::null() {
+            self.open_submenu(state, position, true);
+            return;
+        }
+
+        let index = self.items[position].index;
+
+        let (checked, radio, item_event) = match self.description.item_mut(&[index]) {
+            Some(description) => (description.checked, description.radio, description.event.clone()),
+            None => return,
+        };
+
+        if let Some(checked) = checked {
+            if radio {
+                for group_index in self.radio_group(index) {
+                    self.set_checked(state, group_index, group_index == index);
+                }
+            } else {
+                self.set_checked(state, index, !checked);
+            }
+        }
+
+        if let Some(mut item_event) = item_event {
+            item_event.origin = entity;
+
+            if item_event.target == Entity::null() {
+                item_event.target = if self.owner != Entity::null() {
+                    self.owner
+                } else {
+                    entity
+                };
+            }
+
+            state.insert_event(item_event);
+        }
+
+        state.close_all_popups();
+    }
+
+    // Returns the entry indices of the radio group containing an entry
+    fn radio_group(&self, index: usize) -> Vec<usize> {
+        let entries = &self.description.entries;
+
+        let is_radio = |entry: &MenuEntry| match entry {
+            MenuEntry::Item(item) => item.radio,
+            MenuEntry::Separator => false,
+        };
+
+        let start = entries[..index]
+            .iter()
+            .rposition(|entry| !is_radio(entry))
+            .map(|position| position + 1)
+            .unwrap_or(0);
+
+        let end = entries[index..]
+            .iter()
+            .position(|entry| !is_radio(entry))
+            .map(|position| index + position)
+            .unwrap_or(entries.len());
+
+        (start..end).collect()
+    }
+
+    fn set_checked(&mut self, state: &mut State, index: usize, checked: bool) {
+        let description = match self.description.item_mut(&[index]) {
+            Some(description) => description,
+            None => return,
+        };
+
+        description.checked = Some(checked);
+
+        let mark = description.mark().to_string();
+
+        if let Some(item) = self.items.iter().find(|item| item.index == index) {
+            item.entity.set_checked(state, checked);
+            item.check.set_text(state, &mark);
+        }
+
+        // The description keeps the checked state so that a menu rebuilt from it matches
+        let mut path = self.path.clone();
+        path.push(index);
+        state.set_context_menu_checked(self.owner, &path, checked);
+    }
+
+    // Moves focus to the next or previous enabled item, wrapping around
+    fn move_focus(&mut self, state: &mut State, forward: bool) {
+        let enabled = self
+            .items
+            .iter()
+            .map(|item| item.entity)
+            .filter(|entity| !entity.is_disabled(state))
+            .collect::<Vec<_>>();
+
+        if enabled.is_empty() {
+            return;
+        }
+
+        let next = match enabled.iter().position(|entity| *entity == state.focused) {
+            Some(index) if forward => enabled[(index + 1) % enabled.len()],
+            Some(index) => enabled[(index + enabled.len() - 1) % enabled.len()],
+            None if forward => enabled[0],
+            None => enabled[enabled.len() - 1],
+        };
+
+        state.set_focus(next);
+    }
+}
+
+impl BuildHandler for PopupMenu {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        // The menu is opened on the popup layer
+        entity
+            .set_flex_direction(state, FlexDirection::Column)
+            .set_display(state, Display::None);
+
+        for (index, entry) in self.description.entries.iter().enumerate() {
+            match entry {
+                MenuEntry::Separator => {
+                    let separator = Element::new().build(state, entity, |builder| {
+                        builder.set_hoverability(false).class("separator")
+                    });
+
+                    separator.set_role(state, Role::Separator);
+                }
+
+                MenuEntry::Item(item) => {
+                    let item_entity = Element::new().build(state, entity, |builder| {
+                        builder.set_flex_direction(FlexDirection::Row).class("item")
+                    });
+
+                    let check = Element::new().build(state, item_entity, |builder| {
+                        builder
+                            .set_text(item.mark())
+                            .set_hoverability(false)
+                            .class("check")
+                    });
+
+                    Label::new(&item.text()).build(state, item_entity, |builder| {
+                        builder.set_flex_grow(1.0).set_hoverability(false).class("label")
+                    });
+
+                    let shortcut = item
+                        .shortcut
+                        .clone()
+                        .or_else(|| {
+                            item.event
+                                .as_ref()
+                                .and_then(|event| state.shortcuts.shortcut_text(event))
+                        })
+                        .unwrap_or_default();
+
+                    Label::new(&shortcut).build(state, item_entity, |builder| {
+                        builder.set_hoverability(false).class("shortcut")
+                    });
+
+                    Element::new().build(state, item_entity, |builder| {
+                        builder
+                            .set_text(if item.submenu.is_some() { ICON_RIGHT_DIR } else { "" })
+                            .set_hoverability(false)
+                            .class("arrow")
+                    });
+
+                    let submenu = match &item.submenu {
+                        Some(submenu) => {
+                            let mut path = self.path.clone();
+                            path.push(index);
+
+                            PopupMenu {
+                                description: submenu.clone(),
+                                owner: self.owner,
+                                path,
+                                remove_on_close: false,
+                                items: Vec::new(),
+                            }
+                            .build(state, item_entity, |builder| builder)
+                        }
+
+                        None => Entity::null(),
+                    };
+
+                    item_entity
+                        .set_tab_index(state, -1)
+                        .set_role(
+                            state,
+                            match item.checked {
+                                Some(_) if item.radio => Role::MenuItemRadio,
+                                Some(_) => Role::MenuItemCheckBox,
+                                None => Role::MenuItem,
+                            },
+                        )
+                        .set_accessible_name(state, &item.text())
+                        .set_checked(state, item.checked == Some(true))
+                        .set_disabled(state, !item.enabled);
+
+                    state.accessibility.expanded.insert(
+                        item_entity,
+                        if submenu != Entity::null() { Some(false) } else { None },
+                    );
+
+                    self.items.push(PopupMenuItem {
+                        entity: item_entity,
+                        index,
+                        check,
+                        submenu,
+                        mnemonic: parse_mnemonic(&item.label).1,
+                    });
+                }
+            }
+        }
+
+        // Allows the menu to hold focus before an item is selected
+        entity.set_tab_index(state, -1);
+        entity.set_role(state, Role::Menu);
+
+        // Unlike a menu button, the menu itself is never collapsed
+        state.accessibility.expanded.insert(entity, None);
+
+        entity.set_element(state, "popup_menu")
+    }
+}
+
+impl EventHandler for PopupMenu {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let target = event.target;
+
+        if let Some(popup_event) = event.message.downcast::<PopupEvent>() {
+            match popup_event {
+                PopupEvent::Closed => {
+                    // The event keeps propagating so the widget which opened the menu is notified
+                    if target == entity {
+                        if self.remove_on_close {
+                            state.remove(entity);
+                        }
+                    } else if let Some(item) = self.items.iter().find(|item| item.submenu == target) {
+                        state.accessibility.expanded.insert(item.entity, Some(false));
+                    }
+                }
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::MouseOver => {
+                    if let Some(position) = self.item_position(target) {
+                        state.set_focus(target);
+
+                        self.open_submenu(state, position, false);
+                    }
+                }
+
+                WindowEvent::MouseUp(MouseButton::Left) => {
+                    if let Some(position) = self.item_position(target) {
+                        if state.mouse.left.pressed == target {
+                            self.activate(state, position);
+                        }
+
+                        event.consume();
+                    }
+                }
+
+                WindowEvent::KeyDown(code, key) => {
+                    let focused = state.focused;
+
+                    if focused != entity && self.item_position(focused).is_none() {
+                        return;
+                    }
+
+                    if *code == Code::Enter || *code == Code::Space {
+                        if let Some(position) = self.item_position(focused) {
+                            self.activate(state, position);
+                        }
+
+                        event.consume();
+                        return;
+                    }
+
+                    match key {
+                        Some(Key::ArrowDown) => {
+                            self.move_focus(state, true);
+                            event.consume();
+                        }
+
+                        Some(Key::ArrowUp) => {
+                            self.move_focus(state, false);
+                            event.consume();
+                        }
+
+                        Some(Key::ArrowRight) => {
+                            if let Some(position) = self.item_position(focused) {
+                                if self.items[position].submenu != Entity::null() {
+                                    self.open_submenu(state, position, true);
+                                    event.consume();
+                                }
+                            }
+                        }
+
+                        Some(Key::ArrowLeft) => {
+                            // Only submenus close on Left
+                            if !self.path.is_empty() {
+                                state.close_popup(entity);
+                                event.consume();
+                            }
+                        }
+
+                        Some(Key::Character(c))
+                            if !state.modifiers.ctrl && !state.modifiers.alt && !state.modifiers.logo =>
+                        {
+                            let mnemonic = c.to_lowercase().chars().next();
+
+                            if let Some(position) = self.items.iter().position(|item| {
+                                item.mnemonic.is_some()
+                                    && item.mnemonic == mnemonic
+                                    && !item.entity.is_disabled(state)
+                            }) {
+                                state.set_focus(self.items[position].entity);
+                                self.activate(state, position);
+                                event.consume();
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
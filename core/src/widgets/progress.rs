@@ -26,6 +26,9 @@ impl ProgressBar {
 impl BuildHandler for ProgressBar {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ProgressBar);
+        entity.set_accessible_value(state, &self.value.to_string());
+
         entity.set_flex_direction(state, FlexDirection::Row);
 
         self.front = Element::new().build(state, entity, |builder| {
@@ -43,6 +46,7 @@ impl EventHandler for ProgressBar {
                 SliderEvent::SetValue(val) => {
                     if event.target == entity {
                         self.value = val.clamp(0.0, 1.0);
+                        entity.set_accessible_value(state, &self.value.to_string());
 
                         self.front.set_width(state, Length::Percentage(self.value));
                     }
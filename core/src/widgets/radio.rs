@@ -2,7 +2,7 @@
 
 const ICON_CHECK: &str = "\u{2713}";
 
-use crate::{Entity, HierarchyTree};
+use crate::{Entity, HierarchyTree, Key, WindowEvent};
 
 use crate::{BuildHandler, Event, EventHandler, Propagation};
 use crate::{PropSet, State};
@@ -21,12 +21,43 @@ impl RadioList {
 impl BuildHandler for RadioList {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::RadioGroup);
+
         entity.set_element(state, "radio_list")
     }
 }
 
 impl EventHandler for RadioList {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                // Arrow keys move focus between the radio buttons and check the newly focused one
+                WindowEvent::KeyDown(_, key) => {
+                    if state.focused.is_child_of(&state.hierarchy, entity) {
+                        let forward = match key {
+                            Some(Key::ArrowDown) | Some(Key::ArrowRight) => Some(true),
+                            Some(Key::ArrowUp) | Some(Key::ArrowLeft) => Some(false),
+                            _ => None,
+                        };
+
+                        if let Some(forward) = forward {
+                            if let Some(radio) = state.focus_child(entity, forward, true) {
+                                state.insert_event(
+                                    Event::new(CheckboxEvent::Checked)
+                                        .target(radio)
+                                        .origin(radio),
+                                );
+                            }
+
+                            event.consume();
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
         if let Some(radio_event) = event.message.downcast::<CheckboxEvent>() {
             match radio_event {
                 CheckboxEvent::Unchecked => {
@@ -131,6 +162,9 @@ impl Radio {
 impl BuildHandler for Radio {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::RadioButton);
+
         self.marker = Element::new().build(state, entity, |builder| {
             builder.set_hoverability(false).class("marker").set_hoverability(false)
         });
@@ -180,6 +214,7 @@ impl BuildHandler for RadioButton {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
         self.checkbox.on_build(state, entity);
+        entity.set_role(state, Role::RadioButton);
         entity.set_element(state, "radio_button").set_font(state, "sans")
     }
 }
@@ -4,7 +4,7 @@ use crate::entity::Entity;
 use crate::events::{BuildHandler, Event, EventHandler};
 use crate::state::style::*;
 use crate::WindowEvent;
-use crate::{MouseButton, State};
+use crate::{MouseButton, Role, State};
 
 use crate::widgets::{Button, Element, HBox, VBox};
 use crate::AnimationState;
@@ -13,6 +13,8 @@ use crate::AnimationState;
 pub enum ScrollEvent {
     ScrollV(f32),
     ScrollH(f32),
+    // Scrolls the content of a vertical scroll container by a number of pixels, positive scrolling down
+    ScrollByV(f32),
 }
 
 pub struct ScrollContainerH {
@@ -52,6 +54,8 @@ impl ScrollContainerH {
 impl BuildHandler for ScrollContainerH {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ScrollArea);
+
         entity
             .set_flex_direction(state, FlexDirection::Column)
             .set_width(state, Length::Percentage(1.0))
@@ -435,6 +439,8 @@ impl ScrollContainer {
 impl BuildHandler for ScrollContainer {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ScrollArea);
+
         entity.set_flex_direction(state, FlexDirection::Row);
 
         //println!("Container: {}", self.container);
@@ -501,6 +507,42 @@ impl BuildHandler for ScrollContainer {
 
 impl EventHandler for ScrollContainer {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(ScrollEvent::ScrollByV(dy)) = event.message.downcast::<ScrollEvent>() {
+            if event.target == entity {
+                let overflow = state.data.get_height(self.container) - state.data.get_height(entity);
+
+                if overflow <= 0.0 {
+                    return;
+                }
+
+                self.scrolly += *dy / overflow;
+
+                if self.scrolly < 0.0 {
+                    self.scrolly = 0.0;
+                }
+
+                if self.scrolly > 1.0 {
+                    self.scrolly = 1.0;
+                }
+
+                let overflow = 1.0
+                    - (state.data.get_height(self.container) / state.data.get_height(entity));
+                let overflow2 = 1.0
+                    - (state.data.get_height(entity) / state.data.get_height(self.container));
+
+                self.container
+                    .set_top(state, Length::Percentage(self.scrolly * overflow));
+                self.vertical_scroll
+                    .set_top(state, Length::Percentage(self.scrolly * overflow2));
+
+                state.insert_event(
+                    Event::new(ScrollEvent::ScrollV(self.scrolly * overflow)).target(entity),
+                );
+
+                event.consume();
+            }
+        }
+
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
                 WindowEvent::GeometryChanged(geometry_changed) => {
@@ -807,6 +849,8 @@ impl ScrollContainerHV {
 impl BuildHandler for ScrollContainerHV {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ScrollArea);
+
         entity
             .set_flex_direction(state, FlexDirection::Row)
             .set_flex_grow(state, 1.0)
@@ -3,7 +3,7 @@
 use crate::entity::Entity;
 use crate::events::{BuildHandler, Event, EventHandler};
 use crate::{MouseButton, WindowEvent};
-use crate::{PropSet, State};
+use crate::{PropSet, Role, State};
 
 use crate::state::style::*;
 
@@ -64,6 +64,8 @@ impl Scrollbar {
 impl BuildHandler for Scrollbar {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::ScrollBar);
+
         self.front = Button::new().build(state, entity, |builder| builder.class("front"));
         match self.direction {
             ScrollDirection::Horizontal => {
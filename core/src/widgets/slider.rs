@@ -3,11 +3,14 @@
 use crate::entity::Entity;
 use crate::mouse::*;
 use crate::{BuildHandler, Event, EventHandler, Length, WindowEvent};
-use crate::{PropSet, State};
+use crate::{PointerEvent, PropSet, Role, State};
 
 use crate::state::style::*;
 
-use crate::widgets::Element;
+use crate::widgets::{begin_edit, end_edit, perform_edit, reset_edit, Element};
+use crate::widgets::{LinearMapping, ValueMapping};
+
+use std::sync::Arc;
 
 
 #[derive(Debug, Clone, PartialEq)]
@@ -101,7 +104,7 @@ impl EventHandler for ProgressBar {
                             self.pressed_x = state.mouse.cursorx;
                             //state.captured = entity;
                             state.capture(entity);
-                            state.focused = entity;
+                            state.set_focus(entity);
 
                             let dx = (self.pressed_x - state.data.get_posx(entity))
                                 / state.data.get_width(entity);
@@ -221,13 +224,20 @@ pub struct Slider {
     thumb: Entity,
     active: Entity,
     sliding: bool,
+    // Whether the current drag has changed the value and so begun an edit
+    editing: bool,
     on_change: Option<Box<dyn Fn(f32) -> Event + Send>>,
 
     value: f32,
+    default: f32,
+
+    mapping: Arc<dyn ValueMapping>,
 
-    min: f32,
-    max: f32,
-    div: f32,
+    // The cursor x position and normalized value a drag continues from
+    pressed_x: f32,
+    pressed_nx: f32,
+    // Whether the fine adjustment modifier was held at the last mouse move of a drag
+    fine: bool,
 }
 
 impl Slider {
@@ -236,12 +246,16 @@ impl Slider {
             thumb: Entity::null(),
             active: Entity::null(),
             sliding: false,
+            editing: false,
             on_change: None,
 
-            min: 0.0,
-            max: 1.0,
-            div: 0.0,
+            mapping: Arc::new(LinearMapping::new(0.0, 1.0)),
             value: 0.0,
+            default: 0.0,
+
+            pressed_x: 0.0,
+            pressed_nx: 0.0,
+            fine: false,
         }
     }
 
@@ -253,41 +267,136 @@ impl Slider {
         self
     }
 
+    /// Sets the initial value, which is also the value the slider is reset to when double clicked
     pub fn with_initial_value(mut self, val: f32) -> Self {
         self.value = val;
+        self.default = val;
 
         self
     }
 
+    /// Sets the value the slider is reset to when double clicked
+    pub fn with_default(mut self, val: f32) -> Self {
+        self.default = val;
+        self
+    }
+
+    /// Sets the minimum of a linear range, replacing the mapping
     pub fn with_min(mut self, val: f32) -> Self {
-        self.min = val;
+        self.mapping = Arc::new(LinearMapping::new(val, self.mapping.max()));
         self
     }
 
+    /// Sets the maximum of a linear range, replacing the mapping
     pub fn with_max(mut self, val: f32) -> Self {
-        self.max = val;
+        self.mapping = Arc::new(LinearMapping::new(self.mapping.min(), val));
+        self
+    }
+
+    /// Does nothing, the divisions were never used by the slider
+    #[deprecated(note = "has no effect, use `with_mapping(LinearMapping::new(min, max).with_step(step))` to snap the slider")]
+    pub fn with_divisions(self, _val: f32) -> Self {
         self
     }
 
-    pub fn with_divisions(mut self, val: f32) -> Self {
-        self.div = val;
+    /// Sets how the position of the thumb maps to a value, which is linear between 0 and 1 by default
+    ///
+    /// Use a `LinearMapping` with a step to snap the slider to divisions.
+    pub fn with_mapping<M: ValueMapping + 'static>(mut self, mapping: M) -> Self {
+        self.mapping = Arc::new(mapping);
         self
     }
+
+    fn normalized(&self, value: f32) -> f32 {
+        self.mapping.to_normalized(value).min(1.0).max(0.0)
+    }
+
+    // Moves the thumb and resizes the active bar to show a normalized value
+    fn update_thumb(&self, state: &mut State, entity: Entity, nx: f32) {
+        let width = state.data.get_width(entity);
+        let thumb_width = state.data.get_width(self.thumb);
+
+        self.active.set_width(state, Length::Percentage(nx));
+
+        if width > 0.0 {
+            self.thumb
+                .set_left(state, Length::Percentage(nx * (width - thumb_width) / width));
+        }
+    }
+
+    // Sets the value from a normalized value and notifies listeners, returning whether it changed
+    fn change(&mut self, state: &mut State, entity: Entity, nx: f32) -> bool {
+        let v = self.mapping.to_plain(nx);
+
+        self.update_thumb(state, entity, self.normalized(v));
+
+        if v == self.value {
+            return false;
+        }
+
+        self.value = v;
+
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(v);
+            event.origin = entity;
+
+            state.insert_event(event);
+        }
+
+        entity.set_accessible_value(state, &self.mapping.format(v));
+
+        state.insert_event(Event::new(SliderEvent::ValueChanged(v)).target(entity));
+
+        true
+    }
+
+    // Sends a value changed by a drag as part of its edit, beginning the edit with the first change
+    // so that pressing without changing the value sends no edit
+    fn perform_drag_edit(&mut self, state: &mut State, entity: Entity) {
+        if !self.editing {
+            self.editing = true;
+            begin_edit(state, entity);
+        }
+
+        perform_edit(state, entity, self.value, self.normalized(self.value));
+    }
+
+    // The normalized value under a cursor x position
+    fn position_to_normalized(&self, state: &State, entity: Entity, x: f32) -> f32 {
+        let width = state.data.get_width(entity);
+        let thumb_width = state.data.get_width(self.thumb);
+
+        if width <= thumb_width {
+            return 0.0;
+        }
+
+        let dx = x - state.data.get_posx(entity) - thumb_width / 2.0;
+
+        (dx / (width - thumb_width)).min(1.0).max(0.0)
+    }
 }
 
 impl BuildHandler for Slider {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Slider);
+
         entity.set_flex_direction(state, FlexDirection::Row);
         // .set_width(state, Length::Pixels(100.0))
         // .set_height(state, Length::Pixels(4.0));
         //.set_align_items(state, AlignItems::Center)
         //.set_background_color(state, Color::rgb(200, 80, 80));
 
+        self.value = self.mapping.snap(self.value);
+        self.default = self.mapping.snap(self.default);
+
+        let nx = self.normalized(self.value);
+
         self.active = Element::new().build(state, entity, |builder| {
             builder
                 .set_position(Position::Absolute)
-                .set_width(Length::Percentage(0.0))
+                .set_width(Length::Percentage(nx))
                 .set_height(Length::Percentage(1.0))
                 //.set_background_color(Color::rgb(60, 60, 200))
                 .set_hoverability(false)
@@ -307,9 +416,7 @@ impl BuildHandler for Slider {
             }, //.set_background_color(Color::rgb(80, 80, 200))
         );
 
-        // TEMP
-        // self.thumb.set_left(state, Length::Pixels(80.0));
-        // self.active.set_width(state, Length::Percentage(1.0));
+        entity.set_accessible_value(state, &self.mapping.format(self.value));
 
         state.style.insert_element(entity, "slider");
 
@@ -319,91 +426,104 @@ impl BuildHandler for Slider {
 
 impl EventHandler for Slider {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(slider_event) = event.message.downcast::<SliderEvent>() {
+            match slider_event {
+                SliderEvent::SetValue(val) => {
+                    if event.target == entity {
+                        self.value = self.mapping.snap(*val);
+
+                        let nx = self.normalized(self.value);
+                        self.update_thumb(state, entity, nx);
+
+                        entity.set_accessible_value(state, &self.mapping.format(self.value));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                // Double clicking resets the slider to its default value
+                PointerEvent::Click { pointer, count } => {
+                    if (event.target == entity || event.target == self.thumb)
+                        && pointer.button == MouseButton::Left
+                        && *count == 2
+                    {
+                        let nx = self.normalized(self.default);
+                        self.change(state, entity, nx);
+
+                        reset_edit(state, entity, self.value, self.normalized(self.value));
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
             match window_event {
                 WindowEvent::MouseDown(button) => {
-                    if *button == MouseButton::Left && event.target == entity
-                        || event.target == self.thumb
+                    if *button == MouseButton::Left
+                        && (event.target == entity || event.target == self.thumb)
                     {
                         self.sliding = true;
                         state.capture(entity);
 
-                        let width = state.data.get_width(entity);
-                        let thumb_width = state.data.get_width(self.thumb);
+                        let x = state.mouse.left.pos_down.0;
 
-                        let mut dx = state.mouse.left.pos_down.0 - state.data.get_posx(entity);
+                        // With the fine adjustment modifier held the value is only changed by dragging
+                        self.fine = state.modifiers.shift;
+                        self.pressed_x = x;
+                        self.pressed_nx = if self.fine {
+                            self.normalized(self.value)
+                        } else {
+                            self.position_to_normalized(state, entity, x)
+                        };
 
-                        if dx <= thumb_width / 2.0 {
-                            dx = thumb_width / 2.0;
+                        if self.change(state, entity, self.pressed_nx) {
+                            self.perform_drag_edit(state, entity);
                         }
-                        if dx >= width - thumb_width / 2.0 {
-                            dx = width - thumb_width / 2.0;
-                        }
-
-                        let nx = (dx - thumb_width / 2.0) / (width - thumb_width);
-
-                        let v = self.min + nx * (self.max - self.min);
-
-                        self.active.set_width(state, Length::Percentage(nx));
-                        self.thumb
-                            .set_left(state, Length::Pixels(dx - thumb_width / 2.0));
-
-                        if let Some(on_change) = &self.on_change {
-                            let mut event = (on_change)(v);
-                            event.origin = entity;
-
-                            state.insert_event(event);
-                        }
-
-                        state.insert_event(Event::new(SliderEvent::ValueChanged(v)).target(entity));
                     }
                 }
 
                 WindowEvent::MouseUp(button) => {
                     if *button == MouseButton::Left {
+                        if self.editing {
+                            end_edit(state, entity);
+                        }
+
                         self.sliding = false;
+                        self.editing = false;
                         state.release(entity);
                     }
                 }
 
                 WindowEvent::MouseMove(x, _) => {
                     if self.sliding {
+                        // Pressing or releasing the fine adjustment modifier continues the drag from the current value
+                        if state.modifiers.shift != self.fine {
+                            self.fine = state.modifiers.shift;
+                            self.pressed_x = *x;
+                            self.pressed_nx = self.normalized(self.value);
+                        }
+
                         let width = state.data.get_width(entity);
                         let thumb_width = state.data.get_width(self.thumb);
 
-                        let mut dx = *x - state.data.get_posx(entity);
-
-                        if dx <= thumb_width / 2.0 {
-                            dx = thumb_width / 2.0;
+                        if width <= thumb_width {
+                            return;
                         }
-                        if dx >= width - thumb_width / 2.0 {
-                            dx = width - thumb_width / 2.0;
-                        }
-
-                        // if dx <= 0.0 {
-                        //     dx = 0.0;
-                        // } if dx >= width - thumb_width {
-                        //     dx = width - thumb_width;
-                        // }
-
-                        // let nx = (dx - thumb_width/2.0) / (width - thumb_width);
-                        let nx = (dx - thumb_width / 2.0) / (width - thumb_width);
 
-                        let v = self.min + nx * (self.max - self.min);
+                        let scale = if self.fine { 0.1 } else { 1.0 };
 
-                        self.active.set_width(state, Length::Percentage(nx));
-                        //self.thumb.set_left(state, Length::Pixels(dx - thumb_width/2.0));
-                        self.thumb
-                            .set_left(state, Length::Percentage((dx - thumb_width / 2.0) / width));
+                        let nx = self.pressed_nx
+                            + scale * (*x - self.pressed_x) / (width - thumb_width);
 
-                        if let Some(on_change) = &self.on_change {
-                            let mut event = (on_change)(v);
-                            event.origin = entity;
-
-                            state.insert_event(event);
+                        if self.change(state, entity, nx) {
+                            self.perform_drag_edit(state, entity);
                         }
-
-                        state.insert_event(Event::new(SliderEvent::ValueChanged(v)).target(entity));
                     }
                 }
 
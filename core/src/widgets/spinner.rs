@@ -9,7 +9,7 @@ const ICON_UP_OPEN_MINI: &str = "\u{e763}";
 use crate::entity::Entity;
 use crate::events::*;
 use crate::state::style::*;
-use crate::{PropSet, State, WindowEvent};
+use crate::{PropSet, Role, State, WindowEvent};
 
 use crate::state::mouse::MouseButton;
 
@@ -169,6 +169,8 @@ where
 {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::SpinButton);
+
         if self.value <= self.min {
             self.value = self.min;
         }
@@ -28,6 +28,7 @@ impl BuildHandler for Switch {
 
 
         self.checkbox.on_build(state, entity);
+        entity.set_role(state, Role::Switch);
 
         self.front = Element::new().build(state, entity, |builder| {
             builder.class("front").set_hoverability(false)
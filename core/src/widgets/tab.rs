@@ -2,7 +2,7 @@
 
 use std::usize;
 
-use crate::{CheckboxEvent, Entity, HierarchyTree, MouseButton, Propagation, Radio, RadioList, State, PropGet, AnimationState};
+use crate::{CheckboxEvent, Entity, HierarchyTree, IntoChildIterator, Key, MouseButton, Propagation, Radio, RadioList, State, PropGet, AnimationState};
 
 use crate::events::{BuildHandler, Event, EventHandler};
 
@@ -34,6 +34,7 @@ impl BuildHandler for TabBar {
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
 
         self.list.on_build(state, entity);
+        entity.set_role(state, Role::TabList);
 
         //entity.set_flex_direction(state, FlexDirection::Row);
 
@@ -45,10 +46,56 @@ impl BuildHandler for TabBar {
 
 impl EventHandler for TabBar {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        navigate_tabs(state, entity, event);
+
         self.list.on_event(state, entity, event);
     }
 }
 
+// Moves focus and the selection to the previous or next tab with the arrow keys, and to the first
+// or last tab with Home and End. Hidden tabs, such as the phantom tabs of a moving tab, are skipped.
+fn navigate_tabs(state: &mut State, tab_bar: Entity, event: &mut Event) {
+    let key = match event.message.downcast::<WindowEvent>() {
+        Some(WindowEvent::KeyDown(_, key)) => key.clone(),
+        _ => return,
+    };
+
+    if !state.focused.is_child_of(&state.hierarchy, tab_bar) {
+        return;
+    }
+
+    let mut tabs = tab_bar.child_iter(&state.hierarchy).collect::<Vec<_>>();
+    tabs.retain(|tab| {
+        state.style.display.get(*tab).cloned().unwrap_or_default() != Display::None
+            && state.is_focusable(*tab)
+    });
+
+    if tabs.is_empty() {
+        return;
+    }
+
+    let current = tabs.iter().position(|tab| *tab == state.focused);
+
+    let index = match key {
+        Some(Key::ArrowRight) => current.map(|index| (index + 1) % tabs.len()).unwrap_or(0),
+        Some(Key::ArrowLeft) => current
+            .map(|index| (index + tabs.len() - 1) % tabs.len())
+            .unwrap_or(tabs.len() - 1),
+        Some(Key::Home) => 0,
+        Some(Key::End) => tabs.len() - 1,
+        _ => return,
+    };
+
+    state.set_focus(tabs[index]);
+    state.insert_event(
+        Event::new(CheckboxEvent::Checked)
+            .target(tabs[index])
+            .origin(tabs[index]),
+    );
+
+    event.consume();
+}
+
 pub struct Tab {
     pub name: String,
     radio: Radio,
@@ -66,6 +113,9 @@ impl Tab {
 impl BuildHandler for Tab {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Tab);
+
 
         entity.set_element(state, "tab")
     }
@@ -75,6 +125,21 @@ impl EventHandler for Tab {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
         self.radio.on_event(state, entity, event);
 
+        // Only the selected tab is a tab stop, the other tabs are reached with the arrow keys
+        if let Some(checkbox_event) = event.message.downcast::<CheckboxEvent>() {
+            match checkbox_event {
+                CheckboxEvent::Checked | CheckboxEvent::Check => {
+                    entity.set_tab_index(state, 0);
+                }
+
+                CheckboxEvent::Unchecked | CheckboxEvent::Uncheck => {
+                    entity.set_tab_index(state, -1);
+                }
+
+                _ => {}
+            }
+        }
+
         if let Some(tab_event) = event.message.downcast::<TabEvent>() {
             match tab_event {
                 TabEvent::SwitchTab(name) => {
@@ -153,6 +218,8 @@ impl TabContainer {
 impl BuildHandler for TabContainer {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_role(state, Role::TabPanel);
+
         entity.set_element(state, "tab_container")
     }
 }
@@ -210,6 +277,7 @@ impl BuildHandler for TabBar2 {
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
 
         self.list.on_build(state, entity);
+        entity.set_role(state, Role::TabList);
 
         self.phantom_tab1 = Tab::new("phantom1").build(state, entity, |builder| builder
             .set_display(Display::None)
@@ -245,6 +313,7 @@ impl BuildHandler for TabBar2 {
 
 impl EventHandler for TabBar2 {
     fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        navigate_tabs(state, entity, event);
 
         self.list.on_event(state, entity, event);
 
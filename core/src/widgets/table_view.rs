@@ -0,0 +1,788 @@
+use crate::widgets::*;
+use crate::state::style::*;
+use crate::{HierarchyTree, IntoChildIterator, Key, MouseButton, PointerEvent, ScrollEvent};
+
+const ICON_SORT_ASCENDING: &str = "\u{25b4}";
+const ICON_SORT_DESCENDING: &str = "\u{25be}";
+
+// Width of the area at the right edge of a column header which can be dragged to resize the column
+const RESIZE_HANDLE_WIDTH: f32 = 4.0;
+
+const MIN_COLUMN_WIDTH: f32 = 20.0;
+
+/// Provides the cells of a `TableView`
+///
+/// Like `ListDataSource`, row entities are recycled while scrolling. `build_cell` is called once per
+/// cell entity and `update_cell` every time the cell is assigned a different row. Columns are
+/// identified by the order they were added to the table, which doesn't change when they are moved.
+pub trait TableDataSource: Send {
+    /// Returns the number of rows in the table
+    fn row_count(&self) -> usize;
+
+    /// Builds the child widgets of a new cell entity. By default cells only show text.
+    fn build_cell(&mut self, _state: &mut State, _cell: Entity, _column: usize) {}
+
+    /// Updates a cell entity to show the given row and column
+    fn update_cell(&mut self, state: &mut State, cell: Entity, row: usize, column: usize);
+
+    /// Returns the height of a row when the table uses measured row heights
+    fn measure_row(&mut self, _state: &State, _row: usize) -> Option<f32> {
+        None
+    }
+
+    /// Sorts the rows by a column. Returns false if the column can't be sorted.
+    fn sort(&mut self, _column: usize, _order: SortOrder) -> bool {
+        false
+    }
+
+    /// Returns true if the cell can be edited with a textbox
+    fn is_editable(&self, _row: usize, _column: usize) -> bool {
+        false
+    }
+
+    /// Called with the new text of a cell after it has been edited
+    fn set_cell_text(&mut self, _row: usize, _column: usize, _text: &str) {}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableEvent {
+    // Sets the selected row indices
+    SetSelection(Vec<usize>),
+    // Emitted when the selected rows are changed by the user
+    SelectionChanged(Vec<usize>),
+    // Emitted when the rows are sorted by clicking a column header
+    Sorted(usize, SortOrder),
+    // Emitted when a column is resized, with the column and its new width
+    ColumnResized(usize, f32),
+    // Emitted when a column is dragged to a new position, with the old and new positions
+    ColumnMoved(usize, usize),
+    // Emitted when a cell has been edited, with the row, column and new text
+    CellEdited(usize, usize, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableColumn {
+    pub title: String,
+    pub width: f32,
+    id: usize,
+}
+
+impl TableColumn {
+    pub fn new(title: &str, width: f32) -> Self {
+        TableColumn {
+            title: title.to_string(),
+            width,
+            id: 0,
+        }
+    }
+}
+
+// Presents the cells of a table data source as the rows of a virtual list
+struct TableRows<'a> {
+    source: &'a mut dyn TableDataSource,
+    columns: &'a [TableColumn],
+    selection: &'a Selection,
+}
+
+impl<'a> ListDataSource for TableRows<'a> {
+    fn count(&self) -> usize {
+        self.source.row_count()
+    }
+
+    fn build_row(&mut self, state: &mut State, row: Entity) {
+        row.set_flex_direction(state, FlexDirection::Row);
+
+        for column in self.columns.iter() {
+            let cell = Element::new().build(state, row, |builder| {
+                builder
+                    .set_width(Length::Pixels(column.width))
+                    .set_role(Role::Cell)
+                    .class("cell")
+            });
+
+            self.source.build_cell(state, cell, column.id);
+        }
+    }
+
+    fn update_row(&mut self, state: &mut State, row: Entity, index: usize) {
+        let cells = row.child_iter(&state.hierarchy).collect::<Vec<_>>();
+
+        for (cell, column) in cells.into_iter().zip(self.columns.iter()) {
+            self.source.update_cell(state, cell, index, column.id);
+        }
+
+        row.set_checked(state, self.selection.contains(index));
+    }
+
+    fn measure_row(&mut self, state: &State, index: usize) -> Option<f32> {
+        self.source.measure_row(state, index)
+    }
+}
+
+// A column header being dragged
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HeaderDrag {
+    // Resizing the column at a position, with its width when the drag started
+    Resize(usize, f32),
+    // Moving the column at a position
+    Move(usize),
+}
+
+// A cell being edited
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CellEdit {
+    row: usize,
+    column: usize,
+    textbox: Entity,
+}
+
+/// A table which only creates entities for the visible rows
+///
+/// Clicking a column header sorts the rows, dragging the right edge of a header resizes the column
+/// and dragging the rest of the header moves the column. Double clicking an editable cell, or
+/// pressing F2 with a row selected, edits the cell text with a textbox.
+pub struct TableView {
+    source: Box<dyn TableDataSource>,
+    columns: Vec<TableColumn>,
+    rows: VirtualRows,
+    selection: Selection,
+    // The row moved by the arrow keys, which is the anchor unless a range is being extended
+    cursor: Option<usize>,
+
+    sort: Option<(usize, SortOrder)>,
+    header_drag: Option<HeaderDrag>,
+    edit: Option<CellEdit>,
+
+    header: Entity,
+    scroll: Entity,
+    content: Entity,
+
+    on_change: Option<Box<dyn Fn(Vec<usize>) -> Event + Send>>,
+}
+
+impl TableView {
+    pub fn new<S: TableDataSource + 'static>(source: S) -> Self {
+        TableView {
+            source: Box::new(source),
+            columns: Vec::new(),
+            rows: VirtualRows::new(RowHeight::Fixed(30.0), Role::Row),
+            selection: Selection::new(SelectionMode::Single),
+            cursor: None,
+
+            sort: None,
+            header_drag: None,
+            edit: None,
+
+            header: Entity::null(),
+            scroll: Entity::null(),
+            content: Entity::null(),
+
+            on_change: None,
+        }
+    }
+
+    pub fn with_column(mut self, mut column: TableColumn) -> Self {
+        column.id = self.columns.len();
+        self.columns.push(column);
+
+        self
+    }
+
+    pub fn with_row_height(mut self, row_height: RowHeight) -> Self {
+        self.rows = VirtualRows::new(row_height, Role::Row);
+
+        self
+    }
+
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        self.selection.mode = mode;
+
+        self
+    }
+
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(Vec<usize>) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+
+        self
+    }
+
+    fn reload(&mut self, state: &mut State) {
+        self.cancel_edit(state);
+        self.selection.clamp(self.source.row_count());
+        self.cursor = self.cursor.filter(|cursor| *cursor < self.source.row_count());
+
+        let mut table_rows = TableRows {
+            source: self.source.as_mut(),
+            columns: &self.columns,
+            selection: &self.selection,
+        };
+
+        self.rows.reload(state, self.content, &mut table_rows);
+    }
+
+    fn refresh(&mut self, state: &mut State) {
+        let mut table_rows = TableRows {
+            source: self.source.as_mut(),
+            columns: &self.columns,
+            selection: &self.selection,
+        };
+
+        self.rows.refresh(state, &mut table_rows);
+    }
+
+    fn update_rows(&mut self, state: &mut State) {
+        let viewport = state.data.get_height(self.scroll);
+
+        let mut table_rows = TableRows {
+            source: self.source.as_mut(),
+            columns: &self.columns,
+            selection: &self.selection,
+        };
+
+        self.rows
+            .update(state, self.content, viewport, &mut table_rows);
+
+        // Stop editing a cell when its row is scrolled out of view and recycled
+        if let Some(edit) = self.edit {
+            if self.rows.row(edit.row).is_none() {
+                self.cancel_edit(state);
+            }
+        }
+    }
+
+    fn header_cells(&self, state: &State) -> Vec<Entity> {
+        self.header.child_iter(&state.hierarchy).collect()
+    }
+
+    // Returns the header and every row, which each have one cell per column
+    fn cell_containers(&self) -> Vec<Entity> {
+        let mut containers = self.rows.rows();
+        containers.push(self.header);
+        containers
+    }
+
+    // Sets the width of the cells of every column
+    fn apply_column_widths(&mut self, state: &mut State) {
+        for container in self.cell_containers() {
+            let cells = container.child_iter(&state.hierarchy).collect::<Vec<_>>();
+            for (cell, column) in cells.into_iter().zip(self.columns.iter()) {
+                state.style.width.insert(cell, Length::Pixels(column.width));
+            }
+        }
+
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Shows the sort indicator on the header of the sorted column
+    fn update_sort_indicators(&mut self, state: &mut State) {
+        let header_cells = self.header_cells(state);
+
+        for (header_cell, column) in header_cells.into_iter().zip(self.columns.iter()) {
+            if let Some(indicator) = state.hierarchy.get_last_child(header_cell) {
+                let icon = match self.sort {
+                    Some((id, SortOrder::Ascending)) if id == column.id => ICON_SORT_ASCENDING,
+                    Some((id, SortOrder::Descending)) if id == column.id => ICON_SORT_DESCENDING,
+                    _ => "",
+                };
+
+                indicator.set_text(state, icon);
+            }
+        }
+    }
+
+    // Sorts the rows by the column at a position, reversing the order if it is already sorted by it
+    fn sort_column(&mut self, state: &mut State, entity: Entity, position: usize) {
+        let id = self.columns[position].id;
+
+        let order = match self.sort {
+            Some((sorted, SortOrder::Ascending)) if sorted == id => SortOrder::Descending,
+            _ => SortOrder::Ascending,
+        };
+
+        if !self.source.sort(id, order) {
+            return;
+        }
+
+        self.cancel_edit(state);
+
+        self.sort = Some((id, order));
+        self.update_sort_indicators(state);
+
+        // The selected indices refer to rows which have moved
+        self.selection.anchor = None;
+        self.cursor = None;
+        if self.selection.set(Vec::new()) {
+            self.selection_changed(state, entity);
+        }
+
+        self.refresh(state);
+
+        state.insert_event(
+            Event::new(TableEvent::Sorted(id, order))
+                .target(entity)
+                .origin(entity),
+        );
+    }
+
+    // Moves the column at one position to another by moving its cells in the header and every row
+    fn move_column(&mut self, state: &mut State, entity: Entity, from: usize, to: usize) {
+        if from == to || from >= self.columns.len() || to >= self.columns.len() {
+            return;
+        }
+
+        for container in self.cell_containers() {
+            let cells = container.child_iter(&state.hierarchy).collect::<Vec<_>>();
+
+            if cells.len() != self.columns.len() {
+                continue;
+            }
+
+            if to < from {
+                state.hierarchy.set_prev_sibling(cells[to], cells[from]).ok();
+            } else {
+                state.hierarchy.set_next_sibling(cells[to], cells[from]).ok();
+            }
+        }
+
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+
+        state.insert_event(
+            Event::new(TableEvent::ColumnMoved(from, to))
+                .target(entity)
+                .origin(entity),
+        );
+    }
+
+    // Returns the position of the column under an x coordinate, clamped to the first and last columns
+    fn column_at(&self, state: &State, x: f32) -> usize {
+        let header_cells = self.header_cells(state);
+
+        header_cells
+            .iter()
+            .position(|cell| x < state.data.get_posx(*cell) + state.data.get_width(*cell))
+            .unwrap_or(header_cells.len().saturating_sub(1))
+    }
+
+    // Returns true if the x coordinate is over the resize handle at the right edge of a header cell
+    fn on_resize_handle(&self, state: &State, header_cell: Entity, x: f32) -> bool {
+        let right = state.data.get_posx(header_cell) + state.data.get_width(header_cell);
+
+        x >= right - RESIZE_HANDLE_WIDTH
+    }
+
+    fn select_row(&mut self, state: &mut State, entity: Entity, index: usize, toggle: bool) {
+        let shift = state.modifiers.shift;
+        let ctrl = state.modifiers.ctrl || state.modifiers.logo;
+
+        self.cursor = Some(index);
+
+        if self.selection.select(index, shift, ctrl, toggle) {
+            self.selection_changed(state, entity);
+        }
+    }
+
+    fn update_selected_rows(&mut self, state: &mut State) {
+        for (index, row) in self.rows.active().to_vec() {
+            row.set_checked(state, self.selection.contains(index));
+        }
+    }
+
+    fn selection_changed(&mut self, state: &mut State, entity: Entity) {
+        self.update_selected_rows(state);
+
+        let selected = self.selection.selected.clone();
+
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(selected.clone());
+            event.origin = entity;
+
+            if event.target == Entity::null() {
+                event.target = entity;
+            }
+
+            state.insert_event(event);
+        }
+
+        state.insert_event(
+            Event::new(TableEvent::SelectionChanged(selected))
+                .target(entity)
+                .origin(entity),
+        );
+    }
+
+    // Places a textbox over the cell at a row and column position to edit its text
+    fn begin_edit(&mut self, state: &mut State, row: usize, position: usize) {
+        let column = self.columns[position].id;
+
+        if !self.source.is_editable(row, column) {
+            return;
+        }
+
+        self.cancel_edit(state);
+
+        let cell = match self
+            .rows
+            .row(row)
+            .and_then(|row| row.child_iter(&state.hierarchy).nth(position))
+        {
+            Some(cell) => cell,
+            None => return,
+        };
+
+        let text = state
+            .style
+            .text
+            .get(cell)
+            .map(|text| text.text.clone())
+            .unwrap_or_default();
+
+        let textbox = Textbox::new(&text).build(state, cell, |builder| {
+            builder
+                .set_position(Position::Absolute)
+                .set_left(Length::Pixels(0.0))
+                .set_top(Length::Pixels(0.0))
+                .set_width(Length::Percentage(1.0))
+                .set_height(Length::Percentage(1.0))
+                .class("cell_editor")
+        });
+
+        self.edit = Some(CellEdit {
+            row,
+            column,
+            textbox,
+        });
+
+        // Focusing the textbox starts editing
+        state.set_focus(textbox);
+    }
+
+    // Removes the textbox of the cell being edited without changing the cell
+    fn cancel_edit(&mut self, state: &mut State) {
+        if let Some(edit) = self.edit.take() {
+            if state.focused.is_descendant_of(&state.hierarchy, edit.textbox) {
+                state.set_focus(Entity::root());
+            }
+
+            state.remove(edit.textbox);
+        }
+    }
+}
+
+impl BuildHandler for TableView {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Column);
+
+        self.header = HBox::new().build(state, entity, |builder| {
+            builder.set_role(Role::Row).class("header")
+        });
+
+        for column in self.columns.iter() {
+            let header_cell = Element::new().build(state, self.header, |builder| {
+                builder
+                    .set_flex_direction(FlexDirection::Row)
+                    .set_width(Length::Pixels(column.width))
+                    .set_role(Role::ColumnHeader)
+                    .set_accessible_name(&column.title)
+                    .class("column_header")
+            });
+
+            // Children of the header cell don't receive pointer events so the cell is always the target
+            Label::new(&column.title).build(state, header_cell, |builder| {
+                builder.set_flex_grow(1.0).set_hoverability(false)
+            });
+
+            Element::new().build(state, header_cell, |builder| {
+                builder.set_hoverability(false).class("sort_indicator")
+            });
+        }
+
+        self.content = ScrollContainer::new().build(state, entity, |builder| builder.set_flex_grow(1.0));
+
+        self.scroll = state
+            .hierarchy
+            .get_parent(self.content)
+            .unwrap_or(Entity::null());
+
+        self.content
+            .set_left(state, Length::Pixels(0.0))
+            .set_width(state, Length::Percentage(1.0));
+
+        self.reload(state);
+
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Table);
+
+        entity.set_element(state, "table_view")
+    }
+}
+
+impl EventHandler for TableView {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(list_view_event) = event.message.downcast::<ListViewEvent>() {
+            match list_view_event {
+                ListViewEvent::Reload => {
+                    if event.target == entity {
+                        self.reload(state);
+                        self.update_rows(state);
+                    }
+                }
+            }
+        }
+
+        if let Some(table_event) = event.message.downcast::<TableEvent>() {
+            match table_event {
+                TableEvent::SetSelection(selected) => {
+                    if event.target == entity {
+                        self.selection.set(selected.clone());
+                        self.selection.anchor = self.selection.selected.first().cloned();
+                        self.cursor = self.selection.anchor;
+                        self.update_selected_rows(state);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        // The textbox of an edited cell submits its text when editing finishes
+        if let Some(textbox_event) = event.message.downcast::<TextboxEvent>() {
+            match textbox_event {
+                TextboxEvent::ValueChanged(text) => {
+                    if let Some(edit) = self.edit {
+                        if event.target == edit.textbox {
+                            let text = text.clone();
+
+                            self.source.set_cell_text(edit.row, edit.column, &text);
+                            self.cancel_edit(state);
+                            self.refresh(state);
+                            state.set_focus(entity);
+
+                            state.insert_event(
+                                Event::new(TableEvent::CellEdited(edit.row, edit.column, text))
+                                    .target(entity)
+                                    .origin(entity),
+                            );
+
+                            event.consume();
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        let target = event.target;
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                PointerEvent::Click { pointer, count } => {
+                    if pointer.button != MouseButton::Left {
+                        return;
+                    }
+
+                    let header_cells = self.header_cells(state);
+
+                    if let Some(position) = header_cells.iter().position(|cell| *cell == target) {
+                        if !self.on_resize_handle(state, header_cells[position], pointer.x) {
+                            self.sort_column(state, entity, position);
+                        }
+
+                        event.consume();
+                        return;
+                    }
+
+                    if let Some(edit) = self.edit {
+                        if target.is_descendant_of(&state.hierarchy, edit.textbox) {
+                            return;
+                        }
+                    }
+
+                    if let Some(index) = self.rows.index_of(state, target) {
+                        if *count == 2 {
+                            let position = self.rows.row(index).and_then(|row| {
+                                row.child_iter(&state.hierarchy)
+                                    .position(|cell| target.is_descendant_of(&state.hierarchy, cell))
+                            });
+
+                            if let Some(position) = position {
+                                self.begin_edit(state, index, position);
+                            }
+                        } else {
+                            state.set_focus(entity);
+                            self.select_row(state, entity, index, true);
+                        }
+
+                        event.consume();
+                    }
+                }
+
+                PointerEvent::DragStart { pointer, x, .. } => {
+                    if pointer.button != MouseButton::Left {
+                        return;
+                    }
+
+                    let header_cells = self.header_cells(state);
+
+                    if let Some(position) = header_cells.iter().position(|cell| *cell == target) {
+                        let header_cell = header_cells[position];
+
+                        // Like `ResizableVBox`, dragging the edge of a header resizes the column
+                        self.header_drag = if self.on_resize_handle(state, header_cell, *x) {
+                            Some(HeaderDrag::Resize(position, self.columns[position].width))
+                        } else {
+                            header_cell.set_active(state, true);
+                            Some(HeaderDrag::Move(position))
+                        };
+
+                        event.consume();
+                    }
+                }
+
+                PointerEvent::DragMove { total_dx, .. } => {
+                    if let Some(HeaderDrag::Resize(position, width)) = self.header_drag {
+                        self.columns[position].width = (width + *total_dx).max(MIN_COLUMN_WIDTH);
+                        self.apply_column_widths(state);
+
+                        event.consume();
+                    }
+                }
+
+                PointerEvent::DragEnd { pointer, .. } => match self.header_drag.take() {
+                    Some(HeaderDrag::Resize(position, _)) => {
+                        let column = &self.columns[position];
+
+                        state.insert_event(
+                            Event::new(TableEvent::ColumnResized(column.id, column.width))
+                                .target(entity)
+                                .origin(entity),
+                        );
+
+                        event.consume();
+                    }
+
+                    Some(HeaderDrag::Move(position)) => {
+                        if let Some(header_cell) = self.header_cells(state).get(position) {
+                            header_cell.set_active(state, false);
+                        }
+
+                        let to = self.column_at(state, pointer.x);
+                        self.move_column(state, entity, position, to);
+
+                        event.consume();
+                    }
+
+                    None => {}
+                },
+
+                _ => {}
+            }
+        }
+
+        if let Some(scroll_event) = event.message.downcast::<ScrollEvent>() {
+            match scroll_event {
+                ScrollEvent::ScrollV(top) => {
+                    if event.target == self.scroll {
+                        self.rows.scroll = -*top * state.data.get_height(self.scroll);
+                        self.update_rows(state);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::GeometryChanged(_) => {
+                    if event.target == entity
+                        || event.target == self.scroll
+                        || event.target == self.content
+                    {
+                        self.rows.scroll =
+                            state.data.get_posy(self.scroll) - state.data.get_posy(self.content);
+                        self.update_rows(state);
+                    }
+                }
+
+                WindowEvent::KeyDown(_, key) => {
+                    let key = key.clone();
+
+                    // Escape cancels editing a cell
+                    if let Some(edit) = self.edit {
+                        if key == Some(Key::Escape)
+                            && state.focused.is_descendant_of(&state.hierarchy, edit.textbox)
+                        {
+                            self.cancel_edit(state);
+                            state.set_focus(entity);
+                            event.consume();
+                        }
+                    }
+
+                    if state.focused != entity {
+                        return;
+                    }
+
+                    let count = self.source.row_count();
+
+                    if count == 0 {
+                        return;
+                    }
+
+                    let cursor = self.cursor;
+
+                    let index = match key {
+                        Some(Key::ArrowDown) => cursor.map(|c| (c + 1).min(count - 1)).unwrap_or(0),
+                        Some(Key::ArrowUp) => cursor.map(|c| c.saturating_sub(1)).unwrap_or(count - 1),
+                        Some(Key::Home) => 0,
+                        Some(Key::End) => count - 1,
+
+                        Some(Key::F2) => {
+                            if let Some(cursor) = cursor {
+                                let editable = (0..self.columns.len()).find(|position| {
+                                    self.source.is_editable(cursor, self.columns[*position].id)
+                                });
+
+                                if let Some(position) = editable {
+                                    self.begin_edit(state, cursor, position);
+                                    event.consume();
+                                }
+                            }
+
+                            return;
+                        }
+
+                        _ => return,
+                    };
+
+                    self.select_row(state, entity, index, false);
+
+                    let viewport = state.data.get_height(self.scroll);
+                    let dy = self.rows.scroll_into_view(index, viewport);
+                    if dy != 0.0 {
+                        state.insert_event(Event::new(ScrollEvent::ScrollByV(dy)).target(self.scroll));
+                    }
+
+                    event.consume();
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
@@ -2,12 +2,14 @@
 
 use crate::entity::Entity;
 use crate::events::*;
-use crate::{BuildHandler, Justify, Length, PropSet, State, Visibility, WindowEvent};
+use crate::{BuildHandler, Justify, Length, PropSet, Role, State, Visibility, WindowEvent};
 
 use femtovg::{renderer::OpenGl, Align, Baseline, Canvas, Color, Paint, Path};
 
 use crate::Key;
 
+use std::any::Any;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum TextboxEvent {
     SetValue(String),
@@ -32,9 +34,14 @@ pub struct Textbox {
     hitx: f32,
     dragx: f32,
 
+    // Validation
+    validator: Option<Box<dyn Fn(&str) -> bool + Send>>,
+    formatter: Option<Box<dyn Fn(&str) -> Option<(String, Box<dyn Any>)> + Send>>,
+
     // Events
     on_change: Option<Box<dyn Fn(&str) -> Event + Send>>,
     on_submit: Option<Box<dyn Fn(&str) -> Event + Send>>,
+    on_value: Option<Box<dyn Fn(Box<dyn Any>) -> Option<Event> + Send>>,
 }
 
 impl Textbox {
@@ -59,8 +66,12 @@ impl Textbox {
             hitx: -1.0,
             dragx: -1.0,
 
+            validator: None,
+            formatter: None,
+
             on_change: None,
             on_submit: None,
+            on_value: None,
         }
     }
 
@@ -79,6 +90,185 @@ impl Textbox {
         self
     }
 
+    /// Sets a callback which is called when the text is submitted with Enter or by clicking away.
+    /// The callback receives the formatted value without units.
+    pub fn on_submit<F>(mut self, on_submit: F) -> Self
+    where
+        F: 'static + Fn(&str) -> Event + Send,
+    {
+        self.on_submit = Some(Box::new(on_submit));
+
+        self
+    }
+
+    /// Sets a callback which is called on submit with the typed value parsed by `with_format`.
+    /// The type `T` must match the type returned by the parser.
+    pub fn on_value<T, F>(mut self, on_value: F) -> Self
+    where
+        T: 'static,
+        F: 'static + Fn(T) -> Event + Send,
+    {
+        self.on_value = Some(Box::new(move |val| {
+            val.downcast::<T>().ok().map(|val| (on_value)(*val))
+        }));
+
+        self
+    }
+
+    /// Sets a validator which is run on every edit.
+    /// The `:invalid` pseudo-class is set on the textbox while the validator returns false.
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: 'static + Fn(&str) -> bool + Send,
+    {
+        self.validator = Some(Box::new(validator));
+
+        self
+    }
+
+    /// Sets a parser and formatter for the value of the textbox.
+    ///
+    /// On submit the text (without units) is parsed into a value and then formatted back for display.
+    /// Text which fails to parse is rejected and the previous value is restored. The parser can also
+    /// clamp the value it returns. If no validator is set, text which fails to parse is marked `:invalid`
+    /// while editing. The parsed value is passed to the `on_value` callback.
+    ///
+    /// # Examples
+    /// ```ignore
+    /// Textbox::new("-6.0")
+    ///     .with_format(
+    ///         |text| text.parse::<f32>().ok().map(|val| val.max(-60.0).min(12.0)),
+    ///         |val| format!("{:.1}", val),
+    ///     )
+    ///     .with_units(" dB")
+    ///     .on_value(|val: f32| Event::new(GainEvent::SetGain(val)))
+    /// ```
+    pub fn with_format<T, P, F>(mut self, parse: P, format: F) -> Self
+    where
+        P: 'static + Fn(&str) -> Option<T> + Send,
+        F: 'static + Fn(&T) -> String + Send,
+    {
+        self.formatter = Some(Box::new(move |text| {
+            parse(text).map(|val| (format(&val), Box::new(val) as Box<dyn Any>))
+        }));
+
+        self
+    }
+
+    /// Restricts the textbox to numeric input which is clamped between `min` and `max` on submit.
+    pub fn with_range(self, min: f32, max: f32) -> Self {
+        self.with_format(
+            move |text| text.parse::<f32>().ok().map(|val| val.max(min).min(max)),
+            |val| val.to_string(),
+        )
+    }
+
+    // Removes the units from the end of the text, with or without whitespace before them
+    fn strip_units<'a>(&self, text: &'a str) -> &'a str {
+        let text = text.trim();
+        let units = self.units.trim();
+
+        if units.is_empty() || text.len() < units.len() {
+            return text;
+        }
+
+        let split = text.len() - units.len();
+
+        if text.is_char_boundary(split) && text[split..].eq_ignore_ascii_case(units) {
+            text[..split].trim_end()
+        } else {
+            text
+        }
+    }
+
+    fn is_valid(&self, text: &str) -> bool {
+        let value = self.strip_units(text);
+
+        if let Some(validator) = &self.validator {
+            return (validator)(value);
+        }
+
+        if let Some(formatter) = &self.formatter {
+            return (formatter)(value).is_some();
+        }
+
+        true
+    }
+
+    // Sets the invalid pseudo-class if the current text is rejected by the validator
+    fn validate(&self, state: &mut State, entity: Entity) {
+        if let Some(txt) = state.style.text.get(entity) {
+            let invalid = !self.is_valid(&txt.text);
+
+            if entity.is_invalid(state) != invalid {
+                entity.set_invalid(state, invalid);
+            }
+        }
+    }
+
+    // Formats a value for display, returning None if the value is rejected
+    fn format(&self, value: &str) -> Option<String> {
+        self.parse(value).map(|(text, _)| text)
+    }
+
+    // Parses a value into its display text and the typed value from the formatter, if there is one
+    fn parse(&self, value: &str) -> Option<(String, Option<Box<dyn Any>>)> {
+        let value = self.strip_units(value);
+
+        if let Some(validator) = &self.validator {
+            if !(validator)(value) {
+                return None;
+            }
+        }
+
+        if let Some(formatter) = &self.formatter {
+            (formatter)(value).map(|(text, val)| (text, Some(val)))
+        } else {
+            Some((value.to_string(), None))
+        }
+    }
+
+    // Validates and formats the edited text, restoring the previous text if it is rejected
+    fn submit(&mut self, state: &mut State, entity: Entity) {
+        let text = state.style.text.get(entity).cloned().unwrap_or_default().text;
+
+        if let Some((value, typed)) = self.parse(&text) {
+            entity.set_text(state, &(value.to_owned() + &self.units));
+
+            state.insert_event(Event::new(TextboxEvent::ValueChanged(value.clone())).target(entity));
+
+            if let Some(on_submit) = &self.on_submit {
+                let mut event = (on_submit)(&value);
+
+                if !event.target {
+                    event.target = entity;
+                }
+
+                event.origin = entity;
+
+                state.insert_event(event);
+            }
+
+            if let (Some(on_value), Some(typed)) = (&self.on_value, typed) {
+                if let Some(mut event) = (on_value)(typed) {
+                    if !event.target {
+                        event.target = entity;
+                    }
+
+                    event.origin = entity;
+
+                    state.insert_event(event);
+                }
+            }
+        } else {
+            entity.set_text(state, &self.buffer);
+        }
+
+        if entity.is_invalid(state) {
+            entity.set_invalid(state, false);
+        }
+    }
+
     // pub fn set_enabled(&self, state: &mut WidgetState, val: bool) {
     //     if val {
     //         self.id
@@ -93,7 +283,11 @@ impl Textbox {
 impl BuildHandler for Textbox {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
-        entity.set_text(state, &(self.text.to_owned() + &self.units));
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::TextInput);
+
+        let text = self.format(&self.text).unwrap_or(self.text.to_owned());
+        entity.set_text(state, &(text + &self.units));
 
         self.entity = entity;
 
@@ -109,7 +303,8 @@ impl EventHandler for Textbox {
             match textbox_event {
                 TextboxEvent::SetValue(val) => {
                     if event.target == entity {
-                        entity.set_text(state, &(val.to_owned() + &self.units));
+                        let val = self.format(val).unwrap_or(val.to_owned());
+                        entity.set_text(state, &(val + &self.units));
 
                         // state.insert_event(
                         //     Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
@@ -119,11 +314,18 @@ impl EventHandler for Textbox {
                     }
                 }
 
-                // TextboxEvent::ResetValue => {
-                //     if let Some(text_data) = state.style.text.get_mut(entity) {
-                //         text_data.text = self.buffer.clone();
-                //     }
-                // }
+                TextboxEvent::ResetValue => {
+                    if event.target == entity {
+                        entity.set_text(state, &self.buffer);
+
+                        if entity.is_invalid(state) {
+                            entity.set_invalid(state, false);
+                        }
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                    }
+                }
+
                 _ => {}
             }
         }
@@ -150,12 +352,11 @@ impl EventHandler for Textbox {
                             self.cursor_pos = text_data.text.len() as u32;
                             self.select_pos = 0;
                             self.buffer = text_data.text.clone();
-                            state.focused = entity;
                             //state.captured = entity;
                             state.capture(entity);
                             //self.edit = true;
                             entity.set_active(state, true);
-                            entity.set_focus(state, true);
+                            state.set_focus(entity);
                         }
                         if self.edit == true {
                             self.hitx = state.mouse.cursorx;
@@ -169,14 +370,12 @@ impl EventHandler for Textbox {
 
                         state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
                     } else {
+                        if self.edit {
+                            self.submit(state, entity);
+                        }
+
                         self.edit = false;
                         entity.set_active(state, false);
-                        entity.set_focus(state, false);
-
-                        state.insert_event(
-                            Event::new(TextboxEvent::ValueChanged(text_data.text.clone()))
-                                .target(entity),
-                        );
 
                         // state.insert_event(
                         //     Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
@@ -186,12 +385,14 @@ impl EventHandler for Textbox {
 
                         if state.captured == entity {
                             state.insert_event(
-                                Event::new(WindowEvent::MouseDown(*button)).target(state.hovered),
+                                Event::new(WindowEvent::MouseDown(*button))
+                                    .target(state.hovered)
+                                    .origin(entity),
                             );
                         }
 
                         if state.focused == entity {
-                            state.focused = Entity::root();
+                            state.set_focus(Entity::root());
                         }
 
                         //state.captured = Entity::null();
@@ -203,6 +404,32 @@ impl EventHandler for Textbox {
                     self.hitx = -1.0;
                 }
 
+                // Receiving focus from the keyboard starts editing
+                WindowEvent::FocusIn => {
+                    if event.target == entity && !self.edit && !entity.is_disabled(state) {
+                        self.cursor_pos = text_data.text.len() as u32;
+                        self.select_pos = 0;
+                        self.buffer = text_data.text.clone();
+                        self.edit = true;
+                        state.capture(entity);
+                        entity.set_active(state, true);
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                    }
+                }
+
+                WindowEvent::FocusOut => {
+                    if event.target == entity && self.edit {
+                        self.submit(state, entity);
+
+                        self.edit = false;
+                        entity.set_active(state, false);
+                        state.release(entity);
+
+                        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                    }
+                }
+
                 WindowEvent::KeyDown(_, key) => {
                     //println!("Code: {:?} Key: {:?}", code, key);
                     if *key == Some(Key::ArrowLeft) {
@@ -269,6 +496,8 @@ impl EventHandler for Textbox {
                             //     Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
                             // );
 
+                            self.validate(state, entity);
+
                             if let Some(txt) = state.style.text.get(entity) {
                                 if let Some(on_change) = &self.on_change {
                                     let mut event = (on_change)(&txt.text);
@@ -290,16 +519,11 @@ impl EventHandler for Textbox {
                     }
                     if *key == Some(Key::Enter) {
                         if self.edit {
-                            //text_data.buffer = text_data.text.clone();
-                            state.insert_event(
-                                Event::new(TextboxEvent::ValueChanged(text_data.text.clone()))
-                                    .target(entity),
-                            );
+                            self.submit(state, entity);
 
                             self.edit = false;
                             entity.set_active(state, false);
-                            state.focused = Entity::root();
-                            state.captured = Entity::null();
+                            state.release(entity);
 
                             // state.insert_event(
                             //     Event::new(WindowEvent::Restyle).target(Entity::new(0, 0)),
@@ -313,6 +537,12 @@ impl EventHandler for Textbox {
                     if *key == Some(Key::Escape) {
                         if self.edit {
                             self.text = self.buffer.clone();
+                            entity.set_text(state, &self.buffer);
+
+                            if entity.is_invalid(state) {
+                                entity.set_invalid(state, false);
+                            }
+
                             self.edit = false;
                             entity.set_active(state, false);
 
@@ -328,7 +558,7 @@ impl EventHandler for Textbox {
                 }
 
                 WindowEvent::CharInput(input) => {
-                    if *input as u8 != 8 && *input as u8 != 13 {
+                    if *input as u8 != 8 && *input as u8 != 9 && *input as u8 != 13 {
                         if self.edit {
                             let start = std::cmp::min(self.select_pos, self.cursor_pos) as usize;
                             let end = std::cmp::max(self.select_pos, self.cursor_pos) as usize;
@@ -350,6 +580,8 @@ impl EventHandler for Textbox {
                                 self.select_pos = (start + 1) as u32;
                             }
 
+                            self.validate(state, entity);
+
                             if let Some(txt) = state.style.text.get(entity) {
                                 if let Some(on_change) = &self.on_change {
                                     let mut event = (on_change)(&txt.text);
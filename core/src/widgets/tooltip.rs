@@ -1,5 +1,11 @@
 use crate::widgets::*;
+use crate::style::*;
 
+/// The popup shown by the tooltip manager when the cursor rests on a widget with a tooltip
+///
+/// Tooltips are created and removed by `state.tooltips`, so this widget usually isn't built
+/// directly. A tooltip with text contains a label, rich tooltips are filled by the content builder
+/// passed to `set_tooltip_content`. Tooltips are styled through the `tooltip` element.
 pub struct Tooltip {
     text: String,
 }
@@ -15,9 +21,21 @@ impl Tooltip {
 impl BuildHandler for Tooltip {
     type Ret = Entity;
     fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
-        entity
-            .set_text(state, &self.text)
-            .set_element(state, "tooltip")
+        entity.set_role(state, Role::Tooltip);
+
+        // The tooltip is opened on the popup layer and shouldn't take hover from the widget below it
+        entity.set_display(state, Display::None);
+        state.data.set_hoverability(entity, false);
+
+        if !self.text.is_empty() {
+            Label::new(&self.text).build(state, entity, |builder| {
+                builder.set_hoverability(false)
+            });
+
+            entity.set_accessible_name(state, &self.text);
+        }
+
+        entity.set_element(state, "tooltip")
     }
 }
 
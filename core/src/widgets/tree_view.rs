@@ -0,0 +1,894 @@
+use crate::widgets::*;
+use crate::state::style::*;
+use crate::{
+    HierarchyTree, IntoBranchIterator, IntoChildIterator, IntoParentIterator, Key, MouseButton,
+    PointerEvent, Propagation,
+};
+
+const ICON_DOWN_OPEN: &str = "\u{e75c}";
+const ICON_RIGHT_OPEN: &str = "\u{e75e}";
+
+// Width of one level of indentation, which is also the width of the expand arrow
+const INDENT: f32 = 16.0;
+
+// Fraction of the row height at its top and bottom where dropping an item places it before or after the row
+const DROP_EDGE: f32 = 0.25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeEvent {
+    // Expands or collapses a tree item, sent to the item
+    SetExpanded(bool),
+    // Emitted by a tree item when it is expanded or collapsed
+    Expanded(Entity, bool),
+    // Emitted by a tree item with lazy children the first time it is expanded.
+    // The entity is the container the children should be built into.
+    LoadChildren(Entity),
+    // Updates the expand arrow of a tree item after children are added or removed, sent to the item
+    Refresh,
+    // Sets the selected items of the tree view
+    SetSelection(Vec<Entity>),
+    // Emitted by the tree view when the selection is changed by the user
+    SelectionChanged(Vec<Entity>),
+    // Emitted by the tree view when an item is dropped onto a new position, with the item,
+    // its new parent item (or the tree view for top level items) and its index among its siblings
+    Moved(Entity, Entity, usize),
+}
+
+// Where a dragged item will be placed when it is dropped
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DropPosition {
+    Before(Entity),
+    After(Entity),
+    Into(Entity),
+}
+
+fn is_tree_item(state: &State, entity: Entity) -> bool {
+    state.accessibility.role.get(entity) == Some(&Role::TreeItem)
+}
+
+// The row of a tree item, containing the indentation guides, the arrow and the label
+fn item_row(state: &State, item: Entity) -> Entity {
+    state.hierarchy.get_first_child(item).unwrap_or(Entity::null())
+}
+
+// The container which holds the child items of a tree item
+fn item_children(state: &State, item: Entity) -> Entity {
+    state.hierarchy.get_last_child(item).unwrap_or(Entity::null())
+}
+
+// Returns the tree items which are children of a container
+fn child_items(state: &State, container: Entity) -> Vec<Entity> {
+    container
+        .child_iter(&state.hierarchy)
+        .filter(|child| is_tree_item(state, *child))
+        .collect()
+}
+
+fn parent_item(state: &State, item: Entity) -> Option<Entity> {
+    state
+        .hierarchy
+        .get_parent(item)
+        .and_then(|container| state.hierarchy.get_parent(container))
+        .filter(|parent| is_tree_item(state, *parent))
+}
+
+fn is_expanded(state: &State, item: Entity) -> bool {
+    state.accessibility.expanded.get(item) == Some(&Some(true))
+}
+
+fn is_expandable(state: &State, item: Entity) -> bool {
+    match state.accessibility.expanded.get(item) {
+        Some(Some(_)) => true,
+        _ => false,
+    }
+}
+
+// Sets the number of indentation guides in the rows of an item and its descendants
+fn set_depth(state: &mut State, item: Entity, depth: usize) {
+    let row = item_row(state, item);
+
+    // The arrow and label are the last two children of the row, the guides come before them
+    let guides = row.child_iter(&state.hierarchy).collect::<Vec<_>>();
+    let guides = &guides[..guides.len().saturating_sub(2)];
+
+    if guides.len() > depth {
+        for guide in guides[depth..].iter() {
+            state.remove(*guide);
+        }
+    } else {
+        for _ in guides.len()..depth {
+            let guide = Element::new().build(state, row, |builder| {
+                builder
+                    .set_width(Length::Pixels(INDENT))
+                    .set_hoverability(false)
+                    .class("guide")
+            });
+
+            if let Some(first) = state.hierarchy.get_first_child(row) {
+                state.hierarchy.set_prev_sibling(first, guide).ok();
+            }
+        }
+    }
+
+    for child in child_items(state, item_children(state, item)) {
+        set_depth(state, child, depth + 1);
+    }
+}
+
+/// An item of a `TreeView`
+///
+/// Building a tree item returns the container for its children, so child items are built
+/// directly into the returned entity. Items with lazy children show an expand arrow before they
+/// have any children and emit `TreeEvent::LoadChildren` the first time they are expanded.
+pub struct TreeItem {
+    label: String,
+    expanded: bool,
+    // The children have not been loaded yet
+    lazy: bool,
+    // The children have been requested but the item has not been refreshed since
+    loading: bool,
+
+    arrow: Entity,
+    children: Entity,
+}
+
+impl TreeItem {
+    pub fn new(label: &str) -> Self {
+        TreeItem {
+            label: label.to_string(),
+            expanded: false,
+            lazy: false,
+            loading: false,
+
+            arrow: Entity::null(),
+            children: Entity::null(),
+        }
+    }
+
+    pub fn with_lazy_children(mut self) -> Self {
+        self.lazy = true;
+
+        self
+    }
+
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+
+        self
+    }
+
+    fn set_expanded(&mut self, state: &mut State, entity: Entity, expanded: bool) {
+        if expanded == self.expanded {
+            return;
+        }
+
+        self.expanded = expanded;
+
+        if expanded && self.lazy {
+            self.lazy = false;
+            self.loading = true;
+
+            state.insert_event(
+                Event::new(TreeEvent::LoadChildren(self.children))
+                    .target(entity)
+                    .origin(entity)
+                    .propagate(Propagation::Up),
+            );
+
+            // Refreshes the item once the load has been handled, so the expand arrow is hidden if
+            // no children were added. Children built later refresh the item again.
+            state.insert_event(
+                Event::new(TreeEvent::Refresh)
+                    .target(entity)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        self.update(state, entity);
+
+        state.insert_event(
+            Event::new(TreeEvent::Expanded(entity, expanded))
+                .target(entity)
+                .origin(entity)
+                .propagate(Propagation::Up),
+        );
+    }
+
+    // Shows or hides the children and the expand arrow
+    fn update(&mut self, state: &mut State, entity: Entity) {
+        let expandable =
+            self.lazy || self.loading || state.hierarchy.has_children(self.children);
+
+        self.arrow
+            .set_text(state, if self.expanded { ICON_DOWN_OPEN } else { ICON_RIGHT_OPEN })
+            .set_visibility(
+                state,
+                if expandable {
+                    Visibility::Visible
+                } else {
+                    Visibility::Invisible
+                },
+            );
+
+        self.children.set_display(
+            state,
+            if self.expanded {
+                Display::Flexbox
+            } else {
+                Display::None
+            },
+        );
+
+        state
+            .accessibility
+            .expanded
+            .insert(entity, if expandable { Some(self.expanded) } else { None });
+    }
+}
+
+impl BuildHandler for TreeItem {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Column);
+
+        let row = Element::new().build(state, entity, |builder| {
+            builder.set_flex_direction(FlexDirection::Row).class("row")
+        });
+
+        self.arrow = Element::new().build(state, row, |builder| {
+            builder
+                .set_font("icons")
+                .set_text_justify(Justify::Center)
+                .set_text_align(Align::Center)
+                .set_width(Length::Pixels(INDENT))
+                .class("arrow")
+        });
+
+        Element::new().build(state, row, |builder| {
+            builder
+                .set_text(&self.label)
+                .set_flex_grow(1.0)
+                .set_hoverability(false)
+                .class("label")
+        });
+
+        self.children = Element::new().build(state, entity, |builder| builder.class("children"));
+
+        // Items are only focused with the arrow keys or by clicking
+        entity.set_tab_index(state, -1);
+        entity.set_role(state, Role::TreeItem);
+        entity.set_accessible_name(state, &self.label);
+
+        let depth = entity
+            .parent_iter(&state.hierarchy)
+            .skip(1)
+            .filter(|ancestor| is_tree_item(state, *ancestor))
+            .count();
+
+        set_depth(state, entity, depth);
+
+        self.update(state, entity);
+
+        // The parent item may need to show its expand arrow now that it has a child
+        if let Some(parent) = parent_item(state, entity) {
+            state.insert_event(
+                Event::new(TreeEvent::Refresh)
+                    .target(parent)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        entity.set_element(state, "tree_item");
+
+        self.children
+    }
+}
+
+impl EventHandler for TreeItem {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(tree_event) = event.message.downcast::<TreeEvent>() {
+            match tree_event {
+                TreeEvent::SetExpanded(expanded) => {
+                    if event.target == entity {
+                        self.set_expanded(state, entity, *expanded);
+                    }
+                }
+
+                TreeEvent::Refresh => {
+                    if event.target == entity {
+                        self.loading = false;
+                        self.update(state, entity);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                PointerEvent::Click { pointer, count } => {
+                    if pointer.button != MouseButton::Left {
+                        return;
+                    }
+
+                    // Clicking the arrow or double clicking the row toggles the item
+                    let row = item_row(state, entity);
+                    if event.target == self.arrow || (*count == 2 && event.target == row) {
+                        if is_expandable(state, entity) {
+                            self.set_expanded(state, entity, !self.expanded);
+                        }
+
+                        if event.target == self.arrow {
+                            event.consume();
+                        }
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A hierarchical list of `TreeItem`s which can be expanded, selected and rearranged by dragging
+///
+/// Top level items are built into the tree view. The arrow keys move between visible items,
+/// Right expands an item or moves to its first child and Left collapses an item or moves to its
+/// parent. Dragging an item shows an insertion indicator and dropping it moves the item before,
+/// after or into the item under the pointer.
+pub struct TreeView {
+    mode: SelectionMode,
+    selected: Vec<Entity>,
+    anchor: Option<Entity>,
+
+    drag: Option<Entity>,
+    drop: Option<DropPosition>,
+    indicator: Entity,
+
+    on_change: Option<Box<dyn Fn(Vec<Entity>) -> Event + Send>>,
+}
+
+impl TreeView {
+    pub fn new() -> Self {
+        TreeView {
+            mode: SelectionMode::Single,
+            selected: Vec::new(),
+            anchor: None,
+
+            drag: None,
+            drop: None,
+            indicator: Entity::null(),
+
+            on_change: None,
+        }
+    }
+
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+
+        self
+    }
+
+    pub fn on_change<F>(mut self, message: F) -> Self
+    where
+        F: 'static + Fn(Vec<Entity>) -> Event + Send,
+    {
+        self.on_change = Some(Box::new(message));
+
+        self
+    }
+
+    // Returns the items which are not inside a collapsed item, in the order they are shown
+    fn visible_items(&self, state: &State, entity: Entity) -> Vec<Entity> {
+        fn add_items(state: &State, container: Entity, items: &mut Vec<Entity>) {
+            for item in child_items(state, container) {
+                items.push(item);
+
+                if is_expanded(state, item) {
+                    add_items(state, item_children(state, item), items);
+                }
+            }
+        }
+
+        let mut items = Vec::new();
+        add_items(state, entity, &mut items);
+        items
+    }
+
+    // Returns the item containing the target entity
+    fn item_of(&self, state: &State, entity: Entity, target: Entity) -> Option<Entity> {
+        if !target.is_descendant_of(&state.hierarchy, entity) {
+            return None;
+        }
+
+        target
+            .parent_iter(&state.hierarchy)
+            .take_while(|ancestor| *ancestor != entity)
+            .find(|ancestor| is_tree_item(state, *ancestor))
+    }
+
+    // Returns the item which contains keyboard focus
+    fn cursor(&self, state: &State, entity: Entity) -> Option<Entity> {
+        self.item_of(state, entity, state.focused)
+    }
+
+    fn set_selection(&mut self, state: &mut State, entity: Entity, mut selected: Vec<Entity>) -> bool {
+        selected.dedup();
+
+        if self.mode == SelectionMode::Single {
+            selected.truncate(1);
+        }
+
+        if selected == self.selected {
+            return false;
+        }
+
+        self.selected = selected;
+        self.update_items(state, entity);
+
+        true
+    }
+
+    // Selects an item in response to a click or key press with the current modifiers.
+    // Toggle is false for keyboard navigation, which never deselects an item.
+    fn select_item(&mut self, state: &mut State, entity: Entity, item: Entity, toggle: bool) {
+        let shift = state.modifiers.shift;
+        let ctrl = state.modifiers.ctrl || state.modifiers.logo;
+
+        let mut selected = self.selected.clone();
+
+        match self.mode {
+            SelectionMode::Single => {
+                selected = vec![item];
+                self.anchor = Some(item);
+            }
+
+            SelectionMode::Range if shift => {
+                let items = self.visible_items(state, entity);
+                let anchor = self.anchor.unwrap_or(item);
+
+                if !ctrl {
+                    selected.clear();
+                }
+
+                if let (Some(start), Some(end)) = (
+                    items.iter().position(|i| *i == anchor),
+                    items.iter().position(|i| *i == item),
+                ) {
+                    for i in items[start.min(end)..=start.max(end)].iter() {
+                        if !selected.contains(i) {
+                            selected.push(*i);
+                        }
+                    }
+                }
+            }
+
+            SelectionMode::Range if !ctrl => {
+                selected = vec![item];
+                self.anchor = Some(item);
+            }
+
+            _ => {
+                self.anchor = Some(item);
+
+                if toggle {
+                    if let Some(position) = selected.iter().position(|i| *i == item) {
+                        selected.remove(position);
+                    } else {
+                        selected.push(item);
+                    }
+                }
+            }
+        }
+
+        if self.set_selection(state, entity, selected) {
+            self.selection_changed(state, entity);
+        }
+    }
+
+    fn selection_changed(&mut self, state: &mut State, entity: Entity) {
+        let selected = self.selected.clone();
+
+        if let Some(on_change) = &self.on_change {
+            let mut event = (on_change)(selected.clone());
+            event.origin = entity;
+
+            if event.target == Entity::null() {
+                event.target = entity;
+            }
+
+            state.insert_event(event);
+        }
+
+        state.insert_event(
+            Event::new(TreeEvent::SelectionChanged(selected))
+                .target(entity)
+                .origin(entity),
+        );
+    }
+
+    // Sets the checked pseudo-class of the selected items
+    fn update_items(&mut self, state: &mut State, entity: Entity) {
+        let items = entity
+            .branch_iter(&state.hierarchy)
+            .filter(|item| is_tree_item(state, *item))
+            .collect::<Vec<_>>();
+
+        // Forget items which have been removed from the tree
+        self.selected.retain(|item| items.contains(item));
+
+        for item in items {
+            item.set_checked(state, self.selected.contains(&item));
+        }
+    }
+
+    // Moves keyboard focus to an item and updates the selection
+    fn move_cursor(&mut self, state: &mut State, entity: Entity, item: Entity) {
+        state.set_focus(item);
+
+        // In multi selection mode the arrow keys only move focus, space toggles the selection
+        if self.mode != SelectionMode::Multi {
+            self.select_item(state, entity, item, false);
+        }
+    }
+
+    fn expand(&self, state: &mut State, item: Entity, expanded: bool) {
+        state.insert_event(
+            Event::new(TreeEvent::SetExpanded(expanded))
+                .target(item)
+                .propagate(Propagation::Direct),
+        );
+    }
+
+    // Returns where the dragged item would be dropped with the pointer at a vertical position
+    fn drop_position(&self, state: &State, entity: Entity, dragged: Entity, y: f32) -> Option<DropPosition> {
+        let items = self.visible_items(state, entity);
+
+        let target = items.iter().cloned().find(|item| {
+            let row = item_row(state, *item);
+            let top = state.data.get_posy(row);
+            y >= top && y < top + state.data.get_height(row)
+        });
+
+        let position = match target {
+            Some(item) => {
+                let row = item_row(state, item);
+                let height = state.data.get_height(row);
+                let offset = (y - state.data.get_posy(row)) / height.max(1.0);
+
+                if offset < DROP_EDGE {
+                    DropPosition::Before(item)
+                } else if offset > 1.0 - DROP_EDGE {
+                    // The bottom of an expanded item is the top of its first child
+                    match child_items(state, item_children(state, item)).first() {
+                        Some(first) if is_expanded(state, item) => DropPosition::Before(*first),
+                        _ => DropPosition::After(item),
+                    }
+                } else {
+                    DropPosition::Into(item)
+                }
+            }
+
+            // Below the last item drops at the end of the top level items
+            None => {
+                let last = child_items(state, entity).last().cloned()?;
+
+                let row = item_row(state, items.last().cloned().unwrap_or(last));
+                if y < state.data.get_posy(row) {
+                    return None;
+                }
+
+                DropPosition::After(last)
+            }
+        };
+
+        let target = match position {
+            DropPosition::Before(item) | DropPosition::After(item) | DropPosition::Into(item) => item,
+        };
+
+        // An item can't be dropped into itself or one of its descendants
+        if target.is_descendant_of(&state.hierarchy, dragged) {
+            return None;
+        }
+
+        Some(position)
+    }
+
+    // Places the insertion indicator at the drop position
+    fn show_indicator(&mut self, state: &mut State, entity: Entity) {
+        let (item, into) = match self.drop {
+            Some(DropPosition::Before(item)) | Some(DropPosition::After(item)) => (item, false),
+            Some(DropPosition::Into(item)) => (item, true),
+            None => {
+                state.style.display.insert(self.indicator, Display::None);
+                state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+                return;
+            }
+        };
+
+        let row = item_row(state, item);
+
+        // The indicator starts at the arrow so that it shows the level the item will be dropped at
+        let start = state
+            .hierarchy
+            .get_last_child(row)
+            .and_then(|label| state.hierarchy.get_prev_sibling(label))
+            .map(|arrow| state.data.get_posx(arrow))
+            .unwrap_or(state.data.get_posx(row));
+
+        let left = start - state.data.get_posx(entity);
+        let width = state.data.get_posx(row) + state.data.get_width(row) - start;
+
+        let row_top = state.data.get_posy(row) - state.data.get_posy(entity);
+        let row_height = state.data.get_height(row);
+
+        let (top, height) = match self.drop {
+            Some(DropPosition::Before(_)) => (row_top - 1.0, 2.0),
+            Some(DropPosition::After(_)) => (row_top + row_height - 1.0, 2.0),
+            _ => (row_top, row_height),
+        };
+
+        state.style.display.insert(self.indicator, Display::Flexbox);
+        state.style.left.insert(self.indicator, Length::Pixels(left));
+        state.style.top.insert(self.indicator, Length::Pixels(top));
+        state.style.width.insert(self.indicator, Length::Pixels(width));
+        state.style.height.insert(self.indicator, Length::Pixels(height));
+
+        // Dropping into an item is styled as an outline around the row rather than a line
+        self.indicator.set_checked(state, into);
+
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+
+    // Moves an item to the drop position
+    fn drop_item(&mut self, state: &mut State, entity: Entity, item: Entity, position: DropPosition) {
+        let old_parent = parent_item(state, item);
+
+        match position {
+            DropPosition::Before(target) | DropPosition::After(target) => {
+                if target == item {
+                    return;
+                }
+
+                let container = match state.hierarchy.get_parent(target) {
+                    Some(container) => container,
+                    None => return,
+                };
+
+                state.hierarchy.set_parent(item, container);
+
+                if let DropPosition::Before(target) = position {
+                    state.hierarchy.set_prev_sibling(target, item).ok();
+                } else {
+                    state.hierarchy.set_next_sibling(target, item).ok();
+                }
+            }
+
+            DropPosition::Into(target) => {
+                state.hierarchy.set_parent(item, item_children(state, target));
+                self.expand(state, target, true);
+            }
+        }
+
+        let new_parent = parent_item(state, item);
+
+        let depth = item
+            .parent_iter(&state.hierarchy)
+            .skip(1)
+            .filter(|ancestor| is_tree_item(state, *ancestor))
+            .count();
+
+        set_depth(state, item, depth);
+
+        // The arrows of the old and new parents may need to be shown or hidden
+        for parent in old_parent.iter().chain(new_parent.iter()) {
+            state.insert_event(
+                Event::new(TreeEvent::Refresh)
+                    .target(*parent)
+                    .propagate(Propagation::Direct),
+            );
+        }
+
+        let container = state.hierarchy.get_parent(item).unwrap_or(entity);
+        let index = child_items(state, container)
+            .iter()
+            .position(|sibling| *sibling == item)
+            .unwrap_or_default();
+
+        state.insert_event(
+            Event::new(TreeEvent::Moved(item, new_parent.unwrap_or(entity), index))
+                .target(entity)
+                .origin(entity),
+        );
+
+        state.insert_event(Event::new(WindowEvent::Restyle).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Relayout).target(Entity::root()));
+        state.insert_event(Event::new(WindowEvent::Redraw).target(Entity::root()));
+    }
+}
+
+impl BuildHandler for TreeView {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_flex_direction(state, FlexDirection::Column);
+
+        self.indicator = Element::new().build(state, entity, |builder| {
+            builder
+                .set_position(Position::Absolute)
+                .set_display(Display::None)
+                .set_hoverability(false)
+                .set_z_order(1)
+                .class("drop_indicator")
+        });
+
+        entity.set_tab_index(state, 0);
+        entity.set_role(state, Role::Tree);
+
+        entity.set_element(state, "tree_view")
+    }
+}
+
+impl EventHandler for TreeView {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        let target = event.target;
+
+        if let Some(tree_event) = event.message.downcast::<TreeEvent>() {
+            match tree_event {
+                TreeEvent::SetSelection(selected) => {
+                    if target == entity {
+                        let selected = selected.clone();
+                        self.anchor = selected.first().cloned();
+                        self.set_selection(state, entity, selected);
+                    }
+                }
+
+                // Move focus out of a collapsed item
+                TreeEvent::Expanded(item, false) => {
+                    if state.focused.is_descendant_of(&state.hierarchy, item_children(state, *item)) {
+                        state.set_focus(*item);
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(pointer_event) = event.message.downcast::<PointerEvent>() {
+            match pointer_event {
+                PointerEvent::Click { pointer, .. } => {
+                    if pointer.button == MouseButton::Left {
+                        if let Some(item) = self.item_of(state, entity, target) {
+                            if !state.focused.is_descendant_of(&state.hierarchy, item_row(state, item)) {
+                                state.set_focus(item);
+                            }
+
+                            self.select_item(state, entity, item, true);
+                        }
+                    }
+                }
+
+                PointerEvent::DragStart { pointer, .. } => {
+                    if pointer.button == MouseButton::Left {
+                        if let Some(item) = self.item_of(state, entity, target) {
+                            self.drag = Some(item);
+                            item.set_active(state, true);
+                            event.consume();
+                        }
+                    }
+                }
+
+                PointerEvent::DragMove { pointer, .. } => {
+                    if let Some(dragged) = self.drag {
+                        let drop = self.drop_position(state, entity, dragged, pointer.y);
+
+                        if drop != self.drop {
+                            self.drop = drop;
+                            self.show_indicator(state, entity);
+                        }
+
+                        event.consume();
+                    }
+                }
+
+                PointerEvent::DragEnd { .. } => {
+                    if let Some(dragged) = self.drag.take() {
+                        dragged.set_active(state, false);
+
+                        if let Some(drop) = self.drop.take() {
+                            self.drop_item(state, entity, dragged, drop);
+                        }
+
+                        self.show_indicator(state, entity);
+                        event.consume();
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        if let Some(window_event) = event.message.downcast::<WindowEvent>() {
+            match window_event {
+                WindowEvent::KeyDown(_, key) => {
+                    if state.focused != entity && !state.focused.is_descendant_of(&state.hierarchy, entity) {
+                        return;
+                    }
+
+                    let items = self.visible_items(state, entity);
+
+                    if items.is_empty() {
+                        return;
+                    }
+
+                    let cursor = self.cursor(state, entity);
+                    let position = cursor.and_then(|cursor| items.iter().position(|item| *item == cursor));
+
+                    match key {
+                        Some(Key::ArrowDown) => {
+                            let index = position.map(|p| (p + 1).min(items.len() - 1)).unwrap_or(0);
+                            self.move_cursor(state, entity, items[index]);
+                            event.consume();
+                        }
+
+                        Some(Key::ArrowUp) => {
+                            let index = position.map(|p| p.saturating_sub(1)).unwrap_or(items.len() - 1);
+                            self.move_cursor(state, entity, items[index]);
+                            event.consume();
+                        }
+
+                        Some(Key::Home) => {
+                            self.move_cursor(state, entity, items[0]);
+                            event.consume();
+                        }
+
+                        Some(Key::End) => {
+                            self.move_cursor(state, entity, items[items.len() - 1]);
+                            event.consume();
+                        }
+
+                        // Right expands a collapsed item or moves to the first child of an expanded item
+                        Some(Key::ArrowRight) => {
+                            if let Some(cursor) = cursor {
+                                if is_expanded(state, cursor) {
+                                    if let Some(first) = child_items(state, item_children(state, cursor)).first() {
+                                        self.move_cursor(state, entity, *first);
+                                    }
+                                } else if is_expandable(state, cursor) {
+                                    self.expand(state, cursor, true);
+                                }
+
+                                event.consume();
+                            }
+                        }
+
+                        // Left collapses an expanded item or moves to the parent of a collapsed item
+                        Some(Key::ArrowLeft) => {
+                            if let Some(cursor) = cursor {
+                                if is_expanded(state, cursor) {
+                                    self.expand(state, cursor, false);
+                                } else if let Some(parent) = parent_item(state, cursor) {
+                                    self.move_cursor(state, entity, parent);
+                                }
+
+                                event.consume();
+                            }
+                        }
+
+                        Some(Key::Character(c)) if c == " " => {
+                            if let Some(cursor) = cursor {
+                                self.select_item(state, entity, cursor, true);
+                                event.consume();
+                            }
+                        }
+
+                        _ => {}
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
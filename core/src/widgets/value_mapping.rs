@@ -0,0 +1,489 @@
+/// Maps between the plain value of a parameter and the normalized 0..1 value shown by a widget
+///
+/// Value widgets such as knobs and sliders work in normalized values, so a drag moves evenly through
+/// the range whatever the scaling, and use the mapping to convert to and from the plain value and to
+/// display and parse the value as text. Widgets which share a mapping agree on the scaling.
+///
+/// # Examples
+/// ```ignore
+/// ControlKnob::new(1000.0, 20.0, 20000.0)
+///     .with_mapping(LogMapping::new(20.0, 20000.0).with_unit("Hz"))
+///     .build(state, parent, |builder| builder);
+/// ```
+pub trait ValueMapping: Send + Sync {
+    /// The smallest plain value
+    fn min(&self) -> f32;
+
+    /// The largest plain value
+    fn max(&self) -> f32;
+
+    /// Maps a plain value to a normalized value, clamping the normalized value to 0..1
+    fn to_normalized(&self, plain: f32) -> f32;
+
+    /// Maps a normalized value to a plain value, clamping the normalized value to 0..1
+    fn to_plain(&self, normalized: f32) -> f32;
+
+    /// The unit shown after the value, e.g. "Hz"
+    fn unit(&self) -> &str {
+        ""
+    }
+
+    /// The distance between adjacent steps as a normalized value, or None if the mapping doesn't snap to steps
+    fn normalized_step(&self) -> Option<f32> {
+        None
+    }
+
+    /// Clamps a plain value to the range and snaps it to the nearest step
+    fn snap(&self, plain: f32) -> f32 {
+        self.to_plain(self.to_normalized(plain))
+    }
+
+    /// Converts a plain value to a display string
+    fn format(&self, plain: f32) -> String {
+        format_value(plain, self.unit())
+    }
+
+    /// Converts a display string to a plain value, returning None if the text is not a value
+    fn parse(&self, text: &str) -> Option<f32> {
+        parse_value(text, self.unit()).map(|value| self.snap(value))
+    }
+}
+
+/// Formats a value with a precision depending on its size, using a 'k' prefix from 1000
+///
+/// For example 2.5 is shown as "2.50", 440 as "440 Hz" and 12000 as "12.0 kHz".
+pub fn format_value(value: f32, unit: &str) -> String {
+    let abs = value.abs();
+
+    let (text, prefix) = if abs < 10.0 {
+        (format!("{:.2}", value), "")
+    } else if abs < 100.0 {
+        (format!("{:.1}", value), "")
+    } else if abs < 1000.0 {
+        (format!("{:.0}", value), "")
+    } else if abs < 10000.0 {
+        (format!("{:.2}", value / 1000.0), "k")
+    } else if abs < 100000.0 {
+        (format!("{:.1}", value / 1000.0), "k")
+    } else {
+        (format!("{}", value), "")
+    };
+
+    if unit.is_empty() {
+        format!("{}{}", text, prefix)
+    } else {
+        format!("{} {}{}", text, prefix, unit)
+    }
+}
+
+/// Parses a value formatted by `format_value`, with or without the unit and 'k' prefix
+pub fn parse_value(text: &str, unit: &str) -> Option<f32> {
+    let mut text = text.trim();
+
+    if !unit.is_empty() && text.len() >= unit.len() {
+        let split = text.len() - unit.len();
+        if text.is_char_boundary(split) && text[split..].eq_ignore_ascii_case(unit) {
+            text = text[..split].trim_end();
+        }
+    }
+
+    let (text, multiplier) = match text.strip_suffix('k').or_else(|| text.strip_suffix('K')) {
+        Some(stripped) => (stripped.trim_end(), 1000.0),
+        None => (text, 1.0),
+    };
+
+    text.parse::<f32>().ok().map(|value| value * multiplier)
+}
+
+fn clamp_normalized(normalized: f32) -> f32 {
+    normalized.min(1.0).max(0.0)
+}
+
+/// Maps linearly between min and max, optionally snapping to multiples of a step from min
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearMapping {
+    min: f32,
+    max: f32,
+    step: f32,
+    unit: String,
+}
+
+impl LinearMapping {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            step: 0.0,
+            unit: String::new(),
+        }
+    }
+
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self
+    }
+
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+}
+
+impl ValueMapping for LinearMapping {
+    fn min(&self) -> f32 {
+        self.min
+    }
+
+    fn max(&self) -> f32 {
+        self.max
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        if self.max == self.min {
+            return 0.0;
+        }
+
+        clamp_normalized((plain - self.min) / (self.max - self.min))
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        let value = self.min + clamp_normalized(normalized) * (self.max - self.min);
+
+        if self.step > 0.0 {
+            let stepped = self.min + ((value - self.min) / self.step).round() * self.step;
+            stepped.min(self.max.max(self.min)).max(self.min.min(self.max))
+        } else {
+            value
+        }
+    }
+
+    fn normalized_step(&self) -> Option<f32> {
+        if self.step > 0.0 && self.max != self.min {
+            Some((self.step / (self.max - self.min).abs()).min(1.0))
+        } else {
+            None
+        }
+    }
+
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+}
+
+/// Maps logarithmically between min and max, which must both be greater than zero
+///
+/// Suited to frequencies and times, where each doubling of the value should take the same distance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogMapping {
+    min: f32,
+    max: f32,
+    unit: String,
+}
+
+impl LogMapping {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            unit: String::new(),
+        }
+    }
+
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+}
+
+impl ValueMapping for LogMapping {
+    fn min(&self) -> f32 {
+        self.min
+    }
+
+    fn max(&self) -> f32 {
+        self.max
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        let (min, max) = (self.min.log10(), self.max.log10());
+
+        if max == min {
+            return 0.0;
+        }
+
+        clamp_normalized((plain.log10() - min) / (max - min))
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        let (min, max) = (self.min.log10(), self.max.log10());
+
+        10.0f32.powf(min + clamp_normalized(normalized) * (max - min))
+    }
+
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+}
+
+/// Maps between min and max with the normalized value raised to an exponent
+///
+/// An exponent greater than one gives more of the range to small values, less than one to large values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkewMapping {
+    min: f32,
+    max: f32,
+    exponent: f32,
+    unit: String,
+}
+
+impl SkewMapping {
+    pub fn new(min: f32, max: f32, exponent: f32) -> Self {
+        Self {
+            min,
+            max,
+            exponent,
+            unit: String::new(),
+        }
+    }
+
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+}
+
+impl ValueMapping for SkewMapping {
+    fn min(&self) -> f32 {
+        self.min
+    }
+
+    fn max(&self) -> f32 {
+        self.max
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        if self.max == self.min {
+            return 0.0;
+        }
+
+        let linear = (plain - self.min) / (self.max - self.min);
+
+        clamp_normalized(linear).powf(1.0 / self.exponent)
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        self.min + clamp_normalized(normalized).powf(self.exponent) * (self.max - self.min)
+    }
+
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+}
+
+/// Converts a level in decibels to a linear gain
+pub fn db_to_gain(db: f32) -> f32 {
+    10.0f32.powf(db / 20.0)
+}
+
+/// Converts a linear gain to a level in decibels, which is negative infinity for a gain of zero
+pub fn gain_to_db(gain: f32) -> f32 {
+    20.0 * gain.log10()
+}
+
+/// Maps a linear gain evenly in decibels between a minimum and maximum level
+///
+/// The plain value is the gain, so it can be applied to samples directly, while the value is shown and
+/// entered in dB. The bottom of the range is silence, shown as "-inf dB".
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecibelMapping {
+    min_db: f32,
+    max_db: f32,
+}
+
+impl DecibelMapping {
+    pub fn new(min_db: f32, max_db: f32) -> Self {
+        Self { min_db, max_db }
+    }
+}
+
+impl ValueMapping for DecibelMapping {
+    fn min(&self) -> f32 {
+        0.0
+    }
+
+    fn max(&self) -> f32 {
+        db_to_gain(self.max_db)
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        if plain <= 0.0 || self.max_db == self.min_db {
+            return 0.0;
+        }
+
+        clamp_normalized((gain_to_db(plain) - self.min_db) / (self.max_db - self.min_db))
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        let normalized = clamp_normalized(normalized);
+
+        if normalized == 0.0 {
+            return 0.0;
+        }
+
+        db_to_gain(self.min_db + normalized * (self.max_db - self.min_db))
+    }
+
+    fn unit(&self) -> &str {
+        "dB"
+    }
+
+    fn format(&self, plain: f32) -> String {
+        if plain <= 0.0 {
+            "-inf dB".to_string()
+        } else {
+            format!("{:.1} dB", gain_to_db(plain))
+        }
+    }
+
+    fn parse(&self, text: &str) -> Option<f32> {
+        let text = text.trim();
+        let text = match text.len().checked_sub(2) {
+            Some(split) if text.is_char_boundary(split) && text[split..].eq_ignore_ascii_case("dB") => {
+                text[..split].trim_end()
+            }
+            _ => text,
+        };
+
+        if text.eq_ignore_ascii_case("-inf") {
+            return Some(0.0);
+        }
+
+        text.parse::<f32>()
+            .ok()
+            .map(|db| self.snap(db_to_gain(db)))
+    }
+}
+
+/// Maps to a fixed number of named steps, such as the waveforms of an oscillator
+///
+/// The plain value is the index of the step, shown as the name of the step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SteppedMapping {
+    steps: Vec<String>,
+}
+
+impl SteppedMapping {
+    pub fn new(steps: &[&str]) -> Self {
+        Self {
+            steps: steps.iter().map(|step| step.to_string()).collect(),
+        }
+    }
+
+    fn last(&self) -> f32 {
+        self.steps.len().saturating_sub(1) as f32
+    }
+}
+
+impl ValueMapping for SteppedMapping {
+    fn min(&self) -> f32 {
+        0.0
+    }
+
+    fn max(&self) -> f32 {
+        self.last()
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        if self.last() == 0.0 {
+            return 0.0;
+        }
+
+        clamp_normalized(plain / self.last())
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        (clamp_normalized(normalized) * self.last()).round()
+    }
+
+    fn normalized_step(&self) -> Option<f32> {
+        if self.last() > 0.0 {
+            Some(1.0 / self.last())
+        } else {
+            None
+        }
+    }
+
+    fn format(&self, plain: f32) -> String {
+        let index = self.snap(plain) as usize;
+
+        self.steps.get(index).cloned().unwrap_or_default()
+    }
+
+    fn parse(&self, text: &str) -> Option<f32> {
+        let text = text.trim();
+
+        match self.steps.iter().position(|step| step.eq_ignore_ascii_case(text)) {
+            Some(index) => Some(index as f32),
+            None => text.parse::<f32>().ok().map(|index| self.snap(index)),
+        }
+    }
+}
+
+/// Maps linearly between -range and range with zero in the center, such as for pan or detune
+///
+/// Positive values are shown with a leading '+'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BipolarMapping {
+    range: f32,
+    unit: String,
+}
+
+impl BipolarMapping {
+    pub fn new(range: f32) -> Self {
+        Self {
+            range,
+            unit: String::new(),
+        }
+    }
+
+    pub fn with_unit(mut self, unit: &str) -> Self {
+        self.unit = unit.to_string();
+        self
+    }
+}
+
+impl ValueMapping for BipolarMapping {
+    fn min(&self) -> f32 {
+        -self.range
+    }
+
+    fn max(&self) -> f32 {
+        self.range
+    }
+
+    fn to_normalized(&self, plain: f32) -> f32 {
+        if self.range == 0.0 {
+            return 0.5;
+        }
+
+        clamp_normalized(0.5 + plain / (2.0 * self.range))
+    }
+
+    fn to_plain(&self, normalized: f32) -> f32 {
+        (clamp_normalized(normalized) * 2.0 - 1.0) * self.range
+    }
+
+    fn unit(&self) -> &str {
+        &self.unit
+    }
+
+    fn format(&self, plain: f32) -> String {
+        let text = format_value(plain, &self.unit);
+
+        if plain > 0.0 {
+            format!("+{}", text)
+        } else {
+            text
+        }
+    }
+}
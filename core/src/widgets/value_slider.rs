@@ -3,6 +3,9 @@ use crate::state::{Entity, State};
 use crate::events::{BuildHandler, Event, EventHandler, Propagation};
 
 use crate::widgets::{Button, ProgressBar, SliderEvent, Textbox, TextboxEvent};
+use crate::widgets::{LinearMapping, ValueMapping};
+
+use std::sync::Arc;
 
 use crate::state::style::*;
 
@@ -12,6 +15,8 @@ pub struct ValueSlider {
     pub value: Entity,
     pub label: Entity,
     label_text: String,
+
+    mapping: Arc<dyn ValueMapping>,
 }
 
 impl ValueSlider {
@@ -21,8 +26,16 @@ impl ValueSlider {
             value: Entity::null(),
             label: Entity::null(),
             label_text: label_txt.to_string(),
+
+            mapping: Arc::new(LinearMapping::new(0.0, 1.0)),
         }
     }
+
+    /// Sets how the bar maps to a value and how the textbox shows and parses the value
+    pub fn with_mapping<M: ValueMapping + 'static>(mut self, mapping: M) -> Self {
+        self.mapping = Arc::new(mapping);
+        self
+    }
 }
 
 impl BuildHandler for ValueSlider {
@@ -33,7 +46,8 @@ impl BuildHandler for ValueSlider {
             .set_flex_direction(state, FlexDirection::Row);
 
         self.slider = ProgressBar::new().build(state, entity, |builder| builder.set_flex_grow(1.0));
-        self.value = Textbox::new("0.5").build(state, entity, |builder| {
+        let text = self.mapping.format(self.mapping.to_plain(0.5));
+        self.value = Textbox::new(&text).build(state, entity, |builder| {
             builder
                 .set_flex_basis(Length::Pixels(50.0))
                 .set_margin_left(Length::Pixels(5.0))
@@ -60,7 +74,7 @@ impl EventHandler for ValueSlider {
             match slider_event {
                 SliderEvent::ValueChanged(val) => {
                     if event.target == self.slider {
-                        let val_str = format!("{:.*}", 5, &val.to_string());
+                        let val_str = self.mapping.format(self.mapping.to_plain(*val));
                         state.insert_event(
                             Event::new(TextboxEvent::SetValue(val_str))
                                 .target(self.value)
@@ -77,16 +91,10 @@ impl EventHandler for ValueSlider {
             match textbox_event {
                 TextboxEvent::ValueChanged(text) => {
                     if event.target == self.value {
-                        if let Ok(value) = text.parse::<f32>() {
-                            let mut val = value;
-                            if val <= 0.0 {
-                                val = 0.0;
-                            }
-                            if val >= 1.0 {
-                                val = 1.0;
-                            }
-
-                            let val_str = format!("{:.*}", 5, &val.to_string());
+                        if let Some(value) = self.mapping.parse(text) {
+                            let val = self.mapping.to_normalized(value).min(1.0).max(0.0);
+
+                            let val_str = self.mapping.format(value);
                             state.insert_event(
                                 Event::new(TextboxEvent::SetValue(val_str))
                                     .target(self.value)
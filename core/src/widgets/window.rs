@@ -38,6 +38,14 @@ impl EventHandler for WindowWidget {
                     apply_visibility(state, &state.hierarchy.clone());
                     apply_clipping(state, &state.hierarchy.clone());
                     apply_layout(state, &state.hierarchy.clone());
+                    // Popups are placed once their size is known, and a popup anchored inside another
+                    // popup can only be placed after its parent has moved
+                    for _ in 0..state.popups.len() {
+                        if !state.place_popups() {
+                            break;
+                        }
+                        apply_layout(state, &state.hierarchy.clone());
+                    }
                     apply_hover(state);
                 }
 
@@ -0,0 +1,90 @@
+extern crate tuix;
+
+use tuix::*;
+
+use std::time::Duration;
+
+const SAMPLE_RATE: f32 = 44100.0;
+const BLOCK_SIZE: usize = 441;
+const FFT_SIZE: usize = 1024;
+
+fn main() {
+    // Ten seconds of recording and the spectrum of the latest samples
+    let recording = WaveformBuffer::new(SAMPLE_RATE as usize * 10);
+    let spectrum = SpectrumBuffer::new(FFT_SIZE / 2 + 1, SAMPLE_RATE);
+
+    // Stands in for the audio thread, recording a sweeping tone in blocks of 10ms
+    let audio_recording = recording.clone();
+    let audio_spectrum = spectrum.clone();
+    std::thread::spawn(move || {
+        let mut phase = 0.0f32;
+        let mut time = 0.0f32;
+        let mut block = vec![0.0; BLOCK_SIZE];
+        let mut history = vec![0.0; FFT_SIZE];
+        let mut magnitudes = vec![0.0; FFT_SIZE / 2 + 1];
+
+        loop {
+            for sample in block.iter_mut() {
+                time += 1.0 / SAMPLE_RATE;
+
+                let frequency = 100.0 * 2.0f32.powf(time % 6.0);
+                let amplitude = 0.2 + 0.6 * (time * 1.5).sin().abs();
+
+                phase += frequency / SAMPLE_RATE * std::f32::consts::TAU;
+                *sample = amplitude * (phase.sin() + 0.3 * (phase * 3.0).sin()) / 1.3;
+            }
+
+            if audio_recording.push(&block) < block.len() {
+                audio_recording.clear();
+            }
+            audio_recording.set_playhead(Some(audio_recording.len()));
+
+            history.drain(..BLOCK_SIZE);
+            history.extend_from_slice(&block);
+
+            // A plain DFT with a Hann window, which is fast enough for an example
+            for (bin, magnitude) in magnitudes.iter_mut().enumerate() {
+                let (mut re, mut im) = (0.0f32, 0.0f32);
+
+                for (n, sample) in history.iter().enumerate() {
+                    let window = 0.5 - 0.5 * (std::f32::consts::TAU * n as f32 / FFT_SIZE as f32).cos();
+                    let angle = std::f32::consts::TAU * (bin * n) as f32 / FFT_SIZE as f32;
+                    re += sample * window * angle.cos();
+                    im -= sample * window * angle.sin();
+                }
+
+                // A full scale sine has a magnitude of a quarter of the FFT size through a Hann window
+                *magnitude = (re * re + im * im).sqrt() * 4.0 / FFT_SIZE as f32;
+            }
+
+            audio_spectrum.publish(&magnitudes);
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    let app = Application::new(move |win_desc, state, window| {
+        WaveformView::new()
+            .with_buffer(recording)
+            .with_zoom(64.0)
+            .with_follow_playhead(true)
+            .build(state, window, |builder| {
+                builder
+                    .set_height(Length::Pixels(150.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        SpectrumView::new()
+            .with_buffer(spectrum)
+            .with_smoothing(0.15)
+            .build(state, window, |builder| {
+                builder
+                    .set_height(Length::Pixels(250.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        win_desc.with_title("Analyzer")
+    });
+
+    app.run();
+}
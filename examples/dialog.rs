@@ -0,0 +1,70 @@
+extern crate tuix;
+use tuix::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectEvent {
+    AskDelete,
+    Delete(bool),
+}
+
+// Asks for confirmation before deleting the project
+struct Project {
+    status: Entity,
+}
+
+impl Project {
+    pub fn new() -> Self {
+        Project {
+            status: Entity::null(),
+        }
+    }
+}
+
+impl BuildHandler for Project {
+    type Ret = Entity;
+
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        Button::with_label("Delete Project")
+            .on_release(Event::new(ProjectEvent::AskDelete).target(entity))
+            .build(state, entity, |builder| {
+                builder
+                    .set_width(Length::Pixels(150.0))
+                    .set_height(Length::Pixels(30.0))
+            });
+
+        self.status = Label::new("").build(state, entity, |builder| builder);
+
+        entity
+    }
+}
+
+impl EventHandler for Project {
+    fn on_event(&mut self, state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(project_event) = event.message.downcast::<ProjectEvent>() {
+            match project_event {
+                ProjectEvent::AskDelete => {
+                    Dialogue::confirm("Delete Project", "Are you sure you want to delete the project?")
+                        .on_result(move |button| {
+                            Event::new(ProjectEvent::Delete(button == DialogueButton::Yes)).target(entity)
+                        })
+                        .build(state, Entity::root(), |builder| builder);
+                }
+
+                ProjectEvent::Delete(deleted) => {
+                    let status = if *deleted { "Project deleted" } else { "Cancelled" };
+                    self.status.set_text(state, status);
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = Application::new(|win_desc, state, window| {
+        Project::new().build(state, window, |builder| builder.set_flex_grow(1.0));
+
+        win_desc.with_title("Dialog").with_inner_size(400, 300)
+    });
+
+    app.run();
+}
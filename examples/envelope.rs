@@ -0,0 +1,79 @@
+extern crate tuix;
+
+use tuix::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum AppEvent {
+    EnvelopeChanged(Vec<EnvelopeNode>),
+}
+
+struct App {
+    envelope: Entity,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            envelope: Entity::null(),
+        }
+    }
+}
+
+impl BuildHandler for App {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        self.envelope = EnvelopeEditor::new()
+            .with_x_axis(
+                GridAxis::new(LinearMapping::new(0.0, 2.0).with_unit("s"))
+                    .with_lines(&[0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 1.75]),
+            )
+            .with_y_axis(
+                GridAxis::new(LinearMapping::new(0.0, 1.0))
+                    .with_lines(&[0.25, 0.5, 0.75])
+                    .with_labels(false),
+            )
+            .with_nodes(vec![
+                EnvelopeNode::new(0.0, 0.0).with_tension(-0.4),
+                EnvelopeNode::new(0.05, 1.0).with_tension(0.5),
+                EnvelopeNode::new(0.4, 0.6),
+                EnvelopeNode::new(1.4, 0.6).with_tension(0.5),
+                EnvelopeNode::new(2.0, 0.0),
+            ])
+            .on_change(|nodes| Event::new(AppEvent::EnvelopeChanged(nodes.to_vec())))
+            .build(state, entity, |builder| {
+                builder
+                    .set_width(Length::Pixels(500.0))
+                    .set_height(Length::Pixels(250.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        entity
+    }
+}
+
+impl EventHandler for App {
+    fn on_event(&mut self, _state: &mut State, _entity: Entity, event: &mut Event) {
+        if let Some(app_event) = event.message.downcast::<AppEvent>() {
+            match app_event {
+                AppEvent::EnvelopeChanged(nodes) => {
+                    if event.origin == self.envelope {
+                        for node in nodes.iter() {
+                            print!("({:.2}s, {:.2}) ", node.time, node.level);
+                        }
+                        println!();
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = Application::new(|win_desc, state, window| {
+        App::new().build(state, window, |builder| builder);
+
+        win_desc.with_title("Envelope")
+    });
+
+    app.run();
+}
@@ -0,0 +1,136 @@
+extern crate tuix;
+
+use tuix::*;
+
+// A pan knob draws its arc out from the middle, and a wide knob sweeps almost all the way round
+const THEME: &str = r#"
+knob.pan {
+    arc-center: 50%;
+    arc-width: 3px;
+}
+
+knob.wide {
+    arc-start-angle: -160deg;
+    arc-end-angle: 160deg;
+    arc-width: 8px;
+}
+"#;
+
+const SIZE: usize = 64;
+const FRAMES: usize = 32;
+
+// Draws a film strip of a ring that fills clockwise, as a plugin skin would be rendered
+fn film_strip() -> KnobImage {
+    let mut data = vec![0u8; SIZE * SIZE * FRAMES * 4];
+
+    for frame in 0..FRAMES {
+        let fill = frame as f32 / (FRAMES - 1) as f32;
+
+        for y in 0..SIZE {
+            for x in 0..SIZE {
+                let dx = x as f32 + 0.5 - 0.5 * SIZE as f32;
+                let dy = y as f32 + 0.5 - 0.5 * SIZE as f32;
+                let radius = (dx * dx + dy * dy).sqrt() / (0.5 * SIZE as f32);
+
+                // The angle clockwise from the bottom, as a fraction of a turn
+                let angle = ((-dx).atan2(dy) / std::f32::consts::TAU + 1.0) % 1.0;
+                let swept = (angle - 0.125) / 0.75;
+
+                let pixel = if radius > 0.75 && radius < 0.95 && (0.0..=1.0).contains(&swept) {
+                    if swept <= fill {
+                        [230, 140, 40, 255]
+                    } else {
+                        [70, 70, 70, 255]
+                    }
+                } else if radius < 0.65 {
+                    [40, 40, 40, 255]
+                } else {
+                    [0, 0, 0, 0]
+                };
+
+                let index = ((frame * SIZE + y) * SIZE + x) * 4;
+                data[index..index + 4].copy_from_slice(&pixel);
+            }
+        }
+    }
+
+    KnobImage::new(data, SIZE, SIZE * FRAMES)
+}
+
+// Draws a knob cap with a pointer at the top, which the knob turns
+fn cap() -> KnobImage {
+    let mut data = vec![0u8; SIZE * SIZE * 4];
+
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let dx = x as f32 + 0.5 - 0.5 * SIZE as f32;
+            let dy = y as f32 + 0.5 - 0.5 * SIZE as f32;
+            let radius = (dx * dx + dy * dy).sqrt() / (0.5 * SIZE as f32);
+
+            let pixel = if radius < 1.0 {
+                if dx.abs() < 2.0 && dy < 0.0 && radius > 0.4 {
+                    [240, 240, 240, 255]
+                } else {
+                    let shade = (90.0 - 40.0 * radius) as u8;
+                    [shade, shade, shade, 255]
+                }
+            } else {
+                [0, 0, 0, 0]
+            };
+
+            let index = (y * SIZE + x) * 4;
+            data[index..index + 4].copy_from_slice(&pixel);
+        }
+    }
+
+    KnobImage::new(data, SIZE, SIZE)
+}
+
+fn main() {
+    let app = Application::new(|win_desc, state, window| {
+        state.add_theme(THEME);
+
+        let row = HBox::new().build(state, window, |builder| builder);
+
+        ControlKnob::new(0.0, -1.0, 1.0).build(state, row, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(50.0))
+                .set_margin(Length::Pixels(20.0))
+                .class("pan")
+        });
+
+        ControlKnob::new(0.5, 0.0, 1.0).build(state, row, |builder| {
+            builder
+                .set_width(Length::Pixels(50.0))
+                .set_height(Length::Pixels(50.0))
+                .set_margin(Length::Pixels(20.0))
+                .class("wide")
+        });
+
+        ControlKnob::new(0.5, 0.0, 1.0)
+            .with_skin(KnobSkin::FilmStrip {
+                image: film_strip(),
+                frames: FRAMES,
+            })
+            .build(state, row, |builder| {
+                builder
+                    .set_width(Length::Pixels(64.0))
+                    .set_height(Length::Pixels(64.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        ControlKnob::new(0.5, 0.0, 1.0)
+            .with_skin(KnobSkin::Rotary { image: cap() })
+            .build(state, row, |builder| {
+                builder
+                    .set_width(Length::Pixels(64.0))
+                    .set_height(Length::Pixels(64.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        win_desc.with_title("Knob Skins")
+    });
+
+    app.run();
+}
@@ -0,0 +1,93 @@
+extern crate tuix;
+
+use tuix::*;
+
+// A sample library with too many entries to build one entity per row
+pub struct SampleLibrary {
+    samples: Vec<(String, f32)>,
+}
+
+impl SampleLibrary {
+    pub fn new(count: usize) -> Self {
+        SampleLibrary {
+            samples: (0..count)
+                .map(|index| (format!("Sample {:05}.wav", index), (index % 97) as f32 * 0.25))
+                .collect(),
+        }
+    }
+}
+
+impl ListDataSource for SampleLibrary {
+    fn count(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn build_row(&mut self, state: &mut State, row: Entity) {
+        Label::new("").build(state, row, |builder| builder.set_flex_grow(1.0));
+    }
+
+    fn update_row(&mut self, state: &mut State, row: Entity, index: usize) {
+        if let Some(label) = state.hierarchy.get_first_child(row) {
+            label.set_text(state, &self.samples[index].0);
+        }
+    }
+}
+
+impl TableDataSource for SampleLibrary {
+    fn row_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    fn update_cell(&mut self, state: &mut State, cell: Entity, row: usize, column: usize) {
+        let (name, length) = &self.samples[row];
+
+        match column {
+            0 => cell.set_text(state, name),
+            _ => cell.set_text(state, &format!("{:.2} s", length)),
+        };
+    }
+
+    fn sort(&mut self, column: usize, order: SortOrder) -> bool {
+        match column {
+            0 => self.samples.sort_by(|a, b| a.0.cmp(&b.0)),
+            _ => self.samples.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap()),
+        }
+
+        if order == SortOrder::Descending {
+            self.samples.reverse();
+        }
+
+        true
+    }
+
+    fn is_editable(&self, _row: usize, column: usize) -> bool {
+        column == 0
+    }
+
+    fn set_cell_text(&mut self, row: usize, column: usize, text: &str) {
+        if column == 0 {
+            self.samples[row].0 = text.to_string();
+        }
+    }
+}
+
+fn main() {
+    let app = Application::new(|win_desc, state, window| {
+        let row = HBox::new().build(state, window, |builder| builder.set_flex_grow(1.0));
+
+        ListView::new(SampleLibrary::new(20000))
+            .with_row_height(RowHeight::Fixed(24.0))
+            .build(state, row, |builder| builder.set_flex_grow(1.0));
+
+        TableView::new(SampleLibrary::new(20000))
+            .with_column(TableColumn::new("Name", 200.0))
+            .with_column(TableColumn::new("Length", 80.0))
+            .with_row_height(RowHeight::Fixed(24.0))
+            .with_selection_mode(SelectionMode::Range)
+            .build(state, row, |builder| builder.set_flex_grow(1.0));
+
+        win_desc.with_title("List View")
+    });
+
+    app.run();
+}
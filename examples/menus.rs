@@ -2,181 +2,141 @@ extern crate tuix;
 
 use tuix::*;
 
-use tuix::widgets::Button;
-
 static THEME: &'static str = include_str!("themes/menus_theme.css");
 
-// #[derive(Clone, Debug)]
-// pub enum MenuBarEvent {
-//     SetText(String),
-//     OptionChanged(u32),
-// }
-
-// impl Message for MenuBarEvent {}
-
-// pub struct MenuBar {
-//     open_menu: Entity,
-// }
-
-// impl MenuBar {
-//     pub fn new(text: &str) -> Self {
-//         MenuBar {
-//             open_menu: Entity::null(),
-//         }
-//     }
-// }
-
-// impl EventHandler for MenuBar {
-//     fn build<'a>(
-//         mut self,
-//         state: &'a mut State,
-//         parent: Entity,
-//         event_manager: &'a mut EventManager,
-//     ) -> Builder<'a> {
-//         let id = state.add(parent);
-//         id.set_width(state, 200.0)
-//             .set_height(state, 30.0)
-//             .set_display(state, Display::Flexbox);
-
-//         event_manager.build(id, parent, state, self)
-//     }
-
-//     fn handle_event(
-//         &mut self,
-//         id: Entity,
-//         state: &mut State,
-//         event: &Event,
-//         event_manager: &mut EventManager,
-//     ) -> bool {
-//         // if let Some(menu_event) = event.message.downcast::<MenuEvent>() {
-//         //     match menu_event {
-
-//         //     }
-//         // }
-
-//         if let Some(window_event) = event.message.downcast::<WindowEvent>() {
-//             match window_event {
-//                 WindowEvent::MouseMove(x, y) => {
-//                     for child in id.child_iter(&state.hierarchy) {
-//                         if child == state.hovered {
-//                             //event_manager.insert_event(Event::new(MenuEvent::Open(child)).target(child).propagate(false));
-
-//                             return false;
-//                         }
-//                     }
-//                 }
-
-//                 WindowEvent::MouseDown(button, mods) => match button {
-//                     MouseButton::Left => {
-//                         if state.hovered == id {
-//                             event_manager.insert_event(Event::new(StyleEvent::Restyle);
-//                         } else {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppEvent {
+    New,
+    Open,
+    Save,
+    ToggleGrid,
+    Zoom(u32),
+}
 
-//                         }
-//                     }
-//                     _ => {}
-//                 },
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClipEvent {
+    Cut,
+    Copy,
+    Paste,
+    ToggleLoop,
+    Reverse,
+    Normalize,
+}
 
-//                 WindowEvent::MouseUp(button, mods) => match button {
-//                     MouseButton::Left => {}
+// A widget which prints the events sent by its context menu
+struct Clip;
 
-//                     _ => {}
-//                 },
+impl BuildHandler for Clip {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        entity.set_text(state, "Right Click Me");
 
-//                 _ => {}
-//             }
-//         }
+        entity
+    }
+}
 
-//         false
-//     }
-// }
+impl EventHandler for Clip {
+    fn on_event(&mut self, _state: &mut State, entity: Entity, event: &mut Event) {
+        if let Some(clip_event) = event.message.downcast::<ClipEvent>() {
+            if event.target == entity {
+                println!("Clip: {:?}", clip_event);
+            }
+        }
+    }
+}
 
 fn main() {
     // Create the app
     let app = Application::new(|win_desc, state, window| {
         state.add_theme(THEME);
 
-        // let menu1 = Menu::new("Menu", MenuPosition::Down).build(state, window, |builder| {
-        //     builder
-        //         .set_width(Length::Pixels(100.0))
-        //         .set_height(Length::Pixels(30.0))
-        //         .set_flex_grow(0.0)
-        //         .set_text_justify(Justify::Center)
-        //         .class("menu")
-        // });
-
-        // // Button::new().build2(state, menu1, |builder| builder.class("spacer2"));
-
-        // Button::with_label("Item 1").build(state, menu1, |builder| builder.class("item"));
-        // Button::with_label("Item 2").build(state, menu1, |builder| builder.class("item"));
-        // Button::with_label("Item 3")
-        //     .on_press(Event::new(WindowEvent::WindowClose))
-        //     .build(state, menu1, |builder| builder.class("item"));
-
-        // let spacer = Button::new().build(state, menu1, |builder| builder.class("spacer"));
-        // Button::new().build(state, spacer, |builder| builder.class("spacer1"));
-        // Button::new().build(state, spacer, |builder| builder.class("spacer2"));
-
-        // let menu2 = Menu::new("Submenu", MenuPosition::Right).build(state, menu1, |builder| {
-        //     builder.class("item").class("submenu")
-        // });
-
-        // Button::with_label("Item 4").build(state, menu1, |builder| builder.class("item"));
-
-        // // Button::new().build2(state, menu1, |builder| builder.class("spacer2"));
-
-        // Button::with_label("SubItem 1").build(state, menu2, |builder| builder.class("item"));
-        // Button::with_label("SubItem 2").build(state, menu2, |builder| builder.class("item"));
-        // Button::with_label("SubItem 3")
-        //     .on_press(Event::new(WindowEvent::WindowClose))
-        //     .build(state, menu2, |builder| builder.class("item"));
-
-        // Button::new().build(state, menu1, |builder| builder.class("spacer2"));
-
-        // let menu3 = Menu::new("SubSubMenu", MenuPosition::Right).build(state, menu2, |builder| {
-        //     builder.class("item").class("submenu")
-        // });
-
-        // Button::with_label("SubSubItem 1").build(state, menu3, |builder| builder.class("item"));
-        // Button::with_label("SubSubItem 2").build(state, menu3, |builder| builder.class("item"));
-        // Button::with_label("SubSubItem 3")
-        //     .on_press(Event::new(WindowEvent::WindowClose))
-        //     .build(state, menu3, |builder| builder.class("item"));
-
-        let button = Button::with_label("Right Click Me").build(state, window, |builder| {
+        MenuBar::new()
+            .with_menu(
+                "&File",
+                MenuDescription::new()
+                    .with_item(
+                        MenuItemDescription::new("&New", Event::new(AppEvent::New))
+                            .with_shortcut("Ctrl+N"),
+                    )
+                    .with_item(
+                        MenuItemDescription::new("&Open", Event::new(AppEvent::Open))
+                            .with_shortcut("Ctrl+O"),
+                    )
+                    .with_item(
+                        MenuItemDescription::new("&Save", Event::new(AppEvent::Save))
+                            .with_shortcut("Ctrl+S")
+                            .with_enabled(false),
+                    )
+                    .with_separator()
+                    .with_item(MenuItemDescription::new(
+                        "E&xit",
+                        Event::new(WindowEvent::WindowClose),
+                    )),
+            )
+            .with_menu(
+                "&View",
+                MenuDescription::new()
+                    .with_item(
+                        MenuItemDescription::new("&Grid", Event::new(AppEvent::ToggleGrid))
+                            .with_checked(true),
+                    )
+                    .with_separator()
+                    .with_item(
+                        MenuItemDescription::new("&50%", Event::new(AppEvent::Zoom(50)))
+                            .with_radio(false),
+                    )
+                    .with_item(
+                        MenuItemDescription::new("&100%", Event::new(AppEvent::Zoom(100)))
+                            .with_radio(true),
+                    )
+                    .with_item(
+                        MenuItemDescription::new("&200%", Event::new(AppEvent::Zoom(200)))
+                            .with_radio(false),
+                    ),
+            )
+            .build(state, window, |builder| builder);
+
+        let clip = Clip.build(state, window, |builder| {
             builder
                 .set_left(Length::Pixels(100.0))
                 .set_top(Length::Pixels(100.0))
                 .set_width(Length::Pixels(150.0))
                 .set_height(Length::Pixels(30.0))
                 .set_background_color(Color::green())
+                .set_tab_index(0)
         });
 
-        let (_container, menu) = ContextMenu::new().build(state, button, |builder| {
-            builder
-                .set_width(Length::Percentage(1.0))
-                .set_height(Length::Percentage(1.0))
-        });
-
-        menu.set_width(state, Length::Pixels(100.0));
-
-        Button::with_label("Option 1").build(state, menu, |builder| {
-            builder
-                .set_height(Length::Pixels(30.0))
-                .set_background_color(Color::rgb(50, 50, 50))
-        });
-
-        Button::with_label("Option 2").build(state, menu, |builder| {
-            builder
-                .set_height(Length::Pixels(30.0))
-                .set_background_color(Color::rgb(50, 50, 50))
-        });
-
-        Button::with_label("Option 3").build(state, menu, |builder| {
-            builder
-                .set_height(Length::Pixels(30.0))
-                .set_background_color(Color::rgb(50, 50, 50))
-        });
+        state.set_context_menu(
+            clip,
+            MenuDescription::new()
+                .with_item(
+                    MenuItemDescription::new("Cut", Event::new(ClipEvent::Cut))
+                        .with_shortcut("Ctrl+X"),
+                )
+                .with_item(
+                    MenuItemDescription::new("Copy", Event::new(ClipEvent::Copy))
+                        .with_shortcut("Ctrl+C"),
+                )
+                .with_item(
+                    MenuItemDescription::new("Paste", Event::new(ClipEvent::Paste))
+                        .with_shortcut("Ctrl+V")
+                        .with_enabled(false),
+                )
+                .with_separator()
+                .with_item(
+                    MenuItemDescription::new("Loop", Event::new(ClipEvent::ToggleLoop))
+                        .with_checked(true),
+                )
+                .with_submenu(
+                    "Process",
+                    MenuDescription::new()
+                        .with_item(MenuItemDescription::new("Reverse", Event::new(ClipEvent::Reverse)))
+                        .with_item(MenuItemDescription::new(
+                            "Normalize",
+                            Event::new(ClipEvent::Normalize),
+                        )),
+                ),
+        );
 
         win_desc.with_title("Menus")
     });
@@ -0,0 +1,72 @@
+extern crate tuix;
+
+use tuix::*;
+
+use std::time::Duration;
+
+fn main() {
+    let feed = MeterFeed::new(2);
+    let mix_feed = MeterFeed::new(1);
+
+    // Stands in for the audio thread, producing a block of 512 samples every 10ms
+    let audio_feed = feed.clone();
+    let audio_mix_feed = mix_feed.clone();
+    std::thread::spawn(move || {
+        let mut phase = 0.0f32;
+        let mut time = 0.0f32;
+        let mut left = vec![0.0; 512];
+        let mut right = vec![0.0; 512];
+        let mut mix = vec![0.0; 512];
+
+        loop {
+            time += 0.01;
+
+            // Swells slowly and clips now and then
+            let amplitude = 0.6 + 0.45 * (time * 0.7).sin();
+
+            for ((l, r), m) in left.iter_mut().zip(right.iter_mut()).zip(mix.iter_mut()) {
+                phase += 440.0 / 44100.0 * std::f32::consts::TAU;
+                *l = amplitude * phase.sin();
+                *r = 0.5 * amplitude * phase.sin();
+                *m = 0.5 * (*l + *r);
+            }
+
+            audio_feed.process(0, &left);
+            audio_feed.process(1, &right);
+            audio_mix_feed.process(0, &mix);
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    let app = Application::new(move |win_desc, state, window| {
+        window.set_flex_direction(state, FlexDirection::Row);
+
+        LevelMeter::new(2)
+            .with_feed(feed)
+            .with_range(-60.0, 6.0)
+            .with_ticks(&[6.0, 0.0, -6.0, -12.0, -24.0, -48.0])
+            .build(state, window, |builder| {
+                builder
+                    .set_width(Length::Pixels(50.0))
+                    .set_height(Length::Pixels(200.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        LevelMeter::new(1)
+            .with_feed(mix_feed)
+            .with_orientation(Orientation::Horizontal)
+            .with_rms(false)
+            .with_peak_hold(0.5, 40.0)
+            .build(state, window, |builder| {
+                builder
+                    .set_width(Length::Pixels(200.0))
+                    .set_height(Length::Pixels(30.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        win_desc.with_title("Meters")
+    });
+
+    app.run();
+}
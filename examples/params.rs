@@ -0,0 +1,65 @@
+extern crate tuix;
+
+use tuix::*;
+
+use std::time::Duration;
+
+const GAIN: ParamId = 0;
+const LEVEL: ParamId = 1;
+
+fn main() {
+    let params = ParamStore::new(vec![
+        ParamInfo::new("Gain", 0.0, 1.0, 0.5),
+        ParamInfo::new("Level", 0.0, 1.0, 0.0),
+    ]);
+
+    // Stands in for the audio thread, which must never block
+    let audio_params = params.clone();
+    std::thread::spawn(move || {
+        let mut phase = 0.0f32;
+
+        loop {
+            while let Some(gesture) = audio_params.pop_gesture() {
+                println!("Gesture: {:?}", gesture);
+            }
+
+            phase += 0.05;
+
+            let level = audio_params.get(GAIN) * (0.5 + 0.5 * phase.sin());
+            audio_params.set(LEVEL, level);
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    });
+
+    let app = Application::new(move |win_desc, state, window| {
+        window.set_flex_direction(state, FlexDirection::Row);
+
+        // Double clicking the knob resets the gain to its default
+        let knob = ValueKnob::new("Gain", 0.5, 0.0, 1.0)
+            .with_mapping(DecibelMapping::new(-60.0, 0.0))
+            .with_default(0.5)
+            .build(state, window, |builder| {
+                builder
+                    .set_width(Length::Pixels(50.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        let meter = AudioLevelBar::new().build(state, window, |builder| {
+            builder
+                .set_width(Length::Pixels(20.0))
+                .set_height(Length::Pixels(150.0))
+                .set_margin(Length::Pixels(20.0))
+        });
+
+        state.set_param_store(params);
+        state.bind_param(knob, GAIN);
+        state.bind_param_with(meter, LEVEL, |level| {
+            Event::new(AudioLevelEvent::SetLevel(level))
+        });
+
+        win_desc.with_title("Parameters")
+    });
+
+    app.run();
+}
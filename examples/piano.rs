@@ -0,0 +1,79 @@
+extern crate tuix;
+
+use tuix::*;
+
+struct App {
+    keyboard: Entity,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            keyboard: Entity::null(),
+        }
+    }
+}
+
+impl BuildHandler for App {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        self.keyboard = PianoKeyboard::new()
+            .with_range(36, 84)
+            .build(state, entity, |builder| {
+                builder
+                    .set_height(Length::Pixels(100.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        entity
+    }
+}
+
+impl EventHandler for App {
+    fn on_event(&mut self, state: &mut State, _entity: Entity, event: &mut Event) {
+        if let Some(piano_event) = event.message.downcast::<PianoEvent>() {
+            if event.origin != self.keyboard {
+                return;
+            }
+
+            // Highlights the fifth above each played note, as a host playing along would
+            match piano_event {
+                PianoEvent::NoteOn { note, velocity } => {
+                    println!("Note on: {} velocity: {:.2}", note, velocity);
+
+                    state.insert_event(
+                        Event::new(PianoEvent::Highlight {
+                            note: note + 7,
+                            highlighted: true,
+                        })
+                        .target(self.keyboard),
+                    );
+                }
+
+                PianoEvent::NoteOff { note } => {
+                    println!("Note off: {}", note);
+
+                    state.insert_event(
+                        Event::new(PianoEvent::Highlight {
+                            note: note + 7,
+                            highlighted: false,
+                        })
+                        .target(self.keyboard),
+                    );
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = Application::new(|win_desc, state, window| {
+        App::new().build(state, window, |builder| builder);
+
+        win_desc.with_title("Piano")
+    });
+
+    app.run();
+}
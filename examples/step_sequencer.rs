@@ -0,0 +1,124 @@
+extern crate tuix;
+
+use tuix::*;
+
+use std::time::Duration;
+
+// The step being played, or -1.0 when stopped
+const POSITION: ParamId = 0;
+
+const STEPS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq)]
+enum AppEvent {
+    StepChanged(usize, usize, Step),
+}
+
+struct App {
+    sequencer: Entity,
+}
+
+impl App {
+    fn new() -> Self {
+        App {
+            sequencer: Entity::null(),
+        }
+    }
+}
+
+impl BuildHandler for App {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        // Four on the floor with an off beat hat
+        let mut matrix = vec![vec![Step::off(); STEPS]; 4];
+        for step in (0..STEPS).step_by(4) {
+            matrix[0][step] = Step::on(1.0);
+            matrix[2][step + 2] = Step::on(0.6);
+        }
+        matrix[1][4] = Step::on(0.9);
+        matrix[1][12] = Step::on(0.9);
+
+        self.sequencer = StepSequencer::new(4, STEPS)
+            .with_row_labels(&["Kick", "Snare", "Hat", "Clap"])
+            .with_matrix(matrix)
+            .on_change(|row, step, value| Event::new(AppEvent::StepChanged(row, step, value)))
+            .build(state, entity, |builder| {
+                builder
+                    .set_height(Length::Pixels(140.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        state.bind_param_with(self.sequencer, POSITION, |position| {
+            let playhead = if position < 0.0 {
+                None
+            } else {
+                Some(position as usize)
+            };
+
+            Event::new(SequencerEvent::SetPlayhead(playhead))
+        });
+
+        // Stores the matrix when the button is pressed, which is answered with a Matrix event sent to the button
+        Button::with_label("Print pattern")
+            .on_press(Event::new(SequencerEvent::StoreMatrix).target(self.sequencer))
+            .build(state, entity, |builder| {
+                builder
+                    .set_width(Length::Pixels(120.0))
+                    .set_height(Length::Pixels(30.0))
+                    .set_margin(Length::Pixels(20.0))
+                    .set_background_color(Color::rgb(50, 50, 50))
+            });
+
+        entity
+    }
+}
+
+impl EventHandler for App {
+    fn on_event(&mut self, _state: &mut State, _entity: Entity, event: &mut Event) {
+        if let Some(app_event) = event.message.downcast::<AppEvent>() {
+            match app_event {
+                AppEvent::StepChanged(row, step, value) => {
+                    if event.origin == self.sequencer {
+                        println!("Row {} step {}: {:?}", row, step, value);
+                    }
+                }
+            }
+        }
+
+        if let Some(SequencerEvent::Matrix(matrix)) = event.message.downcast::<SequencerEvent>() {
+            if event.origin == self.sequencer {
+                for row in matrix.iter() {
+                    let line: String = row.iter().map(|step| if step.on { 'x' } else { '.' }).collect();
+                    println!("{}", line);
+                }
+            }
+        }
+    }
+}
+
+fn main() {
+    let params = ParamStore::new(vec![ParamInfo::new("Position", -1.0, STEPS as f32, -1.0)]);
+
+    // Stands in for the host transport, playing sixteenth notes at 120 BPM
+    let transport = params.clone();
+    std::thread::spawn(move || {
+        let mut step = 0;
+
+        loop {
+            transport.set(POSITION, step as f32);
+            step = (step + 1) % STEPS;
+
+            std::thread::sleep(Duration::from_millis(125));
+        }
+    });
+
+    let app = Application::new(move |win_desc, state, window| {
+        state.set_param_store(params);
+
+        App::new().build(state, window, |builder| builder);
+
+        win_desc.with_title("Step Sequencer")
+    });
+
+    app.run();
+}
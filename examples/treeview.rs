@@ -2,16 +2,84 @@ extern crate tuix;
 
 use tuix::*;
 
-use tuix::widgets::{Panel, ResizableVBox, ScrollContainer};
+use tuix::widgets::{ResizableVBox, ScrollContainer};
 
 static THEME: &'static str = include_str!("themes/treeview_theme.css");
 
-fn main() {
-    //let event_loop = EventLoop::new();
-    //Create the glutin window
-    //let window = Window::new(&event_loop, WindowDescription::new().with_title("Panels").with_inner_size(800, 600));
+// A project browser whose sample folders are only filled in when they are first expanded
+pub struct ProjectBrowser {
+    folders: Vec<(Entity, usize)>,
+}
+
+impl ProjectBrowser {
+    pub fn new() -> Self {
+        ProjectBrowser {
+            folders: Vec::new(),
+        }
+    }
+}
+
+impl BuildHandler for ProjectBrowser {
+    type Ret = Entity;
+    fn on_build(&mut self, state: &mut State, entity: Entity) -> Self::Ret {
+        let scroll = ScrollContainer::new().build(state, entity, |builder| builder);
+
+        let tree = TreeView::new()
+            .with_selection_mode(SelectionMode::Range)
+            .build(state, scroll, |builder| builder);
+
+        let project = TreeItem::new("Project")
+            .with_expanded(true)
+            .build(state, tree, |builder| builder);
+
+        let tracks = TreeItem::new("Tracks").build(state, project, |builder| builder);
+        TreeItem::new("Drums").build(state, tracks, |builder| builder);
+        TreeItem::new("Bass").build(state, tracks, |builder| builder);
+        TreeItem::new("Lead").build(state, tracks, |builder| builder);
+
+        let samples = TreeItem::new("Samples").build(state, project, |builder| builder);
+
+        for (index, name) in ["Kicks", "Snares", "Hats"].iter().enumerate() {
+            let folder = TreeItem::new(name)
+                .with_lazy_children()
+                .build(state, samples, |builder| builder);
+
+            self.folders.push((folder, index));
+        }
 
-    // Create the app
+        TreeItem::new("Presets").build(state, tree, |builder| builder);
+
+        entity
+    }
+}
+
+impl EventHandler for ProjectBrowser {
+    fn on_event(&mut self, state: &mut State, _entity: Entity, event: &mut Event) {
+        let origin = event.origin;
+
+        if let Some(tree_event) = event.message.downcast::<TreeEvent>() {
+            match tree_event {
+                TreeEvent::LoadChildren(container) => {
+                    if let Some((_, index)) = self.folders.iter().find(|(folder, _)| *folder == origin) {
+                        let count = 3 + index * 2;
+                        for sample in 0..count {
+                            TreeItem::new(&format!("Sample {}.wav", sample + 1))
+                                .build(state, *container, |builder| builder);
+                        }
+                    }
+                }
+
+                TreeEvent::Moved(item, parent, index) => {
+                    println!("Moved {} to {} at {}", item, parent, index);
+                }
+
+                _ => {}
+            }
+        }
+    }
+}
+
+fn main() {
     let app = Application::new(|win_desc, state, window| {
         state.add_theme(THEME);
 
@@ -19,42 +87,12 @@ fn main() {
             builder
                 .set_width(Length::Pixels(300.0))
                 .set_height(Length::Percentage(1.0))
-                .set_background_color(Color::blue())
                 .class("container")
         });
 
-        let scroll = ScrollContainer::new().build(state, rvbox, |builder| builder);
-
-        let root = Panel::new("ROOT").build(state, scroll, |builder| builder);
-
-        let one = Panel::new("Level 1").build(state, root, |builder| builder.class("level1"));
-        let _one_one = Label::new("Level 2").build(state, one, |builder| builder.class("level2"));
-        let _one_two = Label::new("Level 2").build(state, one, |builder| builder.class("level2"));
-
-        let one_three = Panel::new("Level 2").build(state, one, |builder| builder.class("level2"));
-        let _one_three_one =
-            Label::new("Level 3").build(state, one_three, |builder| builder.class("level3"));
-        let _one_three_two =
-            Label::new("Level 3").build(state, one_three, |builder| builder.class("level3"));
-        let _one_four = Label::new("Level 2").build(state, one, |builder| builder.class("level2"));
-
-        let two = Panel::new("Level 1").build(state, root, |builder| builder.class("level1"));
-        let _two_one = Label::new("Level 2").build(state, two, |builder| builder.class("level2"));
-        let _two_two = Label::new("Level 2").build(state, two, |builder| builder.class("level2"));
-        let _two_three = Label::new("Level 2").build(state, two, |builder| builder.class("level2"));
-        let _two_four = Label::new("Level 2").build(state, two, |builder| builder.class("level2"));
-
-        let three = Panel::new("Level 1").build(state, root, |builder| builder.class("level1"));
-        let three_one =
-            Panel::new("Level 2").build(state, three, |builder| builder.class("level2"));
-        let three_one_one =
-            Panel::new("Level 3").build(state, three_one, |builder| builder.class("level3"));
-        let _three_one_one_one =
-            Label::new("Level 4").build(state, three_one_one, |builder| builder.class("level4"));
-        let _three_one_one_two =
-            Label::new("Level 4").build(state, three_one_one, |builder| builder.class("level4"));
-
-        win_desc.with_title("Panels").with_inner_size(800, 600)
+        ProjectBrowser::new().build(state, rvbox, |builder| builder.set_flex_grow(1.0));
+
+        win_desc.with_title("Tree View").with_inner_size(800, 600)
     });
 
     app.run();
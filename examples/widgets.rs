@@ -40,6 +40,7 @@ fn main() {
                 .set_width(Length::Pixels(100.0))
                 .set_height(Length::Pixels(30.0))
                 .set_flex_grow(1.0)
+                .set_tooltip("Does nothing")
         });
 
         // CHECKBOX
@@ -50,7 +51,12 @@ fn main() {
         // SWITCH
         let row = HBox::new().build(state, panel, |builder| builder);
         Label::new("Switch").build(state, row, |builder| builder);
-        let switch = Switch::new(false).build(state, row, |builder| builder);
+        let switch = Switch::new(false).build(state, row, |builder| {
+            builder.set_tooltip_content(|state, tooltip| {
+                Label::new("Switch").build(state, tooltip, |builder| builder);
+                Label::new("Toggled by the spinner limits").build(state, tooltip, |builder| builder);
+            })
+        });
 
         // INPUT PANEL
         let panel = Panel::new("Input").build(state, rvbox, |builder| builder);
@@ -86,9 +92,22 @@ fn main() {
         Item::new("Item 2", "Item 2").build(state, dropdown, |builder| builder);
         Item::new("Item 3", "Item 3").build(state, dropdown, |builder| builder);
 
+        // COMBO BOX
+        let row = HBox::new().build(state, panel, |builder| builder);
+        Label::new("Combo Box").build(state, row, |builder| builder);
+        ComboBox::new("Init")
+            .with_items((0..800).map(|index| format!("Preset {:03}", index)).collect())
+            .build(state, row, |builder| builder.set_flex_grow(1.0));
+
         // LISTBOX
         let row = HBox::new().build(state, panel, |builder| builder);
         Label::new("Listbox").build(state, row, |builder| builder);
+        let listbox = Listbox::new()
+            .with_selection_mode(SelectionMode::Range)
+            .build(state, row, |builder| builder.set_flex_grow(1.0));
+        ListboxItem::with_label("Apple").build(state, listbox, |builder| builder);
+        ListboxItem::with_label("Banana").build(state, listbox, |builder| builder);
+        ListboxItem::with_label("Cherry").build(state, listbox, |builder| builder);
 
         // SLIDERS PANEL
         let panel = Panel::new("Sliders").build(state, rvbox, |builder| builder);
@@ -0,0 +1,60 @@
+extern crate tuix;
+
+use tuix::*;
+
+const CUTOFF: ParamId = 0;
+const RESONANCE: ParamId = 1;
+
+fn main() {
+    let params = ParamStore::new(vec![
+        ParamInfo::new("Cutoff", 20.0, 20000.0, 1000.0),
+        ParamInfo::new("Resonance", 0.0, 1.0, 0.5),
+    ]);
+
+    let app = Application::new(move |win_desc, state, window| {
+        state.set_param_store(params);
+
+        XYPad::new()
+            .with_x_axis(
+                GridAxis::new(LogMapping::new(20.0, 20000.0).with_unit("Hz"))
+                    .with_lines(&[50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0]),
+            )
+            .with_y_axis(
+                GridAxis::new(LinearMapping::new(0.0, 1.0))
+                    .with_lines(&[0.25, 0.5, 0.75])
+                    .with_labels(false),
+            )
+            .with_point(XYPoint::new(1000.0, 0.5).with_params(CUTOFF, RESONANCE))
+            .with_snap(true)
+            .build(state, window, |builder| {
+                builder
+                    .set_width(Length::Pixels(400.0))
+                    .set_height(Length::Pixels(250.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        // Knobs bound to the same parameters follow the pad
+        let row = HBox::new().build(state, window, |builder| builder);
+
+        let cutoff = ValueKnob::new("Cutoff", 1000.0, 20.0, 20000.0)
+            .with_mapping(LogMapping::new(20.0, 20000.0).with_unit("Hz"))
+            .build(state, row, |builder| {
+                builder
+                    .set_width(Length::Pixels(60.0))
+                    .set_margin(Length::Pixels(20.0))
+            });
+
+        let resonance = ValueKnob::new("Resonance", 0.5, 0.0, 1.0).build(state, row, |builder| {
+            builder
+                .set_width(Length::Pixels(60.0))
+                .set_margin(Length::Pixels(20.0))
+        });
+
+        state.bind_param(cutoff, CUTOFF);
+        state.bind_param(resonance, RESONANCE);
+
+        win_desc.with_title("XY Pad")
+    });
+
+    app.run();
+}
@@ -11,9 +11,8 @@ use tuix_core::{Entity, State};
 
 use tuix_core::state::mouse::{MouseButton, MouseButtonState};
 
-use tuix_core::events::{Event, EventManager, Propagation};
+use tuix_core::events::{Event, EventManager, PointerInfo, Propagation};
 
-use tuix_core::state::hierarchy::IntoHierarchyIterator;
 
 use tuix_core::state::Fonts;
 
@@ -141,6 +140,12 @@ impl Application {
                 }
 
                 GEvent::MainEventsCleared => {
+                    // Sends any long press events which are due, shows any pending tooltip and
+                    // updates the widgets bound to parameters changed by the audio thread and the level meters
+                    let gesture_deadline = state.update_gestures();
+                    let tooltip_deadline = state.update_tooltips();
+                    let params_deadline = state.update_params();
+                    let meters_deadline = state.update_meters();
 
                     let mut needs_redraw = false;
                     while !state.event_queue.is_empty() {
@@ -160,6 +165,14 @@ impl Application {
                         //state.insert_event(Event::new(WindowEvent::Redraw));
                         event_loop_proxy.send_event(()).unwrap();
                         window.handle.window().request_redraw();
+                    } else if let Some(deadline) = gesture_deadline
+                        .into_iter()
+                        .chain(tooltip_deadline)
+                        .chain(params_deadline)
+                        .chain(meters_deadline)
+                        .min()
+                    {
+                        *control_flow = ControlFlow::WaitUntil(deadline);
                     } else {
                         //println!("Wait");
                         *control_flow = ControlFlow::Wait;
@@ -196,6 +209,7 @@ impl Application {
                 GEvent::RedrawRequested(_) => {
                     let hierarchy = state.hierarchy.clone();
                     event_manager.draw(&mut state, &hierarchy, &mut window.canvas);
+                    state.update_accessibility();
                     // Swap buffers
                     window
                         .handle
@@ -230,7 +244,13 @@ impl Application {
                         ////////////////////
                         // Focused Window //
                         ////////////////////
-                        glutin::event::WindowEvent::Focused(_) => {
+                        glutin::event::WindowEvent::Focused(focused) => {
+                            state.insert_event(
+                                Event::new(WindowEvent::WindowFocus(focused))
+                                    .target(Entity::root())
+                                    .propagate(Propagation::All),
+                            );
+
                             state.insert_event(
                                 Event::new(WindowEvent::Restyle)
                                     .target(Entity::root())
@@ -289,55 +309,11 @@ impl Application {
                                 if virtual_keycode == VirtualKeyCode::Tab
                                     && s == MouseButtonState::Pressed
                                 {
-                                    let next_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .next;
-                                    let prev_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .prev;
-
                                     if state.modifiers.shift {
-                                        if prev_focus != Entity::null() {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused = prev_focus;
-                                            state.focused.set_focus(&mut state, true);
-                                        } else {
-                                            // TODO impliment reverse iterator for hierarchy
-                                            // state.focused = match state.focused.into_iter(&state.hierarchy).next() {
-                                            //     Some(val) => val,
-                                            //     None => Entity::root(),
-                                            // };
-                                        }
+                                        state.focus_prev();
                                     } else {
-                                        let hierarchy = state.hierarchy.clone();
-                                        if next_focus != Entity::null() {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused = next_focus;
-                                            state.focused.set_focus(&mut state, true);
-                                        } else {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused =
-                                                match state.focused.into_iter(&hierarchy).next() {
-                                                    Some(val) => val,
-                                                    None => Entity::root(),
-                                                };
-                                            state.focused.set_focus(&mut state, true);
-                                        }
+                                        state.focus_next();
                                     }
-
-                                    state.insert_event(
-                                        Event::new(WindowEvent::Restyle)
-                                            .target(Entity::root())
-                                            .origin(Entity::root()),
-                                    );
                                 }
                             }
 
@@ -427,6 +403,38 @@ impl Application {
                                         .target(state.hovered),
                                 );
                             }
+
+                            state.pointer_move(0, cursorx, cursory, None);
+                        }
+
+                        glutin::event::WindowEvent::Touch(touch) => {
+                            let x = touch.location.x as f32;
+                            let y = touch.location.y as f32;
+                            let pressure = touch.force.map(|force| force.normalized() as f32);
+                            let pointer = PointerInfo::touch(touch.id, x, y, pressure);
+
+                            state.mouse.cursorx = x;
+                            state.mouse.cursory = y;
+
+                            apply_hover(&mut state);
+
+                            match touch.phase {
+                                glutin::event::TouchPhase::Started => {
+                                    state.pointer_down(pointer);
+                                }
+
+                                glutin::event::TouchPhase::Moved => {
+                                    state.pointer_move(pointer.id, x, y, pressure);
+                                }
+
+                                glutin::event::TouchPhase::Ended => {
+                                    state.pointer_up(pointer.id, x, y);
+                                }
+
+                                glutin::event::TouchPhase::Cancelled => {
+                                    state.pointer_cancel(pointer.id);
+                                }
+                            }
                         }
 
                         glutin::event::WindowEvent::MouseInput {
@@ -506,6 +514,12 @@ impl Application {
 
                                         _ => {}
                                     }
+
+                                    state.pointer_down(PointerInfo::mouse(
+                                        b,
+                                        state.mouse.cursorx,
+                                        state.mouse.cursory,
+                                    ));
                                 }
 
                                 MouseButtonState::Released => {
@@ -546,6 +560,10 @@ impl Application {
 
                                         _ => {}
                                     }
+
+                                    if state.gestures.pressed_button(0) == Some(b) {
+                                        state.pointer_up(0, state.mouse.cursorx, state.mouse.cursory);
+                                    }
                                 }
                             }
                         }
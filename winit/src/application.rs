@@ -3,15 +3,14 @@ use winit::event_loop::{ControlFlow, EventLoop};
 use crate::keyboard::{scan_to_code, vk_to_key};
 use crate::window::Window;
 
-use tuix_core::events::{Event, EventManager, Propagation};
-use tuix_core::state::hierarchy::IntoHierarchyIterator;
+use tuix_core::events::{Event, EventManager, PointerInfo, Propagation};
 use tuix_core::state::mouse::{MouseButton, MouseButtonState};
 use tuix_core::state::Fonts;
 use tuix_core::{Entity, State};
 use tuix_core::{Length, Visibility};
 
 use tuix_core::state::style::prop::*;
-use tuix_core::systems::{apply_styles};
+use tuix_core::systems::{apply_hover, apply_styles};
 use tuix_core::{WindowDescription, WindowEvent, WindowWidget};
 
 type WEvent<'a, T> = winit::event::Event<'a, T>;
@@ -125,9 +124,17 @@ impl Application {
                 WEvent::UserEvent(_) => {}
 
                 WEvent::MainEventsCleared => {
+                    // Sends any long press events which are due, shows any pending tooltip and
+                    // updates the widgets bound to parameters changed by the audio thread and the level meters
+                    let gesture_deadline = state.update_gestures();
+                    let tooltip_deadline = state.update_tooltips();
+                    let params_deadline = state.update_params();
+                    let meters_deadline = state.update_meters();
+
                     let mut needs_redraw = false;
 
                     if state.apply_animations() {
+                        *control_flow = ControlFlow::Poll;
                         state.insert_event(
                             Event::new(WindowEvent::Relayout)
                                 .target(Entity::null())
@@ -135,6 +142,16 @@ impl Application {
                         );
                         //state.insert_event(Event::new(WindowEvent::Redraw));
                         needs_redraw = true;
+                    } else if let Some(deadline) = gesture_deadline
+                        .into_iter()
+                        .chain(tooltip_deadline)
+                        .chain(params_deadline)
+                        .chain(meters_deadline)
+                        .min()
+                    {
+                        *control_flow = ControlFlow::WaitUntil(deadline);
+                    } else {
+                        *control_flow = ControlFlow::Wait;
                     }
 
                     if first_time {
@@ -171,6 +188,7 @@ impl Application {
                     window.context.make_current();
 
                     event_manager.draw(&mut state, &hierarchy, &mut window.canvas);
+                    state.update_accessibility();
 
                     window.context.swap_buffers();
                     window.context.make_not_current();
@@ -203,7 +221,13 @@ impl Application {
                         ////////////////////
                         // Focused Window //
                         ////////////////////
-                        winit::event::WindowEvent::Focused(_) => {
+                        winit::event::WindowEvent::Focused(focused) => {
+                            state.insert_event(
+                                Event::new(WindowEvent::WindowFocus(focused))
+                                    .target(Entity::root())
+                                    .propagate(Propagation::All),
+                            );
+
                             state.insert_event(
                                 Event::new(WindowEvent::Restyle)
                                     .target(Entity::root())
@@ -247,54 +271,11 @@ impl Application {
                                 if virtual_keycode == VirtualKeyCode::Tab
                                     && s == MouseButtonState::Pressed
                                 {
-                                    let next_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .next;
-                                    let prev_focus = state
-                                        .style
-                                        .focus_order
-                                        .get(state.focused)
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .prev;
-
                                     if state.modifiers.shift {
-                                        if prev_focus != Entity::null() {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused = prev_focus;
-                                            state.focused.set_focus(&mut state, true);
-                                        } else {
-                                            // TODO impliment reverse iterator for hierarchy
-                                            // state.focused = match state.focused.into_iter(&state.hierarchy).next() {
-                                            //     Some(val) => val,
-                                            //     None => Entity::root(),
-                                            // };
-                                        }
+                                        state.focus_prev();
                                     } else {
-                                        if next_focus != Entity::null() {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused = next_focus;
-                                            state.focused.set_focus(&mut state, true);
-                                        } else {
-                                            state.focused.set_focus(&mut state, false);
-                                            state.focused =
-                                                match state.focused.into_iter(&hierarchy).next() {
-                                                    Some(val) => val,
-                                                    None => Entity::root(),
-                                                };
-                                            state.focused.set_focus(&mut state, true);
-                                        }
+                                        state.focus_next();
                                     }
-
-                                    state.insert_event(
-                                        Event::new(WindowEvent::Restyle)
-                                            .target(Entity::root())
-                                            .origin(Entity::root()),
-                                    );
                                 }
                             }
 
@@ -508,6 +489,38 @@ impl Application {
                                         .target(state.hovered),
                                 );
                             }
+
+                            state.pointer_move(0, cursorx, cursory, None);
+                        }
+
+                        winit::event::WindowEvent::Touch(touch) => {
+                            let x = touch.location.x as f32;
+                            let y = touch.location.y as f32;
+                            let pressure = touch.force.map(|force| force.normalized() as f32);
+                            let pointer = PointerInfo::touch(touch.id, x, y, pressure);
+
+                            state.mouse.cursorx = x;
+                            state.mouse.cursory = y;
+
+                            apply_hover(&mut state);
+
+                            match touch.phase {
+                                winit::event::TouchPhase::Started => {
+                                    state.pointer_down(pointer);
+                                }
+
+                                winit::event::TouchPhase::Moved => {
+                                    state.pointer_move(pointer.id, x, y, pressure);
+                                }
+
+                                winit::event::TouchPhase::Ended => {
+                                    state.pointer_up(pointer.id, x, y);
+                                }
+
+                                winit::event::TouchPhase::Cancelled => {
+                                    state.pointer_cancel(pointer.id);
+                                }
+                            }
                         }
 
                         winit::event::WindowEvent::MouseInput {
@@ -587,6 +600,12 @@ impl Application {
 
                                         _ => {}
                                     }
+
+                                    state.pointer_down(PointerInfo::mouse(
+                                        b,
+                                        state.mouse.cursorx,
+                                        state.mouse.cursory,
+                                    ));
                                 }
 
                                 MouseButtonState::Released => {
@@ -627,6 +646,10 @@ impl Application {
 
                                         _ => {}
                                     }
+
+                                    if state.gestures.pressed_button(0) == Some(b) {
+                                        state.pointer_up(0, state.mouse.cursorx, state.mouse.cursory);
+                                    }
                                 }
                             }
                         }